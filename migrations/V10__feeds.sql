@@ -0,0 +1,4 @@
+-- feeds are tag actors that boost posts matching a boolean expression
+-- of tags rather than a single tag, see db::types::feed
+ALTER TABLE tags ADD COLUMN expression TEXT NULL;
+CREATE INDEX tags_feeds ON tags(tag_id) WHERE expression IS NOT NULL;
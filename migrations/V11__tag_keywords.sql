@@ -0,0 +1,21 @@
+-- tags and feeds with keywords also match posts whose text contains
+-- one of them, whether or not the post uses the hashtag
+CREATE TABLE tag_keywords (
+	keyword_id		BIGSERIAL PRIMARY KEY,
+	tag				BIGINT NOT NULL REFERENCES tags(tag_id) ON DELETE CASCADE,
+	kind			TEXT NOT NULL CHECK (kind IN ('phrase', 'word')),
+	keyword			TEXT NOT NULL,
+	case_sensitive	BOOLEAN NOT NULL DEFAULT false,
+	created			BIGINT NOT NULL,
+	UNIQUE (tag, kind, keyword)
+);
+
+-- the text of public posts we recently received, kept for a few days
+-- so admins can preview what keywords would match
+CREATE TABLE recent_posts (
+	activitypub_id		TEXT NOT NULL PRIMARY KEY,
+	actor				TEXT NOT NULL,
+	content				TEXT NOT NULL,
+	received			BIGINT NOT NULL
+);
+CREATE INDEX recent_posts_received ON recent_posts(received);
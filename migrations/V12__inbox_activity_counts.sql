@@ -0,0 +1,8 @@
+-- how many activities of each type the inbox has accepted, including
+-- the ones we have no use for and only acknowledge
+CREATE TABLE inbox_activity_counts (
+	kind			TEXT NOT NULL PRIMARY KEY,
+	supported		BOOLEAN NOT NULL,
+	received		BIGINT NOT NULL,
+	last_received	BIGINT NOT NULL
+);
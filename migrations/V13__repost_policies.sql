@@ -0,0 +1,7 @@
+-- how each tag treats its followers boosting or quoting posts that
+-- carry the tag, see db::types::repost
+ALTER TABLE tags
+ADD COLUMN announce_policy TEXT NOT NULL DEFAULT 'ignore'
+	CHECK (announce_policy IN ('ignore', 'boost_original')),
+ADD COLUMN quote_policy TEXT NOT NULL DEFAULT 'ignore'
+	CHECK (quote_policy IN ('ignore', 'boost_original', 'boost_quote'));
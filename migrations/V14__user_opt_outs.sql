@@ -0,0 +1,5 @@
+-- users whose profile asks not to be boosted, see db::types::consent.
+-- null when they have not opted out
+ALTER TABLE users
+ADD COLUMN opt_out TEXT NULL
+	CHECK (opt_out IN ('nobot', 'noboost', 'noindex', 'undiscoverable', 'unindexable'));
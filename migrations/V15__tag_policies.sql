@@ -0,0 +1,19 @@
+-- the rules a post has to pass to be boosted by a tag, as json.
+-- see db::types::tag_policy
+ALTER TABLE tags
+ADD COLUMN policy TEXT NOT NULL DEFAULT '{}';
+
+-- what policies need to know about authors
+ALTER TABLE users
+ADD COLUMN bot BOOLEAN NOT NULL DEFAULT false,
+ADD COLUMN created_at BIGINT NULL;
+
+-- posts a tag matched but did not boost because of its policy
+CREATE TABLE policy_skips (
+	activitypub_id	TEXT NOT NULL,
+	tag				BIGINT NOT NULL REFERENCES tags(tag_id) ON DELETE CASCADE,
+	reason			TEXT NOT NULL,
+	skipped_at		BIGINT NOT NULL,
+	PRIMARY KEY (activitypub_id, tag)
+);
+CREATE INDEX policy_skips_skipped_at ON policy_skips (skipped_at);
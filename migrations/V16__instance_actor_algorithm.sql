@@ -0,0 +1,4 @@
+-- the algorithm was an enum, which can't be read as text. it is
+-- either 'rsa-sha256' or 'hs2019'
+ALTER TABLE ap_instance_actor ALTER COLUMN algorithm TYPE TEXT USING algorithm::TEXT;
+DROP TYPE algorithm;
@@ -0,0 +1,5 @@
+-- activities are queued as the body we received along with the domain
+-- that signed it, and verified again when processed. activities queued
+-- before are stored already verified and have no origin
+ALTER TABLE inbox_queue ADD COLUMN origin TEXT NULL;
+ALTER TABLE inbox_dead_letters ADD COLUMN origin TEXT NULL;
@@ -0,0 +1,3 @@
+-- the follow mode users were last followed back in, so that switching
+-- modes can undo the follow-backs sent in the old one
+ALTER TABLE ap_instance_actor ADD COLUMN follow_mode TEXT NULL;
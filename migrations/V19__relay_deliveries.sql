@@ -0,0 +1,7 @@
+-- every relay subscriber inbox the instance actor's announce of a post
+-- was delivered to. relays announce each post once whichever tags it has
+CREATE TABLE relay_deliveries (
+	pid			uuid NOT NULL REFERENCES posts(pid) ON DELETE CASCADE,
+	inbox		TEXT NOT NULL,
+	PRIMARY KEY(pid, inbox)
+);
@@ -5,7 +5,7 @@ CREATE TABLE instances (
 	domain			TEXT NOT NULL UNIQUE,
 	blocked			BOOLEAN NOT NULL DEFAULT false,
 	reason			TEXT NULL,
-	allowlisted		BOOLEAN NOT NULL DEFAULT false,
+	allowlisted		BOOLEAN NOT NULL DEFAULT false
 );
 
 CREATE TABLE users (
@@ -60,7 +60,7 @@ CREATE TABLE tags (
 	-- plan to allow being set to something else by admins
 	bio TEXT NULL,
 	-- used to allow admins to ban tags for moderation purposes 
-	banned BOOLEAN NOT NOT DEFAULT false
+	banned BOOLEAN NOT NULL DEFAULT false
 );
 
 CREATE TABLE user_tags (
@@ -70,11 +70,11 @@ CREATE TABLE user_tags (
 	-- used to allow the user to undo following a tag
 	user_follow_activitypub_id		TEXT NOT NULL UNIQUE,
 	-- the user that is following
-	user		uuid NOT NULL REFERENCES users(uid) ON DELETE CASCADE,
+	follower		uuid NOT NULL REFERENCES users(uid) ON DELETE CASCADE,
 	-- the user that is being followed
 	tag			BIGINT NOT NULL REFERENCES tags(tag_id) ON DELETE CASCADE,
 	published		BIGINT NOT NULL,
-	PRIMARY KEY(follower, target_user)
+	PRIMARY KEY(follower, tag)
 );
 
 CREATE TABLE posts (
@@ -82,7 +82,7 @@ CREATE TABLE posts (
 	pid 		uuid NOT NULL PRIMARY KEY UNIQUE,
 	-- used to deduplicate and allow for users to delete posts
 	activitypub_id		TEXT NOT NULL UNIQUE,
-	domain		TEXT NOT NULL REFERENCES instances(i_id) ON DELETE CASCADE,
+	domain		TEXT NOT NULL REFERENCES instances(domain) ON DELETE CASCADE,
 	published	BIGINT NOT NULL,
 
 	fetched_at			BIGINT NULL,
@@ -0,0 +1,7 @@
+-- users stored before V14 were never checked for an opt out. they are
+-- refetched once on startup, users stored from now on are checked
+-- when they are created or updated
+ALTER TABLE users
+ADD COLUMN opt_out_checked BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users
+ALTER COLUMN opt_out_checked SET DEFAULT true;
@@ -0,0 +1,3 @@
+-- finds the language scoped tags of a tag, like rust.de for rust,
+-- without going through every tag
+CREATE INDEX tags_scoped ON tags (split_part(tag, '.', 1)) WHERE tag LIKE '%.%';
@@ -0,0 +1,5 @@
+-- a tag that is an alias of another will have its follows and boosts
+-- handled by the canonical tag. aliases always point directly at the
+-- canonical tag, chains are flattened when tags are merged
+ALTER TABLE tags ADD COLUMN alias_of BIGINT NULL REFERENCES tags(tag_id) ON DELETE SET NULL;
+CREATE INDEX tags_alias_of ON tags(alias_of);
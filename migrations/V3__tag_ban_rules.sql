@@ -0,0 +1,12 @@
+-- rule based bans that apply to every tag they match, including
+-- tags that have not been created yet
+CREATE TABLE tag_ban_rules (
+	rule_id		BIGSERIAL PRIMARY KEY,
+	kind		TEXT NOT NULL CHECK (kind IN ('exact', 'prefix', 'suffix', 'regex')),
+	-- always lowercase
+	pattern		TEXT NOT NULL,
+	-- shown to admins, not to the users that are refused
+	reason		TEXT NULL,
+	created		BIGINT NOT NULL,
+	UNIQUE (kind, pattern)
+);
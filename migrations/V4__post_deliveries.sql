@@ -0,0 +1,11 @@
+-- every inbox a tag's announce of a post was delivered to,
+-- used to send the undo to the same inboxes when the post is deleted
+CREATE TABLE post_deliveries (
+	pid			uuid NOT NULL REFERENCES posts(pid) ON DELETE CASCADE,
+	tag			BIGINT NOT NULL REFERENCES tags(tag_id) ON DELETE CASCADE,
+	inbox		TEXT NOT NULL,
+	PRIMARY KEY(pid, tag, inbox)
+);
+
+CREATE INDEX posts_actor ON posts(actor);
+CREATE INDEX users_public_key_id ON users(public_key_id);
@@ -0,0 +1,26 @@
+-- verified activities waiting to be processed by the inbox workers
+CREATE TABLE inbox_queue (
+	qid				uuid PRIMARY KEY,
+	-- the verified activity as json
+	activity		TEXT NOT NULL,
+	received		BIGINT NOT NULL,
+	attempts		INT NOT NULL DEFAULT 0,
+	-- the activity is not picked up again before this time
+	next_attempt	BIGINT NOT NULL,
+	-- set while a worker is processing the activity so that a
+	-- crashed worker does not hold onto it forever
+	locked_until	BIGINT,
+	last_error		TEXT
+);
+
+CREATE INDEX inbox_queue_next_attempt ON inbox_queue(next_attempt);
+
+-- activities that failed on every attempt, kept for inspection
+CREATE TABLE inbox_dead_letters (
+	qid				uuid PRIMARY KEY,
+	activity		TEXT NOT NULL,
+	received		BIGINT NOT NULL,
+	attempts		INT NOT NULL,
+	last_error		TEXT,
+	failed			BIGINT NOT NULL
+);
@@ -0,0 +1,11 @@
+-- ids of activities and objects that have already been queued so
+-- that retries and copies arriving through other shared inboxes
+-- are only processed once. pruned after the retention window
+CREATE TABLE processed_activities (
+	kind			TEXT NOT NULL CHECK (kind IN ('activity', 'object')),
+	activitypub_id	TEXT NOT NULL,
+	processed		BIGINT NOT NULL,
+	PRIMARY KEY(kind, activitypub_id)
+);
+
+CREATE INDEX processed_activities_processed ON processed_activities(processed);
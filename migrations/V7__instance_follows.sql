@@ -0,0 +1,8 @@
+-- users the instance actor follows back on behalf of every tag they
+-- follow. the follow is kept while the user follows at least one tag
+CREATE TABLE instance_follows (
+	-- used for the ending of the id of the follow coming from us
+	ifid			uuid NOT NULL UNIQUE,
+	follower		uuid PRIMARY KEY REFERENCES users(uid) ON DELETE CASCADE,
+	published		BIGINT NOT NULL
+);
@@ -0,0 +1,2 @@
+-- the earliest time the next rate limited fetch to the instance may be made
+ALTER TABLE instances ADD COLUMN next_fetch BIGINT NOT NULL DEFAULT 0;
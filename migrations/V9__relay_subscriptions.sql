@@ -0,0 +1,9 @@
+-- instances subscribed to us as a relay, by the actor that sent the
+-- follow. their inboxes receive tag announces and the public posts
+-- they deliver are matched against tags
+CREATE TABLE relay_subscriptions (
+	follower		uuid PRIMARY KEY REFERENCES users(uid) ON DELETE CASCADE,
+	-- used to allow the instance to unsubscribe
+	follow_activitypub_id		TEXT NOT NULL UNIQUE,
+	published		BIGINT NOT NULL
+);
@@ -1,7 +1,17 @@
 use config::ConfigError;
 use serde::Deserialize;
 
-use crate::db::pg_conn::PgConn;
+use crate::{
+    db::{
+        cache::Cache,
+        pg_conn::PgConn,
+        types::{consent::OptOut, tag::Tag, user::User},
+    },
+    types::hashtag::HashtagSource,
+};
+
+/// a day in milliseconds, the retention settings below are given in days
+pub const DAY: i64 = 24 * 60 * 60 * 1000;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct Config {
@@ -12,6 +22,86 @@ pub struct Config {
     pub contact_email: String,
     pub port: u16,
     pub outbox_pagnation_size: u64,
+    /// slurs and any other names that should never become tags,
+    /// on top of [`crate::db::types::tag_ban::RESERVED_TAGS`]
+    #[serde(default)]
+    pub reserved_tags: Vec<String>,
+    /// the domain this relay has moved to. tag actors point at
+    /// their counterpart there with `movedTo`
+    #[serde(default)]
+    pub moved_to_domain: Option<String>,
+    /// domains this relay used to run on. tag actors list their old
+    /// selves as `alsoKnownAs` so that moves from there are honored
+    #[serde(default)]
+    pub previous_domains: Vec<String>,
+    /// how a post matching several followed tags is boosted
+    #[serde(default)]
+    pub boost_policy: BoostPolicy,
+    /// tags listed first are preferred when boosting from a single tag,
+    /// unlisted tags come after in the order the post lists them
+    #[serde(default)]
+    pub tag_priority: Vec<String>,
+    /// which actor follows users back
+    #[serde(default)]
+    pub follow_mode: FollowMode,
+    /// when set, a user's public posts from this many days back are
+    /// fetched from their outbox and boosted when they first follow a tag
+    #[serde(default)]
+    pub backfill_days: Option<i64>,
+    /// the minimum milliseconds between backfill fetches to one instance
+    #[serde(default = "default_backfill_interval_ms")]
+    pub backfill_interval_ms: i64,
+    /// whether backfilled posts are boosted or only recorded
+    #[serde(default)]
+    pub backfill_mode: BackfillMode,
+    /// whether instances may subscribe to us as a relay by following
+    /// the public collection or the instance actor
+    #[serde(default)]
+    pub relay_subscriptions: bool,
+    /// the tags relayed to subscribed instances, all tags when empty
+    #[serde(default)]
+    pub relay_tags: Vec<String>,
+    /// present every tag as a group (FEP-1b12) so that lemmy, mbin and
+    /// friendica can follow it as a community
+    #[serde(default)]
+    pub group_actors: bool,
+    /// tags presented as groups even when `group_actors` is off
+    #[serde(default)]
+    pub group_tags: Vec<String>,
+    /// tags only matched by hashtags in the tag field of a post, not by
+    /// those found only in its content
+    #[serde(default)]
+    pub structured_tags: Vec<String>,
+    /// also match tags by bare `#word` text in the content of posts,
+    /// which is easily mistaken for a hashtag
+    #[serde(default)]
+    pub text_hashtags: bool,
+    /// tags matched by bare `#word` text even when `text_hashtags` is off
+    #[serde(default)]
+    pub text_hashtag_tags: Vec<String>,
+    /// also refuse users whose account is not indexable. mastodon accounts
+    /// are unindexable unless they opt in, so this refuses most of them
+    #[serde(default)]
+    pub require_indexable: bool,
+    /// how long the text of received posts is kept to preview keywords
+    /// against, nothing is kept when zero
+    #[serde(default = "default_keyword_preview_days")]
+    pub keyword_preview_days: i64,
+    /// how long posts skipped by a tag's policy are kept along with why
+    #[serde(default = "default_policy_skip_days")]
+    pub policy_skip_days: i64,
+    /// how many inbox activities are processed at once
+    #[serde(default = "default_inbox_workers")]
+    pub inbox_workers: usize,
+    /// failed inbox activities are dead lettered after this many attempts
+    #[serde(default = "default_inbox_max_attempts")]
+    pub inbox_max_attempts: i32,
+    /// how long activity ids are remembered to drop duplicate deliveries
+    #[serde(default = "default_dedupe_retention_days")]
+    pub dedupe_retention_days: i64,
+    /// the bearer token of the admin api, which is off when unset
+    #[serde(default)]
+    pub admin_token: Option<String>,
 
     pub pg_user: String,
     pub pg_password: String,
@@ -20,22 +110,144 @@ pub struct Config {
     pub pg_dbname: String,
 }
 
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BoostPolicy {
+    /// every matching tag announces the post
+    #[default]
+    EveryTag,
+    /// only the highest priority matching tag announces the post, to
+    /// the followers of every matching tag, listing the others as tags
+    BestTag,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FollowMode {
+    /// every followed tag follows the user back
+    #[default]
+    PerTag,
+    /// the instance actor follows the user back once for all of
+    /// their tags, for as long as they follow at least one
+    Instance,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackfillMode {
+    /// backfilled posts are boosted as if they had just been delivered
+    #[default]
+    Boost,
+    /// backfilled posts are stored against the tag without being
+    /// announced, edits to them later don't announce them either
+    Record,
+}
+
+impl FollowMode {
+    /// the name used in the config and recorded in the db
+    pub fn name(&self) -> &'static str {
+        match self {
+            FollowMode::PerTag => "per_tag",
+            FollowMode::Instance => "instance",
+        }
+    }
+}
+
+fn default_inbox_workers() -> usize {
+    4
+}
+
+fn default_inbox_max_attempts() -> i32 {
+    8
+}
+
+fn default_backfill_interval_ms() -> i64 {
+    2000
+}
+
+fn default_dedupe_retention_days() -> i64 {
+    7
+}
+
+fn default_keyword_preview_days() -> i64 {
+    3
+}
+
+fn default_policy_skip_days() -> i64 {
+    7
+}
+
+/// if the tag is in a list of tag names from the config,
+/// which may be written as hashtags
+fn listed(names: &[String], tag: &Tag) -> bool {
+    names
+        .iter()
+        .any(|name| name.trim_start_matches('#').eq_ignore_ascii_case(&tag.name))
+}
+
 impl Config {
+    /// whether the tag's announces go to relay subscribers
+    /// and their posts are matched against it
+    pub fn relays(&self, tag: &Tag) -> bool {
+        self.relay_subscriptions && (self.relay_tags.is_empty() || listed(&self.relay_tags, tag))
+    }
+    /// whether the tag is a group that announces the activities of posts
+    /// and accepts posts addressed to it
+    pub fn is_group(&self, tag: &Tag) -> bool {
+        self.group_actors || listed(&self.group_tags, tag)
+    }
+    /// whether hashtags found only in the content of a post are
+    /// ignored when matching it against the tag
+    pub fn structured_only(&self, tag: &Tag) -> bool {
+        listed(&self.structured_tags, tag)
+    }
+    /// whether a hashtag found where `source` says counts toward matching
+    /// a post against the tag
+    pub fn hashtag_counts(&self, source: HashtagSource, tag: &Tag) -> bool {
+        match source {
+            HashtagSource::Tag => true,
+            HashtagSource::Link => !self.structured_only(tag),
+            HashtagSource::Text => {
+                !self.structured_only(tag)
+                    && (self.text_hashtags || listed(&self.text_hashtag_tags, tag))
+            }
+        }
+    }
+    /// how the user opted out of being boosted, if it is one we honor
+    pub fn opted_out(&self, user: &User) -> Option<OptOut> {
+        user.opt_out
+            .filter(|x| *x != OptOut::Unindexable || self.require_indexable)
+    }
     pub fn create_conn(&self) -> PgConn {
         let db_config = deadpool_postgres::Config {
             user: Some(self.pg_user.clone()),
             password: Some(self.pg_password.clone()),
             host: Some(self.pg_host.clone()),
+            port: Some(self.pg_port),
             dbname: Some(self.pg_dbname.clone()),
 
             ..Default::default()
         };
 
         let pool = db_config.create_pool(None, tokio_postgres::NoTls).unwrap();
-        PgConn { db: pool }
+        PgConn {
+            db: pool,
+            relay_subscribers: Cache::default(),
+            keywords: Cache::default(),
+        }
     }
 }
 
+/// the example config shipped with the relay, for tests
+#[cfg(test)]
+pub fn test_config() -> Config {
+    config::Config::builder()
+        .add_source(config::File::with_name("tags_config"))
+        .build()
+        .and_then(|x| x.try_deserialize())
+        .expect("the example config should load")
+}
+
 pub fn get_config() -> Result<Config, ConfigError> {
     let settings = config::Config::builder()
         // Add in `./Settings.toml`
@@ -6,11 +6,12 @@ use super::{
     digest::{sha256_hash, sha512_hash},
     key::{Algorithms, Key, KeyErr, PrivateKey, PublicKey},
 };
+use base64::Engine;
 use ed25519_dalek::VerifyingKey;
 use rand::rngs::OsRng;
 use rsa::{
     pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey},
-    signature::{Keypair, SignerMut},
+    signature::{Keypair, SignatureEncoding, SignerMut},
 };
 use sha2::Sha256;
 
@@ -59,16 +60,23 @@ impl Key for UniversalPrivate {
 }
 
 impl PrivateKey for UniversalPrivate {
+    /// signatures are base64 encoded as expected by the `Signature:` header
     fn sign(&mut self, content: &[u8]) -> String {
-        match self {
-            UniversalPrivate::RSA(rsa_private_key) => rsa_private_key.sign(content).to_string(),
-            UniversalPrivate::Ed35519(signing_key) => signing_key.sign(content).to_string(),
-        }
+        let signature = match self {
+            UniversalPrivate::RSA(rsa_private_key) => rsa_private_key.sign(content).to_vec(),
+            UniversalPrivate::Ed35519(signing_key) => signing_key.sign(content).to_vec(),
+        };
+        base64::prelude::BASE64_STANDARD.encode(signature)
     }
 
     fn generate(algorithm: Algorithms) -> Self {
         match algorithm {
-            Algorithms::RsaSha256 => todo!(),
+            Algorithms::RsaSha256 => {
+                let mut csprng = OsRng;
+                let private_key =
+                    rsa::RsaPrivateKey::new(&mut csprng, 2048).expect("failed to generate rsa key");
+                Self::RSA(rsa::pkcs1v15::SigningKey::new(private_key))
+            }
             Algorithms::Hs2019 => {
                 let mut csprng = OsRng;
                 let signing_key: ed25519_dalek::SigningKey =
@@ -0,0 +1,89 @@
+use std::{
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+/// a value loaded from the db on first use and kept until it is
+/// invalidated by a change to what it was loaded from
+pub struct Cache<T> {
+    inner: Arc<RwLock<Cached<T>>>,
+}
+
+struct Cached<T> {
+    value: Option<Arc<T>>,
+    generation: u64,
+}
+
+impl<T> Cache<T> {
+    /// the cached value, or the generation to [`Cache::set`] the value
+    /// loaded in its place against
+    pub fn get(&self) -> Result<Arc<T>, u64> {
+        let cached = self.inner.read().expect("cache lock poisoned");
+        match &cached.value {
+            Some(value) => Ok(value.clone()),
+            None => Err(cached.generation),
+        }
+    }
+    /// keeps the value unless the cache was invalidated since `generation`,
+    /// in which case it may already be stale and is only returned
+    pub fn set(&self, generation: u64, value: T) -> Arc<T> {
+        let value = Arc::new(value);
+        let mut cached = self.inner.write().expect("cache lock poisoned");
+        if cached.generation == generation {
+            cached.value = Some(value.clone());
+        }
+        value
+    }
+    pub fn invalidate(&self) {
+        let mut cached = self.inner.write().expect("cache lock poisoned");
+        cached.value = None;
+        cached.generation += 1;
+    }
+}
+
+impl<T> Default for Cache<T> {
+    fn default() -> Self {
+        Cache {
+            inner: Arc::new(RwLock::new(Cached {
+                value: None,
+                generation: 0,
+            })),
+        }
+    }
+}
+
+impl<T> Clone for Cache<T> {
+    fn clone(&self) -> Self {
+        Cache {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Cache<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cache").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cache;
+
+    #[test]
+    fn stale_loads_are_not_kept() -> Result<(), String> {
+        let cache: Cache<i32> = Cache::default();
+        let generation = cache.get().err().ok_or("empty cache returned a value")?;
+        cache.invalidate();
+        cache.set(generation, 1);
+        if cache.get().is_ok() {
+            return Err("kept a value loaded before invalidating".to_string());
+        }
+        let generation = cache.get().err().ok_or("empty cache returned a value")?;
+        cache.set(generation, 2);
+        match cache.get() {
+            Ok(value) if *value == 2 => Ok(()),
+            _ => Err("did not keep a fresh value".to_string()),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+pub mod cache;
 pub mod pg_conn;
-mod pg_sesh;
+pub mod pg_sesh;
 pub mod types;
@@ -1,15 +1,46 @@
 use crate::{
-    cryptography::key::{Algorithms, PrivateKey},
+    cryptography::{
+        key::{Algorithms, Key, PrivateKey},
+        universal_keys::UniversalPublic,
+    },
     db::pg_sesh::Sesh,
-    protocol::{ap_protocol::fetch::webfinger_actor, errors::FetchErr},
+    protocol::{
+        ap_protocol::fetch::{authorized_fetch, webfinger_actor},
+        errors::FetchErr,
+        errors::VerifyRequestErr,
+    },
+    types::actors::Actor,
 };
 use deadpool_postgres::Pool;
+use std::{collections::HashSet, sync::Arc};
+use url::Url;
+use uuid::Uuid;
 
-use super::types::{instance_actor::InstanceActor, tag::Tag, user::User};
+use super::cache::Cache;
+use super::types::{
+    feed::{FeedErr, FeedExpr},
+    inbox_queue::{ActivityCount, QueueDepth, QueuedActivity},
+    instance::Instance,
+    instance_actor::InstanceActor,
+    keyword::{
+        validate_keyword, Keyword, KeywordErr, KeywordKind, KeywordPreview, Keywords, TagKeyword,
+    },
+    post::Post,
+    relay_export::{RelayExport, TagBanRuleExport, TagExport},
+    repost::{AnnouncePolicy, QuotePolicy},
+    tag::{Tag, TagAliasErr},
+    tag_ban::{validate_pattern, TagBanErr, TagBanKind, TagBanRule, TagBans},
+    tag_policy::{SkippedPost, TagPolicy},
+    user::User,
+};
 
 #[derive(Clone, Debug)]
 pub struct PgConn {
     pub db: Pool,
+    /// the domains of relay subscribers, checked for every post
+    pub relay_subscribers: Cache<HashSet<String>>,
+    /// every tag's keywords compiled, checked against the text of every post
+    pub keywords: Cache<Keywords<TagKeyword>>,
 }
 
 impl PgConn {
@@ -37,6 +68,8 @@ impl PgConn {
         let sesh = Sesh::Client(client);
         sesh.fetch_instance_actor().await
     }
+    /// tags are stored lowercase, the capitalization used when the
+    /// tag is first initiated is kept as its display name
     pub async fn get_or_init_tag(&self, tag: &str, banned: bool) -> Tag {
         let mut client = self.db.get().await.expect("failed to get client");
         let transaction = client
@@ -44,13 +77,258 @@ impl PgConn {
             .await
             .expect("failed to begin transaction");
         let sesh = Sesh::Transaction(transaction);
-        if let Some(tag) = sesh.get_tag(tag).await {
+        let name = tag.to_lowercase();
+        if let Some(tag) = sesh.get_tag(&name).await {
             return tag;
         }
-        let tag = sesh.create_tag(tag, banned).await;
+        let tag = sesh.create_tag(&name, Some(tag), banned).await;
         sesh.commit().await;
         tag
     }
+    pub async fn get_tag(&self, tag: &str) -> Option<Tag> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_tag(&tag.to_lowercase()).await
+    }
+    pub async fn get_scoped_tags(&self, tag: &str) -> Vec<Tag> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_scoped_tags(&tag.to_lowercase()).await
+    }
+    pub async fn get_tag_by_id(&self, tag_id: i64) -> Option<Tag> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_tag_by_id(tag_id).await
+    }
+    /// gets the tag that follows and boosts of this tag are handled by,
+    /// which is the tag itself if it is not an alias
+    pub async fn get_canonical_tag(&self, tag: Tag) -> Tag {
+        let Some(alias_of) = tag.alias_of else {
+            return tag;
+        };
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_tag_by_id(alias_of).await.unwrap_or(tag)
+    }
+    pub async fn get_tag_aliases(&self, tag: &Tag) -> Vec<Tag> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_tag_aliases(tag).await
+    }
+    /// declares `alias` to be an alias of `canonical`. any aliases of
+    /// `alias` are moved to `canonical` and every existing follow of them
+    /// is migrated to `canonical`
+    ///
+    /// returns the canonical tag along with the follows that were dropped
+    /// because the user already followed it, as the user, the tag they
+    /// followed and its ufid, so that their follow-backs can be undone
+    pub async fn merge_tags(
+        &self,
+        alias: &str,
+        canonical: &str,
+    ) -> Result<(Tag, Vec<(User, Tag, Uuid)>), TagAliasErr> {
+        let alias = self.get_or_init_tag(alias, false).await;
+        let canonical = self.get_or_init_tag(canonical, false).await;
+        let canonical = self.get_canonical_tag(canonical).await;
+        if alias.id == canonical.id {
+            return Err(TagAliasErr::SameTag);
+        }
+
+        let mut client = self.db.get().await.expect("failed to get client");
+        let transaction = client
+            .transaction()
+            .await
+            .expect("failed to begin transaction");
+        let sesh = Sesh::Transaction(transaction);
+        let mut merged = sesh.get_tag_aliases(&alias).await;
+        merged.push(alias);
+        let mut dropped = Vec::new();
+        for tag in &merged {
+            for (follower, ufid) in sesh.merge_tag_follows(tag, &canonical).await {
+                if let Some(user) = sesh.get_user_by_uid(follower).await {
+                    dropped.push((user, tag.clone(), ufid));
+                }
+            }
+        }
+        let alias = merged.pop().expect("merged always contains the alias");
+        sesh.set_tag_alias(&alias, &canonical).await;
+        sesh.commit().await;
+        Ok((canonical, dropped))
+    }
+    /// the tag will go back to being handled on its own. follows that
+    /// were migrated when the tag was merged stay with the canonical tag
+    pub async fn remove_tag_alias(&self, alias: &str) {
+        let Some(alias) = self.get_tag(alias).await else {
+            return;
+        };
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.remove_tag_alias(&alias).await;
+    }
+}
+
+// feeds
+impl PgConn {
+    pub async fn get_feeds(&self) -> Vec<Tag> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_feeds().await
+    }
+    /// creates a feed or changes the expression of an existing one. a
+    /// plain tag can only become a feed while nobody follows it
+    pub async fn create_feed(&self, name: &str, expression: &str) -> Result<Tag, FeedErr> {
+        let expression = FeedExpr::parse(expression)?;
+        if name.is_empty() || !name.chars().all(char::is_alphanumeric) {
+            return Err(FeedErr::InvalidName);
+        }
+        let mut client = self.db.get().await.expect("failed to get client");
+        let transaction = client
+            .transaction()
+            .await
+            .expect("failed to begin transaction");
+        let sesh = Sesh::Transaction(transaction);
+        let mut feed = match sesh.get_tag(&name.to_lowercase()).await {
+            Some(tag) => {
+                if tag.alias_of.is_some()
+                    || (tag.expression.is_none() && sesh.count_tag_followers(&tag).await > 0)
+                {
+                    return Err(FeedErr::NameTaken);
+                }
+                tag
+            }
+            None => {
+                sesh.create_tag(&name.to_lowercase(), Some(name), false)
+                    .await
+            }
+        };
+        feed.expression = Some(expression.to_string());
+        let feed = sesh.update_tag(&feed).await;
+        sesh.commit().await;
+        Ok(feed)
+    }
+    /// the feed goes back to being a plain tag, keeping its followers
+    pub async fn remove_feed(&self, name: &str) {
+        let Some(mut feed) = self.get_tag(name).await else {
+            return;
+        };
+        feed.expression = None;
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.update_tag(&feed).await;
+    }
+}
+
+// tag bans
+impl PgConn {
+    /// loads every ban rule along with the reserved names
+    pub async fn get_tag_bans(&self, reserved: &[String]) -> TagBans {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        TagBans::new(
+            sesh.get_tag_ban_rules().await,
+            reserved,
+            sesh.get_banned_tag_names().await,
+        )
+    }
+    /// the tag unless it is banned. a tag scoped to a language is
+    /// only created if the tag it narrows down already exists
+    pub async fn get_or_init_unbanned_tag(&self, name: &str, bans: &TagBans) -> Option<Tag> {
+        let (base, language) = Tag::split_scope(name);
+        if bans.check(name).is_some() || bans.check(base).is_some() {
+            return None;
+        }
+        if language.is_some() {
+            let base = self.get_tag(base).await?;
+            if bans.is_banned(&base) {
+                return None;
+            }
+        }
+        let tag = self.get_or_init_tag(name, false).await;
+        (!bans.is_banned(&tag)).then_some(tag)
+    }
+    pub async fn get_tag_ban_rules(&self) -> Vec<TagBanRule> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_tag_ban_rules().await
+    }
+    /// adding a rule that already exists updates its reason
+    pub async fn add_tag_ban_rule(
+        &self,
+        kind: TagBanKind,
+        pattern: &str,
+        reason: Option<String>,
+    ) -> Result<TagBanRule, TagBanErr> {
+        let pattern = validate_pattern(kind, pattern)?;
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        Ok(sesh.create_tag_ban_rule(kind, &pattern, reason).await)
+    }
+    pub async fn remove_tag_ban_rule(&self, rule_id: i64) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.delete_tag_ban_rule(rule_id).await
+    }
+    pub async fn set_tag_banned(&self, tag: &str, banned: bool) {
+        let tag = self.get_or_init_tag(tag, banned).await;
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.set_tag_banned(tag.id, banned)
+            .await
+            .expect("failed to set tag banned");
+    }
+}
+
+// repost policies
+impl PgConn {
+    /// sets how the tag treats its followers boosting and quoting posts
+    /// that carry it, none if there is no such tag
+    pub async fn set_tag_repost_policies(
+        &self,
+        tag: &str,
+        announce_policy: AnnouncePolicy,
+        quote_policy: QuotePolicy,
+    ) -> Option<Tag> {
+        let mut tag = self.get_tag(tag).await?;
+        tag.announce_policy = announce_policy;
+        tag.quote_policy = quote_policy;
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        Some(sesh.update_tag(&tag).await)
+    }
+}
+
+// tag policies
+impl PgConn {
+    /// replaces the rules posts have to pass to be boosted
+    /// by the tag, none if there is no such tag
+    pub async fn set_tag_policy(&self, tag: &str, policy: TagPolicy) -> Option<Tag> {
+        let mut tag = self.get_tag(tag).await?;
+        tag.policy = policy;
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        Some(sesh.update_tag(&tag).await)
+    }
+    pub async fn record_policy_skip(&self, activitypub_id: &Url, tag: &Tag, reason: &str) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.record_policy_skip(activitypub_id, tag, reason).await
+    }
+    /// the `limit` posts the tag most recently skipped
+    pub async fn get_policy_skips(&self, tag: &Tag, limit: i64) -> Vec<SkippedPost> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_policy_skips(tag, limit).await
+    }
+    /// forgets skips from more than `retention` milliseconds ago
+    pub async fn prune_policy_skips(&self, retention: i64) -> usize {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let before = chrono::Utc::now().timestamp_millis() - retention;
+        sesh.prune_policy_skips(before).await
+    }
+}
+
+impl PgConn {
     /// backfills users if they are not already present in the db
     pub async fn get_or_init_user<T: PrivateKey>(
         &self,
@@ -70,10 +348,708 @@ impl PgConn {
         }
         // ---------------- backfill ------------------
         let actor = webfinger_actor(instance_key_id, private_key, username, domain).await?;
+        if actor.id.host_str().is_none() {
+            return Err(FetchErr::InvalidUrl(actor.id.to_string()));
+        }
 
+        if sesh.get_instance(domain).await.is_none() {
+            sesh.create_instance(domain, false, None, false).await;
+        }
+        let user = sesh.create_user(actor, false, None).await;
+
+        sesh.commit().await;
+        Ok(user)
+    }
+    pub async fn get_user_by_id(&self, activitypub_id: &Url) -> Option<User> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_user_by_activitypub_id(activitypub_id).await
+    }
+    /// backfills users by their activitypub id if they are not already present in the db
+    pub async fn get_or_init_user_by_id<T: PrivateKey>(
+        &self,
+        activitypub_id: &Url,
+        private_key: &mut T,
+        instance_key_id: &str,
+    ) -> Result<User, FetchErr> {
+        let mut client = self.db.get().await.expect("failed to get client");
+        let transaction = client
+            .transaction()
+            .await
+            .expect("failed to begin transaction");
+        let sesh = Sesh::Transaction(transaction);
+        if let Some(user) = sesh.get_user_by_activitypub_id(activitypub_id).await {
+            return Ok(user);
+        }
+        // ---------------- backfill ------------------
+        let actor: Actor =
+            authorized_fetch(activitypub_id.clone(), instance_key_id, private_key).await?;
+        let Some(domain) = actor.id.host_str() else {
+            return Err(FetchErr::InvalidUrl(actor.id.to_string()));
+        };
+        if actor.id.ne(activitypub_id) {
+            return Err(FetchErr::VerifyErr(VerifyRequestErr::NoAuthority));
+        }
+
+        if sesh.get_instance(domain).await.is_none() {
+            sesh.create_instance(domain, false, None, false).await;
+        }
         let user = sesh.create_user(actor, false, None).await;
 
         sesh.commit().await;
         Ok(user)
     }
+    /// the stored public key of the user the key id belongs to
+    pub async fn get_user_public_key(&self, public_key_id: &str) -> Option<UniversalPublic> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let user = sesh.get_user_by_key_id(public_key_id).await?;
+        UniversalPublic::from_pem(&user.public_key_pem).ok()
+    }
+    /// stores the actor as a user or refreshes the existing one,
+    /// none if the actor's id has no host
+    pub async fn upsert_user(&self, actor: Actor) -> Option<User> {
+        let domain = actor.id.host_str()?.to_string();
+        let mut client = self.db.get().await.expect("failed to get client");
+        let transaction = client
+            .transaction()
+            .await
+            .expect("failed to begin transaction");
+        let sesh = Sesh::Transaction(transaction);
+        let user = match sesh.get_user_by_activitypub_id(&actor.id).await {
+            Some(mut user) => {
+                user.update_from_actor(&actor);
+                sesh.update_user(user).await
+            }
+            None => {
+                if sesh.get_instance(&domain).await.is_none() {
+                    sesh.create_instance(&domain, false, None, false).await;
+                }
+                sesh.create_user(actor, false, None).await
+            }
+        };
+        sesh.commit().await;
+        Some(user)
+    }
+    /// refreshes a known user from an updated copy of their actor.
+    /// returns none if the user is not known
+    pub async fn update_user_from_actor(&self, actor: &Actor) -> Option<User> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let mut user = sesh.get_user_by_activitypub_id(&actor.id).await?;
+        user.update_from_actor(actor);
+        Some(sesh.update_user(user).await)
+    }
+    /// users whose profile has not been checked for an opt out since
+    /// they were stored
+    pub async fn get_unchecked_opt_outs(&self) -> Vec<User> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_unchecked_opt_outs().await
+    }
+    /// also deletes everything belonging to the user
+    pub async fn delete_user(&self, user: User) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.delete_user(user).await;
+        // their relay subscription goes with them
+        self.relay_subscribers.invalidate();
+    }
+    pub async fn get_instance(&self, domain: &str) -> Option<Instance> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_instance(domain).await
+    }
+    /// milliseconds to wait before fetching from the instance so that
+    /// fetches to it are at least `interval` apart
+    pub async fn wait_for_fetch_slot(&self, domain: &str, interval: i64) -> i64 {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let now = chrono::Utc::now().timestamp_millis();
+        match sesh.reserve_fetch_slot(domain, now, interval).await {
+            Some(fetch_at) => (fetch_at - now).max(0),
+            None => 0,
+        }
+    }
+}
+
+// following
+impl PgConn {
+    /// follows of an alias are stored against the canonical tag.
+    /// returns the tag that was followed and the ufid of the follow
+    pub async fn follow_tag(&self, user: &User, tag: Tag, activitypub_id: Url) -> (Tag, Uuid) {
+        let tag = self.get_canonical_tag(tag).await;
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let ufid = sesh.create_following(user, &tag, activitypub_id).await;
+        (tag, ufid)
+    }
+    /// moves every follow of `from` over to `to`. returns each tag along
+    /// with the old ufid and the new one, which is none if `to` was
+    /// already following the tag
+    ///
+    /// `to` never sent these follows, so each is given an id on `domain`
+    /// which [`PgConn::unfollow_issued`] can undo
+    pub async fn move_follows(
+        &self,
+        from: &User,
+        to: &User,
+        domain: &str,
+    ) -> Vec<(Tag, Uuid, Option<Uuid>)> {
+        let mut client = self.db.get().await.expect("failed to get client");
+        let transaction = client
+            .transaction()
+            .await
+            .expect("failed to begin transaction");
+        let sesh = Sesh::Transaction(transaction);
+        let mut moved = Vec::new();
+        for (tag, ufid) in sesh.get_user_following(from).await {
+            let new_ufid = Uuid::now_v7();
+            let activitypub_id = Url::parse(&format!("https://{domain}/follows/{new_ufid}"))
+                .expect("generated invalid follow id");
+            let new_ufid = sesh
+                .transfer_following(ufid, to, new_ufid, &activitypub_id)
+                .await;
+            if new_ufid.is_none() {
+                sesh.delete_following(ufid).await;
+            }
+            moved.push((tag, ufid, new_ufid));
+        }
+        sesh.commit().await;
+        moved
+    }
+    /// removes the user's follow of the tag, as long as it is one moved
+    /// over from another account with an id issued by us on `domain`
+    pub async fn unfollow_issued(&self, user: &User, tag: &Tag, domain: &str) -> Option<Uuid> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.undo_issued_following(user.id, tag.id, &format!("https://{domain}/"))
+            .await
+    }
+    /// removes the follow with the given activitypub id, returning who
+    /// followed which tag along with the ufid of the follow
+    pub async fn unfollow_tag(&self, activitypub_id: &Url) -> Option<(User, Tag, Uuid)> {
+        let mut client = self.db.get().await.expect("failed to get client");
+        let transaction = client
+            .transaction()
+            .await
+            .expect("failed to begin transaction");
+        let sesh = Sesh::Transaction(transaction);
+        let (follower, tag, ufid) = sesh.undo_following(activitypub_id).await?;
+        let user = sesh.get_user_by_uid(follower).await?;
+        let tag = sesh.get_tag_by_id(tag).await?;
+        sesh.commit().await;
+        Some((user, tag, ufid))
+    }
+    /// the activitypub ids of every follow of the user
+    pub async fn get_user_follow_ids(&self, user: &User) -> Vec<Url> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_user_follow_ids(user).await
+    }
+    pub async fn get_followed_tags(&self, user: &User) -> Vec<Tag> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_followed_tags(user).await
+    }
+    /// whether the user follows any tag at all
+    pub async fn follows_any_tag(&self, user: &User) -> bool {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.count_user_following(user).await > 0
+    }
+    /// returns the ifid if the instance actor did not already follow the user
+    pub async fn create_instance_follow(&self, user: &User) -> Option<Uuid> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.create_instance_follow(user).await
+    }
+    /// returns the ifid if the instance actor was following the user
+    pub async fn delete_instance_follow(&self, user: &User) -> Option<Uuid> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.delete_instance_follow(user).await
+    }
+    /// removes every instance follow, returning the user and ifid of each
+    pub async fn delete_instance_follows(&self) -> Vec<(User, Uuid)> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let mut follows = Vec::new();
+        for (follower, ifid) in sesh.delete_instance_follows().await {
+            if let Some(user) = sesh.get_user_by_uid(follower).await {
+                follows.push((user, ifid));
+            }
+        }
+        follows
+    }
+    /// every follow along with its ufid
+    pub async fn get_all_following(&self) -> Vec<(User, Tag, Uuid)> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let mut follows = Vec::new();
+        for (follower, tag, ufid) in sesh.get_all_following().await {
+            let user = sesh.get_user_by_uid(follower).await;
+            let tag = sesh.get_tag_by_id(tag).await;
+            if let (Some(user), Some(tag)) = (user, tag) {
+                follows.push((user, tag, ufid));
+            }
+        }
+        follows
+    }
+    /// the follow mode recorded by [`PgConn::set_follow_mode`]
+    pub async fn get_follow_mode(&self) -> Option<String> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_follow_mode().await
+    }
+    /// records the follow mode users are followed back in. the instance
+    /// actor must have been initialized
+    pub async fn set_follow_mode(&self, follow_mode: &str) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.set_follow_mode(follow_mode).await
+    }
+    /// whether the follow activity has already been handled
+    pub async fn is_follow_recorded(&self, activitypub_id: &Url) -> bool {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_following_by_activitypub_id(activitypub_id)
+            .await
+            .is_some()
+    }
+    pub async fn is_following(&self, user: &User, tag: &Tag) -> bool {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_following(user, tag).await.is_some()
+    }
+    pub async fn count_tag_followers(&self, tag: &Tag) -> i64 {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.count_tag_followers(tag).await
+    }
+    pub async fn tag_unique_inboxes(&self, tag: &Tag) -> Vec<Url> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.tag_unique_inboxes(tag).await
+    }
+}
+
+// posts
+impl PgConn {
+    /// stores a post along with the tags it is being boosted by.
+    /// returns none if the post has already been stored
+    pub async fn create_post(
+        &self,
+        activitypub_id: &Url,
+        published: i64,
+        actor: &User,
+        tags: &[Tag],
+    ) -> Option<Uuid> {
+        let mut client = self.db.get().await.expect("failed to get client");
+        let transaction = client
+            .transaction()
+            .await
+            .expect("failed to begin transaction");
+        let sesh = Sesh::Transaction(transaction);
+        let pid = sesh.create_post(activitypub_id, published, actor).await?;
+        for tag in tags {
+            sesh.add_post_tag(pid, tag).await;
+        }
+        sesh.commit().await;
+        Some(pid)
+    }
+    pub async fn get_post(&self, activitypub_id: &Url) -> Option<Post> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_post(activitypub_id).await
+    }
+    pub async fn get_user_posts(&self, user: &User) -> Vec<Post> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_user_posts(user).await
+    }
+    pub async fn delete_post(&self, post: Post) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.delete_post(post).await
+    }
+    pub async fn add_post_deliveries(&self, pid: Uuid, tag: &Tag, inboxes: &[Url]) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        for inbox in inboxes {
+            sesh.add_post_delivery(pid, tag, inbox).await;
+        }
+    }
+    pub async fn get_post_deliveries(&self, pid: Uuid) -> Vec<(Tag, Vec<Url>)> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_post_deliveries(pid).await
+    }
+    pub async fn get_post_tags(&self, pid: Uuid) -> Vec<Tag> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_post_tags(pid).await
+    }
+    pub async fn add_post_tag(&self, pid: Uuid, tag: &Tag) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.add_post_tag(pid, tag).await
+    }
+    /// forgets where the tag's announce of the post was delivered
+    pub async fn remove_post_deliveries(&self, pid: Uuid, tag: &Tag) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.remove_post_deliveries(pid, tag).await
+    }
+    pub async fn count_tag_announces(&self, tag: &Tag) -> i64 {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.count_tag_announces(tag).await
+    }
+    /// the total number of posts the tag announced and one page of them
+    pub async fn get_tag_announces(
+        &self,
+        tag: &Tag,
+        page_size: i64,
+        page: i64,
+    ) -> (i64, Vec<Post>) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let total = sesh.count_tag_announces(tag).await;
+        let posts = sesh
+            .get_tag_announces(tag, page_size, page_size * page)
+            .await;
+        (total, posts)
+    }
+    /// removes the tag from the post along with the record of where
+    /// the tag's announce of it was delivered
+    pub async fn remove_post_tag(&self, pid: Uuid, tag: &Tag) {
+        let mut client = self.db.get().await.expect("failed to get client");
+        let transaction = client
+            .transaction()
+            .await
+            .expect("failed to begin transaction");
+        let sesh = Sesh::Transaction(transaction);
+        sesh.remove_post_deliveries(pid, tag).await;
+        sesh.remove_post_tag(pid, tag).await;
+        sesh.commit().await;
+    }
+}
+
+// relay moves
+impl PgConn {
+    pub async fn get_tags(&self) -> Vec<Tag> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_tags().await
+    }
+    pub async fn export_relay(&self, domain: &str) -> RelayExport {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let tags = sesh.get_tags().await;
+        let mut exported = Vec::with_capacity(tags.len());
+        for tag in &tags {
+            let alias_of = tag
+                .alias_of
+                .and_then(|id| tags.iter().find(|x| x.id == id))
+                .map(|canonical| canonical.name.clone());
+            let followers = sesh
+                .tag_followers(tag)
+                .await
+                .into_iter()
+                .map(|user| user.activitypub_id)
+                .collect();
+            exported.push(TagExport::new(tag, alias_of, followers));
+        }
+        let tag_ban_rules = sesh
+            .get_tag_ban_rules()
+            .await
+            .into_iter()
+            .map(TagBanRuleExport::from)
+            .collect();
+        RelayExport {
+            domain: domain.to_string(),
+            tags: exported,
+            tag_ban_rules,
+        }
+    }
+    /// recreates the tags, aliases and ban rules of an exported relay.
+    /// tags that already exist take on the exported settings
+    pub async fn import_relay(&self, export: RelayExport) {
+        let mut client = self.db.get().await.expect("failed to get client");
+        let transaction = client
+            .transaction()
+            .await
+            .expect("failed to begin transaction");
+        let sesh = Sesh::Transaction(transaction);
+        for exported in &export.tags {
+            let mut tag = match sesh.get_tag(&exported.name).await {
+                Some(tag) => tag,
+                None => {
+                    sesh.create_tag(
+                        &exported.name,
+                        exported.display_name.as_deref(),
+                        exported.banned,
+                    )
+                    .await
+                }
+            };
+            exported.apply(&mut tag);
+            sesh.update_tag(&tag).await;
+        }
+        for exported in &export.tags {
+            let Some(canonical) = &exported.alias_of else {
+                continue;
+            };
+            let (Some(alias), Some(canonical)) = (
+                sesh.get_tag(&exported.name).await,
+                sesh.get_tag(canonical).await,
+            ) else {
+                continue;
+            };
+            sesh.set_tag_alias(&alias, &canonical).await;
+        }
+        for rule in export.tag_ban_rules {
+            let Some((kind, pattern)) = rule.validated() else {
+                continue;
+            };
+            sesh.create_tag_ban_rule(kind, &pattern, rule.reason).await;
+        }
+        sesh.commit().await;
+    }
+}
+
+// inbox queue
+impl PgConn {
+    /// `origin` is the domain that signed the activity. `seen` is the id of
+    /// the activity and for creates of the object, which are recorded as
+    /// processed along with queueing it. returns false without queueing or
+    /// recording anything if either was seen before
+    pub async fn enqueue_activity(
+        &self,
+        activity: &str,
+        origin: &str,
+        seen: Option<(Url, Option<Url>)>,
+    ) -> bool {
+        let mut client = self.db.get().await.expect("failed to get client");
+        let transaction = client
+            .transaction()
+            .await
+            .expect("failed to begin transaction");
+        let sesh = Sesh::Transaction(transaction);
+        if let Some((activity_id, object)) = &seen {
+            if !sesh.record_processed("activity", activity_id).await {
+                return false;
+            }
+            if let Some(object) = object {
+                if !sesh.record_processed("object", object).await {
+                    return false;
+                }
+            }
+        }
+        sesh.enqueue_activity(activity, origin).await;
+        sesh.commit().await;
+        true
+    }
+    /// the activity is held for `lease` milliseconds before
+    /// another worker may pick it up again
+    pub async fn claim_queued_activity(&self, lease: i64) -> Option<QueuedActivity> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let now = chrono::Utc::now().timestamp_millis();
+        sesh.claim_activity(now, now + lease).await
+    }
+    pub async fn complete_queued_activity(&self, qid: Uuid) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.delete_queued_activity(qid).await
+    }
+    pub async fn retry_queued_activity(&self, qid: Uuid, next_attempt: i64, error: &str) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.retry_activity(qid, next_attempt, error).await
+    }
+    /// moves the activity out of the queue and into the dead letters
+    pub async fn dead_letter_queued_activity(&self, qid: Uuid, error: &str) {
+        let mut client = self.db.get().await.expect("failed to get client");
+        let transaction = client
+            .transaction()
+            .await
+            .expect("failed to begin transaction");
+        let sesh = Sesh::Transaction(transaction);
+        sesh.create_dead_letter(qid, error).await;
+        sesh.delete_queued_activity(qid).await;
+        sesh.commit().await;
+    }
+    pub async fn queue_depth(&self) -> QueueDepth {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.queue_depth().await
+    }
+}
+
+// inbox activity counts
+impl PgConn {
+    pub async fn count_inbox_activity(&self, kind: &str, supported: bool) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let now = chrono::Utc::now().timestamp_millis();
+        sesh.count_inbox_activity(kind, supported, now).await
+    }
+    pub async fn get_inbox_activity_counts(&self) -> Vec<ActivityCount> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_inbox_activity_counts().await
+    }
+}
+
+// processed activities
+impl PgConn {
+    /// forgets ids processed more than `retention` milliseconds ago
+    pub async fn prune_processed(&self, retention: i64) -> usize {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let before = chrono::Utc::now().timestamp_millis() - retention;
+        sesh.prune_processed(before).await
+    }
+}
+
+// relay subscriptions
+impl PgConn {
+    pub async fn create_relay_subscription(&self, user: &User, activitypub_id: &Url) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.create_relay_subscription(user, activitypub_id).await;
+        self.relay_subscribers.invalidate();
+    }
+    /// returns false if the follow was not a relay subscription
+    pub async fn delete_relay_subscription(&self, activitypub_id: &Url) -> bool {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let deleted = sesh.delete_relay_subscription(activitypub_id).await;
+        self.relay_subscribers.invalidate();
+        deleted
+    }
+    /// whether any actor on the instance subscribed to us as a relay
+    pub async fn is_relay_subscriber(&self, domain: &str) -> bool {
+        let subscribers = match self.relay_subscribers.get() {
+            Ok(subscribers) => subscribers,
+            Err(generation) => {
+                let client = self.db.get().await.expect("failed to get client");
+                let sesh = Sesh::Client(client);
+                let domains = sesh.get_relay_subscriber_domains().await;
+                self.relay_subscribers
+                    .set(generation, domains.into_iter().collect())
+            }
+        };
+        subscribers.contains(domain)
+    }
+    pub async fn relay_unique_inboxes(&self) -> Vec<Url> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.relay_unique_inboxes().await
+    }
+    pub async fn add_relay_deliveries(&self, pid: Uuid, inboxes: &[Url]) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        for inbox in inboxes {
+            sesh.add_relay_delivery(pid, inbox).await;
+        }
+    }
+    pub async fn get_relay_deliveries(&self, pid: Uuid) -> Vec<Url> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_relay_deliveries(pid).await
+    }
+}
+
+// keywords
+impl PgConn {
+    /// compiled once and kept until a keyword is added or removed
+    pub async fn get_keywords(&self) -> Arc<Keywords<TagKeyword>> {
+        match self.keywords.get() {
+            Ok(keywords) => keywords,
+            Err(generation) => {
+                let client = self.db.get().await.expect("failed to get client");
+                let sesh = Sesh::Client(client);
+                let rules = sesh.get_tag_keywords().await;
+                self.keywords
+                    .set(generation, Keywords::new(rules, |rule| &rule.keyword))
+            }
+        }
+    }
+    pub async fn get_keywords_of_tag(&self, tag: &Tag) -> Vec<TagKeyword> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.get_keywords_of_tag(tag).await
+    }
+    /// adding a keyword that already exists updates its case sensitivity
+    pub async fn add_tag_keyword(
+        &self,
+        tag: &Tag,
+        kind: KeywordKind,
+        text: &str,
+        case_sensitive: bool,
+    ) -> Result<TagKeyword, KeywordErr> {
+        let keyword = Keyword {
+            kind,
+            text: validate_keyword(text)?,
+            case_sensitive,
+        };
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let created = sesh.create_tag_keyword(tag, &keyword).await;
+        self.keywords.invalidate();
+        Ok(created)
+    }
+    pub async fn remove_tag_keyword(&self, keyword_id: i64) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.delete_tag_keyword(keyword_id).await;
+        self.keywords.invalidate();
+    }
+    pub async fn record_recent_post(&self, activitypub_id: &Url, actor: &Url, content: &str) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.record_recent_post(activitypub_id, actor, content)
+            .await
+    }
+    /// forgets the text of every post by the actor
+    pub async fn delete_recent_posts_by(&self, actor: &Url) {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        sesh.delete_recent_posts_by(actor).await
+    }
+    /// forgets the text of posts received more than `retention` milliseconds ago
+    pub async fn prune_recent_posts(&self, retention: i64) -> usize {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let before = chrono::Utc::now().timestamp_millis() - retention;
+        sesh.prune_recent_posts(before).await
+    }
+    /// the posts among the `limit` most recently received that the
+    /// keywords would have matched, so they can be tried before being added
+    pub async fn preview_keywords(
+        &self,
+        keywords: Vec<Keyword>,
+        limit: i64,
+    ) -> Vec<KeywordPreview> {
+        let client = self.db.get().await.expect("failed to get client");
+        let sesh = Sesh::Client(client);
+        let keywords = Keywords::new(keywords, |keyword| keyword);
+        sesh.get_recent_posts(limit)
+            .await
+            .into_iter()
+            .filter_map(|post| {
+                let matched: Vec<String> = keywords
+                    .matching(&post.content)
+                    .map(|keyword| keyword.text.clone())
+                    .collect();
+                match matched.is_empty() {
+                    true => None,
+                    false => Some(KeywordPreview { post, matched }),
+                }
+            })
+            .collect()
+    }
 }
@@ -3,9 +3,23 @@ use tokio_postgres::{types::ToSql, Statement};
 use url::Url;
 use uuid::Uuid;
 
-use crate::{cryptography::key::Algorithms, types::actors::Actor};
+use crate::{
+    cryptography::key::{Algorithms, Key},
+    types::actors::Actor,
+};
 
-use super::types::{instance::Instance, instance_actor::InstanceActor, tag::Tag, user::User};
+use super::types::{
+    consent::OptOut,
+    inbox_queue::{ActivityCount, QueueDepth, QueuedActivity},
+    instance::Instance,
+    instance_actor::InstanceActor,
+    keyword::{Keyword, RecentPost, TagKeyword},
+    post::Post,
+    tag::Tag,
+    tag_ban::{TagBanKind, TagBanRule},
+    tag_policy::SkippedPost,
+    user::User,
+};
 
 pub enum Sesh<'a> {
     Client(Object),
@@ -112,14 +126,46 @@ impl Sesh<'_> {
             .await
             .expect("failed to fetch tag")
             .pop();
-        match result {
-            Some(row) => Some(row.into()),
-            None => None,
-        }
+        result.map(|row| row.into())
+    }
+    /// the language scoped tags of a tag, like `rust.de` for `rust`
+    pub async fn get_scoped_tags(&self, tag: &str) -> Vec<Tag> {
+        let stmt = r#"
+            SELECT * FROM tags WHERE split_part(tag, '.', 1) = $1 AND tag LIKE '%.%';
+        "#;
+        self.query(stmt, &[&tag])
+            .await
+            .expect("failed to fetch scoped tags")
+            .into_iter()
+            .map(Tag::from)
+            .filter(|x| x.language().is_some())
+            .collect()
+    }
+    pub async fn get_tags(&self) -> Vec<Tag> {
+        let stmt = r#"
+            SELECT * FROM tags ORDER BY tag_id;
+        "#;
+        self.query(stmt, &[])
+            .await
+            .expect("failed to fetch tags")
+            .into_iter()
+            .map(|row| row.into())
+            .collect()
+    }
+    pub async fn get_tag_by_id(&self, tag_id: i64) -> Option<Tag> {
+        let stmt = r#"
+            SELECT * FROM tags WHERE tag_id = $1;
+        "#;
+        let result = self
+            .query(stmt, &[&tag_id])
+            .await
+            .expect("failed to fetch tag")
+            .pop();
+        result.map(|row| row.into())
     }
     pub async fn set_tag_banned(&self, tag_id: i64, banned: bool) -> Result<(), ()> {
         let stmt = r#"
-            UPSATE tags SET banned = $1 WHERE tag_id = $2;
+            UPDATE tags SET banned = $1 WHERE tag_id = $2;
         "#;
         let result = self.query(stmt, &[&banned, &tag_id]).await;
         match result {
@@ -127,16 +173,16 @@ impl Sesh<'_> {
             Err(_) => Err(()),
         }
     }
-    pub async fn create_tag(&self, tag: &str, banned: bool) -> Tag {
+    pub async fn create_tag(&self, tag: &str, display_name: Option<&str>, banned: bool) -> Tag {
         let stmt = r#"
         INSERT INTO tags
-        (tag, banned)
+        (tag, display_name, banned)
         VALUES
-        ($1, $2)
+        ($1, $2, $3)
         RETURNING *;
         "#;
         let result = self
-            .query(stmt, &[&tag, &banned])
+            .query(stmt, &[&tag, &display_name, &banned])
             .await
             .expect("failed to create tag")
             .pop()
@@ -148,12 +194,28 @@ impl Sesh<'_> {
         UPDATE tags SET
         display_name = $1,
         bio = $2,
-        banned = $3
-        WHERE tag_id = $4
+        banned = $3,
+        expression = $4,
+        announce_policy = $5,
+        quote_policy = $6,
+        policy = $7
+        WHERE tag_id = $8
         RETURNING *;
         "#;
         let result = self
-            .query(stmt, &[&tag.display_name, &tag.bio, &tag.banned, &tag.id])
+            .query(
+                stmt,
+                &[
+                    &tag.display_name,
+                    &tag.bio,
+                    &tag.banned,
+                    &tag.expression,
+                    &tag.announce_policy.stringify(),
+                    &tag.quote_policy.stringify(),
+                    &serde_json::to_string(&tag.policy).expect("failed to serialize tag policy"),
+                    &tag.id,
+                ],
+            )
             .await
             .expect("failed to update tag")
             .pop()
@@ -162,70 +224,1279 @@ impl Sesh<'_> {
     }
 }
 
+// feeds
+impl Sesh<'_> {
+    pub async fn get_feeds(&self) -> Vec<Tag> {
+        let stmt = r#"
+            SELECT * FROM tags WHERE expression IS NOT NULL;
+        "#;
+        self.query(stmt, &[])
+            .await
+            .expect("failed to fetch feeds")
+            .into_iter()
+            .map(|row| row.into())
+            .collect()
+    }
+}
+
+// tag aliases
+impl Sesh<'_> {
+    /// all tags that are an alias of the provided tag
+    pub async fn get_tag_aliases(&self, tag: &Tag) -> Vec<Tag> {
+        let stmt = r#"
+            SELECT * FROM tags WHERE alias_of = $1;
+        "#;
+        self.query(stmt, &[&tag.id])
+            .await
+            .expect("failed to fetch tag aliases")
+            .into_iter()
+            .map(|row| row.into())
+            .collect()
+    }
+    /// makes `alias` and every existing alias of it point at `canonical`
+    /// so that alias chains never form
+    pub async fn set_tag_alias(&self, alias: &Tag, canonical: &Tag) {
+        let stmt = r#"
+            UPDATE tags SET alias_of = $1 WHERE tag_id = $2 OR alias_of = $2;
+        "#;
+        self.query(stmt, &[&canonical.id, &alias.id])
+            .await
+            .expect("failed to set tag alias");
+    }
+    pub async fn remove_tag_alias(&self, alias: &Tag) {
+        let stmt = r#"
+            UPDATE tags SET alias_of = NULL WHERE tag_id = $1;
+        "#;
+        self.query(stmt, &[&alias.id])
+            .await
+            .expect("failed to remove tag alias");
+    }
+    /// moves every follow of `from` over to `into`. users already following
+    /// `into` keep their existing follow and their follow of `from` is dropped
+    ///
+    /// returns the follower and ufid of every follow that was dropped
+    pub async fn merge_tag_follows(&self, from: &Tag, into: &Tag) -> Vec<(Uuid, Uuid)> {
+        let stmt = r#"
+            UPDATE user_tags SET tag = $1
+            WHERE tag = $2
+            AND follower NOT IN (SELECT follower FROM user_tags WHERE tag = $1);
+        "#;
+        self.query(stmt, &[&into.id, &from.id])
+            .await
+            .expect("failed to merge tag follows");
+        let stmt = r#"
+            DELETE FROM user_tags WHERE tag = $1 RETURNING follower, ufid;
+        "#;
+        self.query(stmt, &[&from.id])
+            .await
+            .expect("failed to remove duplicate tag follows")
+            .into_iter()
+            .map(|row| (row.get("follower"), row.get("ufid")))
+            .collect()
+    }
+}
+
+// tag ban rules
+impl Sesh<'_> {
+    pub async fn get_tag_ban_rules(&self) -> Vec<TagBanRule> {
+        let stmt = r#"
+            SELECT * FROM tag_ban_rules ORDER BY rule_id;
+        "#;
+        self.query(stmt, &[])
+            .await
+            .expect("failed to fetch tag ban rules")
+            .into_iter()
+            .map(|row| row.into())
+            .collect()
+    }
+    /// the names of tags banned on their own, rather than by a rule
+    pub async fn get_banned_tag_names(&self) -> Vec<String> {
+        let stmt = r#"
+            SELECT tag FROM tags WHERE banned;
+        "#;
+        self.query(stmt, &[])
+            .await
+            .expect("failed to fetch banned tags")
+            .into_iter()
+            .map(|row| row.get("tag"))
+            .collect()
+    }
+    /// the pattern should already be validated
+    pub async fn create_tag_ban_rule(
+        &self,
+        kind: TagBanKind,
+        pattern: &str,
+        reason: Option<String>,
+    ) -> TagBanRule {
+        let stmt = r#"
+        INSERT INTO tag_ban_rules
+        (kind, pattern, reason, created)
+        VALUES
+        ($1, $2, $3, $4)
+        ON CONFLICT (kind, pattern) DO UPDATE SET
+        reason = EXCLUDED.reason
+        RETURNING *;
+        "#;
+        let created = chrono::Utc::now().timestamp_millis();
+        let result = self
+            .query(stmt, &[&kind.stringify(), &pattern, &reason, &created])
+            .await
+            .expect("failed to create tag ban rule")
+            .pop()
+            .expect("creating tag ban rule returned nothing");
+        result.into()
+    }
+    pub async fn delete_tag_ban_rule(&self, rule_id: i64) {
+        let stmt = r#"
+            DELETE FROM tag_ban_rules WHERE rule_id = $1;
+        "#;
+        self.query(stmt, &[&rule_id])
+            .await
+            .expect("failed to delete tag ban rule");
+    }
+}
+
 //users
 impl Sesh<'_> {
+    pub async fn get_user_by_uid(&self, uid: Uuid) -> Option<User> {
+        let stmt = r#"
+            SELECT * FROM users WHERE uid = $1;
+        "#;
+        let result = self
+            .query(stmt, &[&uid])
+            .await
+            .expect("failed to fetch user")
+            .pop();
+        result.map(|row| row.into())
+    }
     pub async fn get_user(&self, username: &str, domain: &str) -> Option<User> {
-        todo!()
+        let stmt = r#"
+            SELECT * FROM users WHERE username = $1 AND domain = $2;
+        "#;
+        let result = self
+            .query(stmt, &[&username, &domain])
+            .await
+            .expect("failed to fetch user")
+            .pop();
+        result.map(|row| row.into())
     }
+    pub async fn get_user_by_key_id(&self, public_key_id: &str) -> Option<User> {
+        let stmt = r#"
+            SELECT * FROM users WHERE public_key_id = $1;
+        "#;
+        let result = self
+            .query(stmt, &[&public_key_id])
+            .await
+            .expect("failed to fetch user")
+            .pop();
+        result.map(|row| row.into())
+    }
+    pub async fn get_user_by_activitypub_id(&self, activitypub_id: &Url) -> Option<User> {
+        let stmt = r#"
+            SELECT * FROM users WHERE activitypub_id = $1;
+        "#;
+        let result = self
+            .query(stmt, &[&activitypub_id.as_str()])
+            .await
+            .expect("failed to fetch user")
+            .pop();
+        result.map(|row| row.into())
+    }
+    /// users stored before their opt out was, see V20
+    pub async fn get_unchecked_opt_outs(&self) -> Vec<User> {
+        let stmt = r#"
+            SELECT * FROM users WHERE NOT opt_out_checked;
+        "#;
+        self.query(stmt, &[])
+            .await
+            .expect("failed to fetch unchecked users")
+            .into_iter()
+            .map(|row| row.into())
+            .collect()
+    }
+    /// the instance of the actor must already exist, and so its id must
+    /// have a host, which may be an ip address
     pub async fn create_user(&self, actor: Actor, banned: bool, reason: Option<String>) -> User {
-        todo!()
+        let stmt = r#"
+        INSERT INTO users
+        (
+            uid, activitypub_id, url, domain, username, display_name,
+            public_key_pem, public_key_id, banned, reason,
+            inbox, shared_inbox, outbox, followers, following, fetched_at,
+            opt_out, bot, created_at
+        )
+        VALUES
+        (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
+            $11, $12, $13, $14, $15, $16, $17, $18, $19
+        )
+        RETURNING *;
+        "#;
+        let id = Uuid::now_v7();
+        let domain = actor.id.host_str().expect("actor has no host").to_string();
+        let url = actor.url.as_ref().unwrap_or(&actor.id).to_string();
+        let public_key_pem = actor
+            .public_key
+            .public_key_pem
+            .to_pem()
+            .expect("failed to encode actor public key");
+        let followers = actor.followers.as_ref().unwrap_or(&actor.id).to_string();
+        let following = actor.following.as_ref().unwrap_or(&actor.id).to_string();
+        let fetched_at = chrono::Utc::now().timestamp_millis();
+        let opt_out = OptOut::of(&actor);
+
+        let result = self
+            .query(
+                stmt,
+                &[
+                    &id,
+                    &actor.id.as_str(),
+                    &url,
+                    &domain,
+                    &actor.preferred_username,
+                    &actor.name,
+                    &public_key_pem,
+                    &actor.public_key.id.as_str(),
+                    &banned,
+                    &reason,
+                    &actor.inbox.as_str(),
+                    &actor.shared_inbox().as_str(),
+                    &actor.outbox.as_str(),
+                    &followers,
+                    &following,
+                    &fetched_at,
+                    &opt_out.as_ref().map(OptOut::stringify),
+                    &actor.is_bot(),
+                    &actor.extensions.published(),
+                ],
+            )
+            .await
+            .expect("failed to create user")
+            .pop()
+            .expect("creating user returned nothing");
+        result.into()
     }
     pub async fn update_user(&self, user: User) -> User {
-        todo!()
+        let stmt = r#"
+        UPDATE users SET
+        url = $1,
+        username = $2,
+        display_name = $3,
+        public_key_pem = $4,
+        public_key_id = $5,
+        inbox = $6,
+        shared_inbox = $7,
+        outbox = $8,
+        followers = $9,
+        following = $10,
+        fetched_at = $11,
+        opt_out = $12,
+        opt_out_checked = true,
+        bot = $13,
+        created_at = $14
+        WHERE uid = $15
+        RETURNING *;
+        "#;
+        let result = self
+            .query(
+                stmt,
+                &[
+                    &user.url.as_str(),
+                    &user.username,
+                    &user.display_name,
+                    &user.public_key_pem,
+                    &user.public_key_id,
+                    &user.inbox.as_str(),
+                    &user.shared_inbox.as_str(),
+                    &user.outbox.as_str(),
+                    &user.followers.as_str(),
+                    &user.following.as_str(),
+                    &user.fetched_at,
+                    &user.opt_out.as_ref().map(OptOut::stringify),
+                    &user.bot,
+                    &user.created_at,
+                    &user.id,
+                ],
+            )
+            .await
+            .expect("failed to update user")
+            .pop()
+            .expect("updating user returned nothing");
+        result.into()
     }
     pub async fn delete_user(&self, user: User) {
-        todo!()
+        let stmt = r#"
+            DELETE FROM users WHERE uid = $1;
+        "#;
+        self.query(stmt, &[&user.id])
+            .await
+            .expect("failed to delete user");
     }
     pub async fn set_user_banned(&self, user: &User, banned: bool, reason: Option<String>) {
-        todo!()
+        let stmt = r#"
+            UPDATE users SET banned = $1, reason = $2 WHERE uid = $3;
+        "#;
+        self.query(stmt, &[&banned, &reason, &user.id])
+            .await
+            .expect("failed to set user banned");
     }
 }
 
 impl Sesh<'_> {
+    /// if the user already follows the tag the follow's activitypub id
+    /// is updated and the existing ufid is kept
     pub async fn create_following(&self, user: &User, tag: &Tag, activitypub_id: Url) -> Uuid {
-        todo!()
+        let stmt = r#"
+        INSERT INTO user_tags
+        (ufid, user_follow_activitypub_id, follower, tag, published)
+        VALUES
+        ($1, $2, $3, $4, $5)
+        ON CONFLICT (follower, tag) DO UPDATE SET
+        user_follow_activitypub_id = EXCLUDED.user_follow_activitypub_id
+        RETURNING ufid;
+        "#;
+        let published = chrono::Utc::now().timestamp_millis();
+        let result = self
+            .query(
+                stmt,
+                &[
+                    &Uuid::now_v7(),
+                    &activitypub_id.as_str(),
+                    &user.id,
+                    &tag.id,
+                    &published,
+                ],
+            )
+            .await
+            .expect("failed to create following")
+            .pop()
+            .expect("creating following returned nothing");
+        result.get("ufid")
     }
     pub async fn get_following(&self, user: &User, tag: &Tag) -> Option<Uuid> {
         let stmt = r#"
-            SELECT * FROM user_tags WHERE user = $1 AND tag = $2;
+            SELECT * FROM user_tags WHERE follower = $1 AND tag = $2;
         "#;
         let result = self
             .query(stmt, &[&user.id, &tag.id])
             .await
             .expect("failed to fetch tag")
             .pop();
-        match result {
-            Some(row) => Some(row.get("ufid")),
-            None => None,
-        }
+        result.map(|row| row.get("ufid"))
     }
-    pub async fn undo_following(&self, activitypub_id: Url) {
-
+    /// every tag the user follows along with the ufid of the follow
+    pub async fn get_user_following(&self, user: &User) -> Vec<(Tag, Uuid)> {
+        let stmt = r#"
+            SELECT tags.*, user_tags.ufid FROM user_tags
+            INNER JOIN tags ON user_tags.tag = tags.tag_id
+            WHERE user_tags.follower = $1;
+        "#;
+        self.query(stmt, &[&user.id])
+            .await
+            .expect("failed to fetch user following")
+            .into_iter()
+            .map(|row| {
+                let ufid = row.get("ufid");
+                (row.into(), ufid)
+            })
+            .collect()
+    }
+    /// hands a follow over to another user under a new ufid and follow
+    /// id, as the old follow id belongs to the old user. returns none if
+    /// that user already follows the tag
+    pub async fn transfer_following(
+        &self,
+        ufid: Uuid,
+        to: &User,
+        new_ufid: Uuid,
+        activitypub_id: &Url,
+    ) -> Option<Uuid> {
+        let stmt = r#"
+        UPDATE user_tags SET follower = $1, ufid = $2, user_follow_activitypub_id = $4
+        WHERE ufid = $3 AND NOT EXISTS (
+            SELECT 1 FROM user_tags AS existing
+            WHERE existing.follower = $1 AND existing.tag = user_tags.tag
+        )
+        RETURNING ufid;
+        "#;
+        let result = self
+            .query(stmt, &[&to.id, &new_ufid, &ufid, &activitypub_id.as_str()])
+            .await
+            .expect("failed to transfer following")
+            .pop();
+        result.map(|row| row.get("ufid"))
+    }
+    pub async fn delete_following(&self, ufid: Uuid) {
+        let stmt = r#"
+            DELETE FROM user_tags WHERE ufid = $1;
+        "#;
+        self.query(stmt, &[&ufid])
+            .await
+            .expect("failed to delete following");
+    }
+    pub async fn get_following_by_activitypub_id(&self, activitypub_id: &Url) -> Option<Uuid> {
+        let stmt = r#"
+            SELECT ufid FROM user_tags WHERE user_follow_activitypub_id = $1;
+        "#;
+        let result = self
+            .query(stmt, &[&activitypub_id.as_str()])
+            .await
+            .expect("failed to fetch following")
+            .pop();
+        result.map(|row| row.get("ufid"))
+    }
+    /// returns the follower, tag and ufid of the removed follow
+    pub async fn undo_following(&self, activitypub_id: &Url) -> Option<(Uuid, i64, Uuid)> {
+        let stmt = r#"
+            DELETE FROM user_tags WHERE user_follow_activitypub_id = $1
+            RETURNING follower, tag, ufid;
+        "#;
+        let result = self
+            .query(stmt, &[&activitypub_id.as_str()])
+            .await
+            .expect("failed to undo following")
+            .pop();
+        result.map(|row| (row.get("follower"), row.get("tag"), row.get("ufid")))
+    }
+    /// removes the user's follow of the tag if its id starts with
+    /// `issued_prefix`, that is if the id was issued by us
+    pub async fn undo_issued_following(
+        &self,
+        follower: Uuid,
+        tag: i64,
+        issued_prefix: &str,
+    ) -> Option<Uuid> {
+        let stmt = r#"
+            DELETE FROM user_tags
+            WHERE follower = $1 AND tag = $2 AND starts_with(user_follow_activitypub_id, $3)
+            RETURNING ufid;
+        "#;
+        let result = self
+            .query(stmt, &[&follower, &tag, &issued_prefix])
+            .await
+            .expect("failed to undo following")
+            .pop();
+        result.map(|row| row.get("ufid"))
+    }
+    /// the activitypub ids of every follow of the user
+    pub async fn get_user_follow_ids(&self, user: &User) -> Vec<Url> {
+        let stmt = r#"
+            SELECT user_follow_activitypub_id FROM user_tags WHERE follower = $1;
+        "#;
+        self.query(stmt, &[&user.id])
+            .await
+            .expect("failed to fetch user follows")
+            .into_iter()
+            .map(|row| {
+                let id: String = row.get("user_follow_activitypub_id");
+                Url::parse(&id).expect("invalid follow id in db")
+            })
+            .collect()
+    }
+    pub async fn count_user_following(&self, user: &User) -> i64 {
+        let stmt = r#"
+            SELECT count(*) AS following FROM user_tags WHERE follower = $1;
+        "#;
+        self.query(stmt, &[&user.id])
+            .await
+            .expect("failed to count user following")
+            .pop()
+            .expect("counting user following returned nothing")
+            .get("following")
+    }
+    pub async fn get_followed_tags(&self, user: &User) -> Vec<Tag> {
+        let stmt = r#"
+            SELECT tags.* FROM user_tags
+            INNER JOIN tags ON user_tags.tag = tags.tag_id
+            WHERE user_tags.follower = $1;
+        "#;
+        self.query(stmt, &[&user.id])
+            .await
+            .expect("failed to fetch followed tags")
+            .into_iter()
+            .map(|row| row.into())
+            .collect()
     }
     pub async fn tag_followers(&self, tag: &Tag) -> Vec<User> {
-        todo!()
+        let stmt = r#"
+            SELECT users.* FROM user_tags
+            INNER JOIN users ON user_tags.follower = users.uid
+            WHERE user_tags.tag = $1;
+        "#;
+        self.query(stmt, &[&tag.id])
+            .await
+            .expect("failed to fetch tag followers")
+            .into_iter()
+            .map(|row| row.into())
+            .collect()
     }
+    pub async fn count_tag_followers(&self, tag: &Tag) -> i64 {
+        let stmt = r#"
+            SELECT COUNT(*) AS followers FROM user_tags WHERE tag = $1;
+        "#;
+        self.query(stmt, &[&tag.id])
+            .await
+            .expect("failed to count tag followers")
+            .pop()
+            .map(|row| row.get("followers"))
+            .unwrap_or(0)
+    }
+    /// the distinct shared inboxes of every follower of a tag
+    /// excluding banned users and blocked instances
     pub async fn tag_unique_inboxes(&self, tag: &Tag) -> Vec<Url> {
-        todo!()
+        let stmt = r#"
+            SELECT DISTINCT users.shared_inbox FROM user_tags
+            INNER JOIN users ON user_tags.follower = users.uid
+            INNER JOIN instances ON users.domain = instances.domain
+            WHERE user_tags.tag = $1
+            AND NOT users.banned
+            AND NOT instances.blocked;
+        "#;
+        self.query(stmt, &[&tag.id])
+            .await
+            .expect("failed to fetch tag inboxes")
+            .into_iter()
+            .filter_map(|row| {
+                let inbox: String = row.get("shared_inbox");
+                Url::parse(&inbox).ok()
+            })
+            .collect()
+    }
+}
+
+// posts
+impl Sesh<'_> {
+    /// returns none if the post already exists
+    pub async fn create_post(
+        &self,
+        activitypub_id: &Url,
+        published: i64,
+        actor: &User,
+    ) -> Option<Uuid> {
+        let stmt = r#"
+        INSERT INTO posts
+        (pid, activitypub_id, domain, published, fetched_at, actor)
+        VALUES
+        ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (activitypub_id) DO NOTHING
+        RETURNING pid;
+        "#;
+        let fetched_at = chrono::Utc::now().timestamp_millis();
+        let result = self
+            .query(
+                stmt,
+                &[
+                    &Uuid::now_v7(),
+                    &activitypub_id.as_str(),
+                    &actor.domain,
+                    &published,
+                    &fetched_at,
+                    &actor.id,
+                ],
+            )
+            .await
+            .expect("failed to create post")
+            .pop();
+        result.map(|row| row.get("pid"))
+    }
+    pub async fn get_post(&self, activitypub_id: &Url) -> Option<Post> {
+        let stmt = r#"
+            SELECT * FROM posts WHERE activitypub_id = $1;
+        "#;
+        let result = self
+            .query(stmt, &[&activitypub_id.as_str()])
+            .await
+            .expect("failed to fetch post")
+            .pop();
+        result.map(|row| row.into())
+    }
+    pub async fn get_user_posts(&self, user: &User) -> Vec<Post> {
+        let stmt = r#"
+            SELECT * FROM posts WHERE actor = $1;
+        "#;
+        self.query(stmt, &[&user.id])
+            .await
+            .expect("failed to fetch user posts")
+            .into_iter()
+            .map(|row| row.into())
+            .collect()
+    }
+    /// also removes the post's tags and deliveries
+    pub async fn delete_post(&self, post: Post) {
+        let stmt = r#"
+            DELETE FROM posts WHERE pid = $1;
+        "#;
+        self.query(stmt, &[&post.id])
+            .await
+            .expect("failed to delete post");
+    }
+    pub async fn add_post_tag(&self, pid: Uuid, tag: &Tag) {
+        let stmt = r#"
+        INSERT INTO post_tags
+        (pid, tag)
+        VALUES
+        ($1, $2)
+        ON CONFLICT DO NOTHING;
+        "#;
+        self.query(stmt, &[&pid, &tag.id])
+            .await
+            .expect("failed to add post tag");
+    }
+    pub async fn get_post_tags(&self, pid: Uuid) -> Vec<Tag> {
+        let stmt = r#"
+            SELECT tags.* FROM post_tags
+            INNER JOIN tags ON post_tags.tag = tags.tag_id
+            WHERE post_tags.pid = $1;
+        "#;
+        self.query(stmt, &[&pid])
+            .await
+            .expect("failed to fetch post tags")
+            .into_iter()
+            .map(|row| row.into())
+            .collect()
+    }
+    pub async fn remove_post_tag(&self, pid: Uuid, tag: &Tag) {
+        let stmt = r#"
+            DELETE FROM post_tags WHERE pid = $1 AND tag = $2;
+        "#;
+        self.query(stmt, &[&pid, &tag.id])
+            .await
+            .expect("failed to remove post tag");
+    }
+}
+
+// post deliveries
+impl Sesh<'_> {
+    pub async fn add_post_delivery(&self, pid: Uuid, tag: &Tag, inbox: &Url) {
+        let stmt = r#"
+        INSERT INTO post_deliveries
+        (pid, tag, inbox)
+        VALUES
+        ($1, $2, $3)
+        ON CONFLICT DO NOTHING;
+        "#;
+        self.query(stmt, &[&pid, &tag.id, &inbox.as_str()])
+            .await
+            .expect("failed to add post delivery");
+    }
+    /// every tag that announced the post along with the inboxes it was delivered to
+    pub async fn get_post_deliveries(&self, pid: Uuid) -> Vec<(Tag, Vec<Url>)> {
+        let stmt = r#"
+            SELECT tags.*, array_agg(post_deliveries.inbox) AS inboxes FROM post_deliveries
+            INNER JOIN tags ON post_deliveries.tag = tags.tag_id
+            WHERE post_deliveries.pid = $1
+            GROUP BY tags.tag_id;
+        "#;
+        self.query(stmt, &[&pid])
+            .await
+            .expect("failed to fetch post deliveries")
+            .into_iter()
+            .map(|row| {
+                let inboxes: Vec<String> = row.get("inboxes");
+                let inboxes = inboxes
+                    .iter()
+                    .filter_map(|inbox| Url::parse(inbox).ok())
+                    .collect();
+                (row.into(), inboxes)
+            })
+            .collect()
+    }
+    pub async fn remove_post_deliveries(&self, pid: Uuid, tag: &Tag) {
+        let stmt = r#"
+            DELETE FROM post_deliveries WHERE pid = $1 AND tag = $2;
+        "#;
+        self.query(stmt, &[&pid, &tag.id])
+            .await
+            .expect("failed to remove post deliveries");
+    }
+    /// the posts the tag announced, newest first
+    pub async fn get_tag_announces(&self, tag: &Tag, limit: i64, offset: i64) -> Vec<Post> {
+        let stmt = r#"
+            SELECT * FROM posts WHERE pid IN (
+                SELECT DISTINCT pid FROM post_deliveries WHERE tag = $1
+            )
+            ORDER BY published DESC
+            LIMIT $2 OFFSET $3;
+        "#;
+        self.query(stmt, &[&tag.id, &limit, &offset])
+            .await
+            .expect("failed to fetch tag announces")
+            .into_iter()
+            .map(|row| row.into())
+            .collect()
+    }
+    pub async fn count_tag_announces(&self, tag: &Tag) -> i64 {
+        let stmt = r#"
+            SELECT COUNT(DISTINCT pid) AS announces FROM post_deliveries WHERE tag = $1;
+        "#;
+        self.query(stmt, &[&tag.id])
+            .await
+            .expect("failed to count tag announces")
+            .pop()
+            .map(|row| row.get("announces"))
+            .unwrap_or(0)
     }
 }
 
 impl Sesh<'_> {
-    pub async fn create_instance(&self, domain: &str, banned: bool, reason: Option<String>, allowlist: bool) -> Instance {
-        todo!()
+    pub async fn create_instance(
+        &self,
+        domain: &str,
+        banned: bool,
+        reason: Option<String>,
+        allowlist: bool,
+    ) -> Instance {
+        let stmt = r#"
+        INSERT INTO instances
+        (i_id, domain, blocked, reason, allowlisted)
+        VALUES
+        ($1, $2, $3, $4, $5)
+        RETURNING *;
+        "#;
+        let result = self
+            .query(
+                stmt,
+                &[&Uuid::now_v7(), &domain, &banned, &reason, &allowlist],
+            )
+            .await
+            .expect("failed to create instance")
+            .pop()
+            .expect("creating instance returned nothing");
+        result.into()
     }
     pub async fn get_instance(&self, domain: &str) -> Option<Instance> {
-        todo!()
+        let stmt = r#"
+            SELECT * FROM instances WHERE domain = $1;
+        "#;
+        let result = self
+            .query(stmt, &[&domain])
+            .await
+            .expect("failed to fetch instance")
+            .pop();
+        result.map(|row| row.into())
     }
     /// ban an istance without severing any connections or deleting data, will pause any future following
     /// and any incoming and outgoing traffic to this instance will stop
-    /// 
-    /// to delete and ban, create a transaction and use [`Sesh::delete_instance`] and then [`Sesh::create_instance`] 
+    ///
+    /// to delete and ban, create a transaction and use [`Sesh::delete_instance`] and then [`Sesh::create_instance`]
     /// with banned set to true
-    pub async fn set_instance_banned(&self, instance: Instance, banned: bool, reason: Option<String>) {
-        todo!()
+    pub async fn set_instance_banned(
+        &self,
+        instance: Instance,
+        banned: bool,
+        reason: Option<String>,
+    ) {
+        let stmt = r#"
+            UPDATE instances SET blocked = $1, reason = $2 WHERE i_id = $3;
+        "#;
+        self.query(stmt, &[&banned, &reason, &instance.id])
+            .await
+            .expect("failed to set instance banned");
+    }
+    /// reserves the next fetch to the instance, at least `interval` after
+    /// the previously reserved one. returns when the fetch may be made
+    pub async fn reserve_fetch_slot(&self, domain: &str, now: i64, interval: i64) -> Option<i64> {
+        let stmt = r#"
+        UPDATE instances SET
+        next_fetch = GREATEST(next_fetch, $2) + $3
+        WHERE domain = $1
+        RETURNING next_fetch - $3 AS fetch_at;
+        "#;
+        let result = self
+            .query(stmt, &[&domain, &now, &interval])
+            .await
+            .expect("failed to reserve fetch slot")
+            .pop();
+        result.map(|row| row.get("fetch_at"))
     }
     pub async fn delete_instance(&self, instance: Instance) {
-        todo!()
+        let stmt = r#"
+            DELETE FROM instances WHERE i_id = $1;
+        "#;
+        self.query(stmt, &[&instance.id])
+            .await
+            .expect("failed to delete instance");
+    }
+}
+
+// inbox queue
+impl Sesh<'_> {
+    pub async fn enqueue_activity(&self, activity: &str, origin: &str) {
+        let stmt = r#"
+        INSERT INTO inbox_queue
+        (qid, activity, origin, received, next_attempt)
+        VALUES
+        ($1, $2, $3, $4, $4);
+        "#;
+        let received = chrono::Utc::now().timestamp_millis();
+        self.query(stmt, &[&Uuid::now_v7(), &activity, &origin, &received])
+            .await
+            .expect("failed to enqueue activity");
+    }
+    /// takes the next due activity that no other worker holds, holding
+    /// it until `locked_until` and counting the attempt
+    pub async fn claim_activity(&self, now: i64, locked_until: i64) -> Option<QueuedActivity> {
+        let stmt = r#"
+        UPDATE inbox_queue SET
+        locked_until = $2,
+        attempts = attempts + 1
+        WHERE qid = (
+            SELECT qid FROM inbox_queue
+            WHERE next_attempt <= $1
+            AND (locked_until IS NULL OR locked_until < $1)
+            ORDER BY next_attempt
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING *;
+        "#;
+        let result = self
+            .query(stmt, &[&now, &locked_until])
+            .await
+            .expect("failed to claim activity")
+            .pop();
+        result.map(|row| row.into())
+    }
+    pub async fn delete_queued_activity(&self, qid: Uuid) {
+        let stmt = r#"
+            DELETE FROM inbox_queue WHERE qid = $1;
+        "#;
+        self.query(stmt, &[&qid])
+            .await
+            .expect("failed to delete queued activity");
+    }
+    pub async fn retry_activity(&self, qid: Uuid, next_attempt: i64, error: &str) {
+        let stmt = r#"
+        UPDATE inbox_queue SET
+        locked_until = NULL,
+        next_attempt = $2,
+        last_error = $3
+        WHERE qid = $1;
+        "#;
+        self.query(stmt, &[&qid, &next_attempt, &error])
+            .await
+            .expect("failed to retry activity");
+    }
+    /// copies the activity into the dead letters, it still
+    /// has to be removed from the queue
+    pub async fn create_dead_letter(&self, qid: Uuid, error: &str) {
+        let stmt = r#"
+        INSERT INTO inbox_dead_letters
+        (qid, activity, origin, received, attempts, last_error, failed)
+        SELECT qid, activity, origin, received, attempts, $2, $3
+        FROM inbox_queue WHERE qid = $1
+        ON CONFLICT DO NOTHING;
+        "#;
+        let failed = chrono::Utc::now().timestamp_millis();
+        self.query(stmt, &[&qid, &error, &failed])
+            .await
+            .expect("failed to create dead letter");
+    }
+    pub async fn queue_depth(&self) -> QueueDepth {
+        let stmt = r#"
+        SELECT
+        (SELECT count(*) FROM inbox_queue WHERE attempts = 0) AS pending,
+        (SELECT count(*) FROM inbox_queue WHERE attempts > 0) AS retrying,
+        (SELECT count(*) FROM inbox_dead_letters) AS dead_letters;
+        "#;
+        self.query(stmt, &[])
+            .await
+            .expect("failed to fetch queue depth")
+            .pop()
+            .expect("queue depth returned nothing")
+            .into()
+    }
+}
+
+// inbox activity counts
+impl Sesh<'_> {
+    /// whether the kind is supported follows the running release
+    pub async fn count_inbox_activity(&self, kind: &str, supported: bool, received: i64) {
+        let stmt = r#"
+        INSERT INTO inbox_activity_counts
+        (kind, supported, received, last_received)
+        VALUES
+        ($1, $2, 1, $3)
+        ON CONFLICT (kind) DO UPDATE SET
+        supported = EXCLUDED.supported,
+        received = inbox_activity_counts.received + 1,
+        last_received = EXCLUDED.last_received
+        RETURNING kind;
+        "#;
+        self.query(stmt, &[&kind, &supported, &received])
+            .await
+            .expect("failed to count inbox activity");
+    }
+    pub async fn get_inbox_activity_counts(&self) -> Vec<ActivityCount> {
+        let stmt = r#"
+        SELECT * FROM inbox_activity_counts
+        ORDER BY received DESC;
+        "#;
+        self.query(stmt, &[])
+            .await
+            .expect("failed to get inbox activity counts")
+            .into_iter()
+            .map(ActivityCount::from)
+            .collect()
+    }
+}
+
+// processed activities
+impl Sesh<'_> {
+    /// returns false if the id was already recorded
+    pub async fn record_processed(&self, kind: &str, activitypub_id: &Url) -> bool {
+        let stmt = r#"
+        INSERT INTO processed_activities
+        (kind, activitypub_id, processed)
+        VALUES
+        ($1, $2, $3)
+        ON CONFLICT DO NOTHING
+        RETURNING activitypub_id;
+        "#;
+        let processed = chrono::Utc::now().timestamp_millis();
+        !self
+            .query(stmt, &[&kind, &activitypub_id.as_str(), &processed])
+            .await
+            .expect("failed to record processed activity")
+            .is_empty()
+    }
+    /// returns the number of ids that were forgotten
+    pub async fn prune_processed(&self, before: i64) -> usize {
+        let stmt = r#"
+            DELETE FROM processed_activities WHERE processed < $1 RETURNING kind;
+        "#;
+        self.query(stmt, &[&before])
+            .await
+            .expect("failed to prune processed activities")
+            .len()
+    }
+}
+
+// instance follows
+impl Sesh<'_> {
+    /// returns none if the instance actor already follows the user
+    pub async fn create_instance_follow(&self, user: &User) -> Option<Uuid> {
+        let stmt = r#"
+        INSERT INTO instance_follows
+        (ifid, follower, published)
+        VALUES
+        ($1, $2, $3)
+        ON CONFLICT DO NOTHING
+        RETURNING ifid;
+        "#;
+        let published = chrono::Utc::now().timestamp_millis();
+        let result = self
+            .query(stmt, &[&Uuid::now_v7(), &user.id, &published])
+            .await
+            .expect("failed to create instance follow")
+            .pop();
+        result.map(|row| row.get("ifid"))
+    }
+    /// returns the ifid of the removed follow
+    pub async fn delete_instance_follow(&self, user: &User) -> Option<Uuid> {
+        let stmt = r#"
+            DELETE FROM instance_follows WHERE follower = $1 RETURNING ifid;
+        "#;
+        let result = self
+            .query(stmt, &[&user.id])
+            .await
+            .expect("failed to delete instance follow")
+            .pop();
+        result.map(|row| row.get("ifid"))
+    }
+    /// removes every instance follow, returning the follower and ifid
+    pub async fn delete_instance_follows(&self) -> Vec<(Uuid, Uuid)> {
+        let stmt = r#"
+            DELETE FROM instance_follows RETURNING follower, ifid;
+        "#;
+        self.query(stmt, &[])
+            .await
+            .expect("failed to delete instance follows")
+            .into_iter()
+            .map(|row| (row.get("follower"), row.get("ifid")))
+            .collect()
+    }
+    /// the follower, tag and ufid of every follow
+    pub async fn get_all_following(&self) -> Vec<(Uuid, i64, Uuid)> {
+        let stmt = r#"
+            SELECT follower, tag, ufid FROM user_tags;
+        "#;
+        self.query(stmt, &[])
+            .await
+            .expect("failed to fetch follows")
+            .into_iter()
+            .map(|row| (row.get("follower"), row.get("tag"), row.get("ufid")))
+            .collect()
+    }
+    pub async fn get_follow_mode(&self) -> Option<String> {
+        let stmt = r#"
+            SELECT follow_mode FROM ap_instance_actor LIMIT 1;
+        "#;
+        self.query(stmt, &[])
+            .await
+            .expect("failed to fetch follow mode")
+            .pop()
+            .and_then(|row| row.get("follow_mode"))
+    }
+    pub async fn set_follow_mode(&self, follow_mode: &str) {
+        let stmt = r#"
+            UPDATE ap_instance_actor SET follow_mode = $1;
+        "#;
+        self.query(stmt, &[&follow_mode])
+            .await
+            .expect("failed to set follow mode");
+    }
+}
+
+// relay subscriptions
+impl Sesh<'_> {
+    /// a repeated subscription replaces the follow it is undone by
+    pub async fn create_relay_subscription(&self, user: &User, activitypub_id: &Url) {
+        let stmt = r#"
+        INSERT INTO relay_subscriptions
+        (follower, follow_activitypub_id, published)
+        VALUES
+        ($1, $2, $3)
+        ON CONFLICT (follower) DO UPDATE
+        SET follow_activitypub_id = EXCLUDED.follow_activitypub_id;
+        "#;
+        let published = chrono::Utc::now().timestamp_millis();
+        self.query(stmt, &[&user.id, &activitypub_id.as_str(), &published])
+            .await
+            .expect("failed to create relay subscription");
+    }
+    /// returns false if the follow was not a relay subscription
+    pub async fn delete_relay_subscription(&self, activitypub_id: &Url) -> bool {
+        let stmt = r#"
+            DELETE FROM relay_subscriptions WHERE follow_activitypub_id = $1
+            RETURNING follower;
+        "#;
+        !self
+            .query(stmt, &[&activitypub_id.as_str()])
+            .await
+            .expect("failed to delete relay subscription")
+            .is_empty()
+    }
+    /// the domains of every instance with a relay subscription
+    pub async fn get_relay_subscriber_domains(&self) -> Vec<String> {
+        let stmt = r#"
+            SELECT DISTINCT users.domain FROM relay_subscriptions
+            INNER JOIN users ON relay_subscriptions.follower = users.uid;
+        "#;
+        self.query(stmt, &[])
+            .await
+            .expect("failed to fetch relay subscribers")
+            .into_iter()
+            .map(|row| row.get("domain"))
+            .collect()
     }
-}
\ No newline at end of file
+    pub async fn relay_unique_inboxes(&self) -> Vec<Url> {
+        let stmt = r#"
+            SELECT DISTINCT users.shared_inbox FROM relay_subscriptions
+            INNER JOIN users ON relay_subscriptions.follower = users.uid
+            INNER JOIN instances ON users.domain = instances.domain
+            WHERE NOT users.banned
+            AND NOT instances.blocked;
+        "#;
+        self.query(stmt, &[])
+            .await
+            .expect("failed to fetch relay inboxes")
+            .into_iter()
+            .filter_map(|row| {
+                let inbox: String = row.get("shared_inbox");
+                Url::parse(&inbox).ok()
+            })
+            .collect()
+    }
+    pub async fn add_relay_delivery(&self, pid: Uuid, inbox: &Url) {
+        let stmt = r#"
+        INSERT INTO relay_deliveries
+        (pid, inbox)
+        VALUES
+        ($1, $2)
+        ON CONFLICT DO NOTHING;
+        "#;
+        self.query(stmt, &[&pid, &inbox.as_str()])
+            .await
+            .expect("failed to add relay delivery");
+    }
+    /// the inboxes the instance actor's announce of the post was delivered to
+    pub async fn get_relay_deliveries(&self, pid: Uuid) -> Vec<Url> {
+        let stmt = r#"
+            SELECT inbox FROM relay_deliveries WHERE pid = $1;
+        "#;
+        self.query(stmt, &[&pid])
+            .await
+            .expect("failed to fetch relay deliveries")
+            .into_iter()
+            .filter_map(|row| {
+                let inbox: String = row.get("inbox");
+                Url::parse(&inbox).ok()
+            })
+            .collect()
+    }
+}
+
+// keywords
+impl Sesh<'_> {
+    pub async fn get_tag_keywords(&self) -> Vec<TagKeyword> {
+        let stmt = r#"
+            SELECT * FROM tag_keywords ORDER BY keyword_id;
+        "#;
+        self.query(stmt, &[])
+            .await
+            .expect("failed to fetch tag keywords")
+            .into_iter()
+            .map(|row| row.into())
+            .collect()
+    }
+    pub async fn get_keywords_of_tag(&self, tag: &Tag) -> Vec<TagKeyword> {
+        let stmt = r#"
+            SELECT * FROM tag_keywords WHERE tag = $1 ORDER BY keyword_id;
+        "#;
+        self.query(stmt, &[&tag.id])
+            .await
+            .expect("failed to fetch keywords of tag")
+            .into_iter()
+            .map(|row| row.into())
+            .collect()
+    }
+    /// the keyword should already be validated
+    pub async fn create_tag_keyword(&self, tag: &Tag, keyword: &Keyword) -> TagKeyword {
+        let stmt = r#"
+        INSERT INTO tag_keywords
+        (tag, kind, keyword, case_sensitive, created)
+        VALUES
+        ($1, $2, $3, $4, $5)
+        ON CONFLICT (tag, kind, keyword) DO UPDATE
+        SET case_sensitive = EXCLUDED.case_sensitive
+        RETURNING *;
+        "#;
+        let created = chrono::Utc::now().timestamp_millis();
+        let result = self
+            .query(
+                stmt,
+                &[
+                    &tag.id,
+                    &keyword.kind.stringify(),
+                    &keyword.text,
+                    &keyword.case_sensitive,
+                    &created,
+                ],
+            )
+            .await
+            .expect("failed to create tag keyword")
+            .pop()
+            .expect("creating tag keyword returned nothing");
+        result.into()
+    }
+    pub async fn delete_tag_keyword(&self, keyword_id: i64) {
+        let stmt = r#"
+            DELETE FROM tag_keywords WHERE keyword_id = $1;
+        "#;
+        self.query(stmt, &[&keyword_id])
+            .await
+            .expect("failed to delete tag keyword");
+    }
+}
+
+// recent posts
+impl Sesh<'_> {
+    /// a post that arrives again has been edited and replaces its text
+    pub async fn record_recent_post(&self, activitypub_id: &Url, actor: &Url, content: &str) {
+        let stmt = r#"
+        INSERT INTO recent_posts
+        (activitypub_id, actor, content, received)
+        VALUES
+        ($1, $2, $3, $4)
+        ON CONFLICT (activitypub_id) DO UPDATE
+        SET content = EXCLUDED.content, received = EXCLUDED.received;
+        "#;
+        let received = chrono::Utc::now().timestamp_millis();
+        self.query(
+            stmt,
+            &[
+                &activitypub_id.as_str(),
+                &actor.as_str(),
+                &content,
+                &received,
+            ],
+        )
+        .await
+        .expect("failed to record recent post");
+    }
+    /// newest first
+    pub async fn get_recent_posts(&self, limit: i64) -> Vec<RecentPost> {
+        let stmt = r#"
+            SELECT * FROM recent_posts ORDER BY received DESC LIMIT $1;
+        "#;
+        self.query(stmt, &[&limit])
+            .await
+            .expect("failed to fetch recent posts")
+            .into_iter()
+            .map(|row| row.into())
+            .collect()
+    }
+    pub async fn delete_recent_posts_by(&self, actor: &Url) {
+        let stmt = r#"
+            DELETE FROM recent_posts WHERE actor = $1;
+        "#;
+        self.query(stmt, &[&actor.as_str()])
+            .await
+            .expect("failed to delete recent posts");
+    }
+    pub async fn prune_recent_posts(&self, before: i64) -> usize {
+        let stmt = r#"
+            DELETE FROM recent_posts WHERE received < $1 RETURNING activitypub_id;
+        "#;
+        self.query(stmt, &[&before])
+            .await
+            .expect("failed to prune recent posts")
+            .len()
+    }
+}
+
+// policy skips
+impl Sesh<'_> {
+    /// a post skipped again, after an edit, keeps the latest reason
+    pub async fn record_policy_skip(&self, activitypub_id: &Url, tag: &Tag, reason: &str) {
+        let stmt = r#"
+        INSERT INTO policy_skips
+        (activitypub_id, tag, reason, skipped_at)
+        VALUES
+        ($1, $2, $3, $4)
+        ON CONFLICT (activitypub_id, tag) DO UPDATE
+        SET reason = EXCLUDED.reason, skipped_at = EXCLUDED.skipped_at
+        RETURNING tag;
+        "#;
+        let skipped_at = chrono::Utc::now().timestamp_millis();
+        self.query(
+            stmt,
+            &[&activitypub_id.as_str(), &tag.id, &reason, &skipped_at],
+        )
+        .await
+        .expect("failed to record policy skip");
+    }
+    /// newest first
+    pub async fn get_policy_skips(&self, tag: &Tag, limit: i64) -> Vec<SkippedPost> {
+        let stmt = r#"
+            SELECT * FROM policy_skips WHERE tag = $1
+            ORDER BY skipped_at DESC LIMIT $2;
+        "#;
+        self.query(stmt, &[&tag.id, &limit])
+            .await
+            .expect("failed to fetch policy skips")
+            .into_iter()
+            .map(|row| row.into())
+            .collect()
+    }
+    pub async fn prune_policy_skips(&self, before: i64) -> usize {
+        let stmt = r#"
+            DELETE FROM policy_skips WHERE skipped_at < $1 RETURNING tag;
+        "#;
+        self.query(stmt, &[&before])
+            .await
+            .expect("failed to prune policy skips")
+            .len()
+    }
+}
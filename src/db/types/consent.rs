@@ -0,0 +1,147 @@
+use crate::types::{actors::Actor, hashtag::merge_hashtags};
+
+/// the way a user has asked not to be boosted. the first
+/// one found on their profile is the one kept
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OptOut {
+    /// `#nobot` in the bio or a profile field
+    NoBot,
+    /// `#noboost` in the bio or a profile field
+    NoBoost,
+    /// misskey's `noindex`
+    NoIndex,
+    /// `discoverable` set to false
+    Undiscoverable,
+    /// `indexable` set to false, which mastodon defaults to, so it
+    /// is only honored when the config asks for it
+    Unindexable,
+}
+
+impl OptOut {
+    pub fn stringify(&self) -> &str {
+        match self {
+            OptOut::NoBot => "nobot",
+            OptOut::NoBoost => "noboost",
+            OptOut::NoIndex => "noindex",
+            OptOut::Undiscoverable => "undiscoverable",
+            OptOut::Unindexable => "unindexable",
+        }
+    }
+    /// how the opt out reads to the user it was found on
+    pub fn describe(&self) -> &str {
+        match self {
+            OptOut::NoBot => "your profile has #nobot",
+            OptOut::NoBoost => "your profile has #noboost",
+            OptOut::NoIndex => "your account is set to hide from search engines",
+            OptOut::Undiscoverable => "your account is set not to be featured or recommended",
+            OptOut::Unindexable => "your account is set not to be indexed for search",
+        }
+    }
+    /// the opt out of the actor if they have one
+    pub fn of(actor: &Actor) -> Option<OptOut> {
+        // the bio, and the names and values of the profile fields
+        let fields = actor.extensions.attachment();
+        let mut profile: Vec<&str> = actor.summary.iter().map(String::as_str).collect();
+        for field in &fields {
+            profile.extend(
+                ["name", "value"]
+                    .iter()
+                    .filter_map(|x| field.get(x)?.as_str()),
+            );
+        }
+        let hashtags = merge_hashtags(None, &profile);
+        let has = |name: &str| hashtags.iter().any(|x| x.name.eq_ignore_ascii_case(name));
+
+        if has("nobot") {
+            Some(OptOut::NoBot)
+        } else if has("noboost") {
+            Some(OptOut::NoBoost)
+        } else if actor.extensions.noindex() == Some(true) {
+            Some(OptOut::NoIndex)
+        } else if actor.extensions.discoverable() == Some(false) {
+            Some(OptOut::Undiscoverable)
+        } else if actor.extensions.indexable() == Some(false) {
+            Some(OptOut::Unindexable)
+        } else {
+            None
+        }
+    }
+}
+
+impl TryFrom<&str> for OptOut {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "nobot" => Ok(Self::NoBot),
+            "noboost" => Ok(Self::NoBoost),
+            "noindex" => Ok(Self::NoIndex),
+            "undiscoverable" => Ok(Self::Undiscoverable),
+            "unindexable" => Ok(Self::Unindexable),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::actors::Actor;
+
+    use super::OptOut;
+
+    fn actor(extra: &str) -> Result<Actor, String> {
+        let actor = format!(
+            r##"
+{{
+	"id": "https://mastodon.social/users/Mastodon",
+	"type": "Person",
+	"following": "https://mastodon.social/users/Mastodon/following",
+	"followers": "https://mastodon.social/users/Mastodon/followers",
+	"inbox": "https://mastodon.social/users/Mastodon/inbox",
+	"outbox": "https://mastodon.social/users/Mastodon/outbox",
+	"preferredUsername": "Mastodon",
+	"name": "Mastodon",
+	"publicKey": {{
+		"id": "https://mastodon.social/users/Mastodon#main-key",
+		"owner": "https://mastodon.social/users/Mastodon",
+		"publicKeyPem": "-----BEGIN PUBLIC KEY-----\nMIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEAtpNfuGPl/WTnSq3dTurF\nMRelAIdvGVkO/VKYZJvIleYA27/YTnpmlY2g+0Xxe1Xv/ZSMd9dl8Xad9kvcJPjH\nvJH7VdHp4cODWEWHm2DR3rEsfqsaaqgTvHn+J8VvQ2aQmMpDvY0/G+kScKnmlLZv\nmzYAWpFPPPZVnfOAjsZ7u/D3ScUqz9+Y3Uu8yNTNhEJqTi0TmDrXnfMOjWfASpmO\nFCeMGzPgfrpbTJmLMLD+tNDXtWD3d3LWUnNuDOHW7Dam3c0yhK1tVo6lUaIYF0YV\nfkFCJOF5qGQbxNi4eMu2XXqVAjBJ9e+k1m2gVcT4NfT8vTBAYXmMCrjA7DwRqVHF\nXwIDAQAB\n-----END PUBLIC KEY-----\n"
+	}},
+	{extra}
+	"discoverable": true
+}}
+        "##
+        );
+        serde_json::from_str(&actor).map_err(|x| x.to_string())
+    }
+
+    #[test]
+    fn opt_outs_are_found_on_the_profile() -> Result<(), String> {
+        let cases = [
+            ("", None),
+            (
+                r#""summary": "<p>I post about <a href=\"https://mastodon.social/tags/rust\" class=\"mention hashtag\" rel=\"tag\">#<span>rust</span></a></p>","#,
+                None,
+            ),
+            (
+                r#""summary": "<p>please no bots #NoBot</p>","#,
+                Some(OptOut::NoBot),
+            ),
+            (
+                r#""attachment": [{"type": "PropertyValue", "name": "boosts", "value": "<p>#noboost</p>"}],"#,
+                Some(OptOut::NoBoost),
+            ),
+            (r#""noindex": true,"#, Some(OptOut::NoIndex)),
+            (r#""indexable": false,"#, Some(OptOut::Unindexable)),
+        ];
+        for (extra, expected) in cases {
+            let found = OptOut::of(&actor(extra)?);
+            if found != expected {
+                return Err(format!(
+                    "expected {:?} from {}, got {:?}",
+                    expected, extra, found
+                ));
+            }
+        }
+        Ok(())
+    }
+}
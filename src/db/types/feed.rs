@@ -0,0 +1,248 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+/// the boolean expression a feed matches posts against, for example
+/// `#rust AND (#async OR #tokio) AND NOT #nsfw`
+///
+/// `NOT` binds tighter than `AND` which binds tighter than `OR`.
+/// operators are case insensitive and the `#` of tags is optional
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum FeedExpr {
+    /// always lowercase
+    Tag(String),
+    Not(Box<FeedExpr>),
+    And(Box<FeedExpr>, Box<FeedExpr>),
+    Or(Box<FeedExpr>, Box<FeedExpr>),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum FeedErr {
+    /// the expression could not be parsed, with what went wrong
+    InvalidExpression(String),
+    /// feed names have to be valid tag names
+    InvalidName,
+    /// the name belongs to a tag that is an alias or already has followers
+    NameTaken,
+}
+
+impl std::fmt::Display for FeedErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FeedErr::InvalidExpression(x) => write!(f, "InvalidExpression: {}", x),
+            FeedErr::InvalidName => write!(f, "InvalidName"),
+            FeedErr::NameTaken => write!(f, "NameTaken"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Tag(String),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, FeedErr> {
+    let mut tokens = Vec::new();
+    let spaced = input.replace('(', " ( ").replace(')', " ) ");
+    for word in spaced.split_whitespace() {
+        let token = match word.to_lowercase().as_str() {
+            "(" => Token::Open,
+            ")" => Token::Close,
+            "and" => Token::And,
+            "or" => Token::Or,
+            "not" => Token::Not,
+            word => {
+                let tag = word.strip_prefix('#').unwrap_or(word);
+                if tag.is_empty() || !tag.chars().all(char::is_alphanumeric) {
+                    return Err(FeedErr::InvalidExpression(format!("invalid tag {word}")));
+                }
+                Token::Tag(tag.to_string())
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next_is(&self, token: Token) -> bool {
+        self.tokens.get(self.position).is_some_and(|x| x.eq(&token))
+    }
+    fn or(&mut self) -> Result<FeedExpr, FeedErr> {
+        let mut expr = self.and()?;
+        while self.next_is(Token::Or) {
+            self.position += 1;
+            expr = FeedExpr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+    fn and(&mut self) -> Result<FeedExpr, FeedErr> {
+        let mut expr = self.unary()?;
+        while self.next_is(Token::And) {
+            self.position += 1;
+            expr = FeedExpr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+    fn unary(&mut self) -> Result<FeedExpr, FeedErr> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        match token {
+            Some(Token::Not) => Ok(FeedExpr::Not(Box::new(self.unary()?))),
+            Some(Token::Tag(tag)) => Ok(FeedExpr::Tag(tag.clone())),
+            Some(Token::Open) => {
+                let expr = self.or()?;
+                match self.next_is(Token::Close) {
+                    true => {
+                        self.position += 1;
+                        Ok(expr)
+                    }
+                    false => Err(FeedErr::InvalidExpression("unclosed (".to_string())),
+                }
+            }
+            Some(x) => Err(FeedErr::InvalidExpression(format!("unexpected {x:?}"))),
+            None => Err(FeedErr::InvalidExpression(
+                "expression ends early".to_string(),
+            )),
+        }
+    }
+}
+
+impl FeedExpr {
+    pub fn parse(input: &str) -> Result<Self, FeedErr> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        };
+        let expr = parser.or()?;
+        match parser.tokens.get(parser.position) {
+            None => Ok(expr),
+            Some(x) => Err(FeedErr::InvalidExpression(format!("unexpected {x:?}"))),
+        }
+    }
+    /// whether a post with these lowercase tag names matches
+    pub fn matches(&self, tags: &HashSet<String>) -> bool {
+        match self {
+            FeedExpr::Tag(tag) => tags.contains(tag),
+            FeedExpr::Not(expr) => !expr.matches(tags),
+            FeedExpr::And(a, b) => a.matches(tags) && b.matches(tags),
+            FeedExpr::Or(a, b) => a.matches(tags) || b.matches(tags),
+        }
+    }
+    /// the tags a post has to contain for it to match, rather than
+    /// the ones it must not. following one of them counts as
+    /// following the feed
+    pub fn included_tags(&self) -> Vec<&str> {
+        match self {
+            FeedExpr::Tag(tag) => vec![tag.as_str()],
+            FeedExpr::Not(_) => Vec::new(),
+            FeedExpr::And(a, b) | FeedExpr::Or(a, b) => {
+                let mut tags = a.included_tags();
+                tags.extend(b.included_tags());
+                tags
+            }
+        }
+    }
+    fn precedence(&self) -> u8 {
+        match self {
+            FeedExpr::Or(..) => 0,
+            FeedExpr::And(..) => 1,
+            FeedExpr::Not(_) | FeedExpr::Tag(_) => 2,
+        }
+    }
+    fn fmt_operand(&self, f: &mut std::fmt::Formatter<'_>, min: u8) -> std::fmt::Result {
+        match self.precedence() < min {
+            true => write!(f, "({})", self),
+            false => write!(f, "{}", self),
+        }
+    }
+}
+
+/// the expression in the form it is stored in
+impl std::fmt::Display for FeedExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FeedExpr::Tag(tag) => write!(f, "#{tag}"),
+            FeedExpr::Not(expr) => {
+                write!(f, "NOT ")?;
+                expr.fmt_operand(f, 2)
+            }
+            FeedExpr::And(a, b) => {
+                a.fmt_operand(f, 1)?;
+                write!(f, " AND ")?;
+                b.fmt_operand(f, 2)
+            }
+            FeedExpr::Or(a, b) => {
+                a.fmt_operand(f, 0)?;
+                write!(f, " OR ")?;
+                b.fmt_operand(f, 1)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn test_feed_expression() -> Result<(), String> {
+        let expr = FeedExpr::parse("#Rust and (#async OR tokio) AND NOT #nsfw")
+            .map_err(|x| x.to_string())?;
+        let matching = [vec!["rust", "async"], vec!["rust", "tokio", "axum"]];
+        let not_matching = [
+            vec!["rust"],
+            vec!["async", "tokio"],
+            vec!["rust", "async", "nsfw"],
+        ];
+        for post in matching {
+            if !expr.matches(&tags(&post)) {
+                return Err(format!("{post:?} should match {expr}"));
+            }
+        }
+        for post in not_matching {
+            if expr.matches(&tags(&post)) {
+                return Err(format!("{post:?} should not match {expr}"));
+            }
+        }
+        let stored = expr.to_string();
+        if stored.ne("#rust AND (#async OR #tokio) AND NOT #nsfw") {
+            return Err(format!("unexpected stored form {stored}"));
+        }
+        match FeedExpr::parse(&stored) {
+            Ok(reparsed) if reparsed.eq(&expr) => Ok(()),
+            x => Err(format!("stored form reparsed to {x:?}")),
+        }
+    }
+
+    #[test]
+    fn test_invalid_feed_expression() -> Result<(), String> {
+        for input in [
+            "",
+            "#rust AND",
+            "(#rust OR #go",
+            "#rust #go",
+            "#c++",
+            "OR #go",
+        ] {
+            if let Ok(x) = FeedExpr::parse(input) {
+                return Err(format!("{input:?} should not parse but gave {x}"));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// a verified activity waiting to be processed
+pub struct QueuedActivity {
+    pub id: Uuid,
+    /// the body of the activity as it was received
+    pub activity: String,
+    /// the domain that signed the activity, which it is verified against
+    /// again when processed. activities queued before the origin was kept
+    /// have none and are a [`crate::types::inboxable::VerifiedInboxable`]
+    pub origin: Option<String>,
+    pub received: i64,
+    /// includes the attempt currently being made
+    pub attempts: i32,
+    pub next_attempt: i64,
+    pub last_error: Option<String>,
+}
+
+impl From<tokio_postgres::Row> for QueuedActivity {
+    fn from(row: tokio_postgres::Row) -> Self {
+        QueuedActivity {
+            id: row.get("qid"),
+            activity: row.get("activity"),
+            origin: row.get("origin"),
+            received: row.get("received"),
+            attempts: row.get("attempts"),
+            next_attempt: row.get("next_attempt"),
+            last_error: row.get("last_error"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct QueueDepth {
+    /// activities that have not been attempted yet
+    pub pending: i64,
+    /// activities that are being processed or have failed at least once
+    pub retrying: i64,
+    pub dead_letters: i64,
+}
+
+impl From<tokio_postgres::Row> for QueueDepth {
+    fn from(row: tokio_postgres::Row) -> Self {
+        QueueDepth {
+            pending: row.get("pending"),
+            retrying: row.get("retrying"),
+            dead_letters: row.get("dead_letters"),
+        }
+    }
+}
+
+/// how often the inbox has accepted activities of a type
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ActivityCount {
+    pub kind: String,
+    /// false for activities that are only acknowledged
+    pub supported: bool,
+    pub received: i64,
+    pub last_received: i64,
+}
+
+impl From<tokio_postgres::Row> for ActivityCount {
+    fn from(row: tokio_postgres::Row) -> Self {
+        ActivityCount {
+            kind: row.get("kind"),
+            supported: row.get("supported"),
+            received: row.get("received"),
+            last_received: row.get("last_received"),
+        }
+    }
+}
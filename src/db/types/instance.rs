@@ -1 +1,21 @@
-pub struct Instance {}
\ No newline at end of file
+use uuid::Uuid;
+
+pub struct Instance {
+    pub id: Uuid,
+    pub domain: String,
+    pub blocked: bool,
+    pub reason: Option<String>,
+    pub allowlisted: bool,
+}
+
+impl From<tokio_postgres::Row> for Instance {
+    fn from(row: tokio_postgres::Row) -> Self {
+        Instance {
+            id: row.get("i_id"),
+            domain: row.get("domain"),
+            blocked: row.get("blocked"),
+            reason: row.get("reason"),
+            allowlisted: row.get("allowlisted"),
+        }
+    }
+}
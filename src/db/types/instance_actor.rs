@@ -1,6 +1,17 @@
 use url::Url;
+use uuid::Uuid;
 
-use crate::{cryptography::key::Algorithms, types::actors::Actor};
+use crate::{
+    cryptography::{
+        key::{Algorithms, Key, PrivateKey},
+        universal_keys::{UniversalPrivate, UniversalPublic},
+    },
+    types::{
+        actors::{Actor, ActorType, Endpoints},
+        extensions::Extensions,
+        public_key::ApPublicKey,
+    },
+};
 
 pub struct InstanceActor {
     pub private_key_pem: String,
@@ -15,36 +26,68 @@ impl InstanceActor {
     pub fn get_key_id(domain: &str) -> String {
         format!("https://{}/{}", domain, domain)
     }
-    pub fn activitypub_id(_domain: &str) -> Url {
-        todo!()
-    }
-    pub fn to_actor(&self, _domain: &str) -> Actor {
-        //     // let links = instance_actor_links(domain);
-        //     todo!()
-        //     // Actor {
-        //     //     type_field: crate::activitystream_objects::actors::ActorType::Application,
-        //     //     id: links.id.clone(),
-        //     //     preferred_username: domain.to_string(),
-        //     //     summary: None,
-        //     //     name: None,
-        //     //     url: Some(
-        //     //         Url::parse(&format!("https://{domain}/about/more?instance_actor=true")).unwrap(),
-        //     //     ),
-        //     //     public_key: PublicKey {
-        //     //         id: links.pub_key_id,
-        //     //         owner: links.id,
-        //     //         public_key_pem: self.public_key_pem.clone(),
-        //     //     },
-        //     //     inbox: links.inbox,
-        //     //     outbox: links.outbox,
-        //     //     followers: links.followers,
-        //     //     following: links.following,
-        //     //     domain: Some(domain.to_string()),
-        //     //     liked: Some(links.liked),
-        //     // }
-        todo!()
+    pub fn activitypub_id(domain: &str) -> Url {
+        Url::parse(&format!("https://{domain}/actor/ap"))
+            .expect("generated invalid instance actor id")
+    }
+    /// the instance actor's announce of a post to relay subscribers
+    pub fn announce_id(domain: &str, pid: Uuid) -> Url {
+        Url::parse(&format!(
+            "{}/announces/{pid}",
+            InstanceActor::activitypub_id(domain)
+        ))
+        .expect("generated invalid announce id")
+    }
+    /// the shared inbox used by the instance actor and every tag actor
+    pub fn shared_inbox(domain: &str) -> Url {
+        Url::parse(&format!("https://{domain}/inbox")).expect("generated invalid shared inbox")
+    }
+    pub fn private_key(&self) -> UniversalPrivate {
+        UniversalPrivate::from_pem(&self.private_key_pem).expect("invalid private key in db")
+    }
+    pub fn public_key(&self) -> UniversalPublic {
+        UniversalPublic::from_pem(&self.public_key_pem).expect("invalid public key in db")
+    }
+    pub fn to_actor(&self, domain: &str) -> Actor {
+        let id = InstanceActor::activitypub_id(domain);
+        Actor {
+            type_field: ActorType::Application,
+            id: id.clone(),
+            preferred_username: domain.to_string(),
+            summary: None,
+            name: None,
+            url: Some(
+                Url::parse(&format!("https://{domain}/about/more?instance_actor=true")).unwrap(),
+            ),
+            public_key: ApPublicKey {
+                id: Url::parse(&InstanceActor::pub_key_id(domain)).unwrap(),
+                owner: id.clone(),
+                public_key_pem: self.public_key(),
+            },
+            inbox: Url::parse(&format!("{id}/inbox")).unwrap(),
+            outbox: Url::parse(&format!("{id}/outbox")).unwrap(),
+            followers: Some(Url::parse(&format!("{id}/followers")).unwrap()),
+            following: Some(Url::parse(&format!("{id}/following")).unwrap()),
+            endpoints: Some(Endpoints {
+                shared_inbox: Some(InstanceActor::shared_inbox(domain)),
+            }),
+            also_known_as: None,
+            moved_to: None,
+            versia_url: None,
+            attributed_to: None,
+            moderators: None,
+            posting_restricted_to_mods: None,
+            extensions: Extensions::default(),
+        }
     }
     pub fn new(algo: Algorithms) -> Self {
-        todo!()
+        let private_key = UniversalPrivate::generate(algo);
+        InstanceActor {
+            private_key_pem: private_key.to_pem().expect("failed to encode private key"),
+            public_key_pem: private_key
+                .public_key_pem()
+                .expect("failed to encode public key"),
+            algorithm: algo,
+        }
     }
 }
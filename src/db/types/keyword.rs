@@ -0,0 +1,205 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeywordKind {
+    /// matches the words in order anywhere, even inside other words
+    Phrase,
+    /// matches the words in order only where they start and end a word.
+    /// hashtags and mentions of the word are left to tag matching
+    Word,
+}
+
+impl KeywordKind {
+    pub fn stringify(&self) -> &str {
+        match self {
+            KeywordKind::Phrase => "phrase",
+            KeywordKind::Word => "word",
+        }
+    }
+}
+
+impl TryFrom<&str> for KeywordKind {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "phrase" => Ok(Self::Phrase),
+            "word" => Ok(Self::Word),
+            _ => Err(()),
+        }
+    }
+}
+
+/// what a tag or feed looks for in the text of posts. any amount
+/// of whitespace between the words of a keyword matches
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Keyword {
+    pub kind: KeywordKind,
+    pub text: String,
+    pub case_sensitive: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TagKeyword {
+    pub id: i64,
+    pub tag: i64,
+    pub keyword: Keyword,
+    pub created: i64,
+}
+
+impl From<tokio_postgres::Row> for TagKeyword {
+    fn from(row: tokio_postgres::Row) -> Self {
+        let kind: String = row.get("kind");
+        TagKeyword {
+            id: row.get("keyword_id"),
+            tag: row.get("tag"),
+            keyword: Keyword {
+                kind: KeywordKind::try_from(kind.as_str()).expect("unknown keyword kind in db"),
+                text: row.get("keyword"),
+                case_sensitive: row.get("case_sensitive"),
+            },
+            created: row.get("created"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum KeywordErr {
+    EmptyKeyword,
+}
+
+impl std::fmt::Display for KeywordErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeywordErr::EmptyKeyword => write!(f, "EmptyKeyword"),
+        }
+    }
+}
+
+/// collapses the whitespace of a keyword before it is stored
+pub fn validate_keyword(text: &str) -> Result<String, KeywordErr> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.is_empty() {
+        true => Err(KeywordErr::EmptyKeyword),
+        false => Ok(text),
+    }
+}
+
+impl Keyword {
+    fn compile(&self) -> Result<Regex, regex::Error> {
+        let words = self
+            .text
+            .split_whitespace()
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join(r"\s+");
+        // \b would not match around keywords like c++ that end in symbols
+        let pattern = match self.kind {
+            KeywordKind::Phrase => words,
+            KeywordKind::Word => format!(r"(?:^|[^\w#@]){words}(?:$|[^\w])"),
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .size_limit(1 << 20)
+            .build()
+    }
+}
+
+/// compiled keywords, ready to be checked against the text of posts
+pub struct Keywords<T> {
+    rules: Vec<(T, Regex)>,
+}
+
+impl<T> Keywords<T> {
+    /// keywords that fail to compile are skipped
+    pub fn new(rules: Vec<T>, keyword: impl Fn(&T) -> &Keyword) -> Self {
+        let rules = rules
+            .into_iter()
+            .filter_map(|rule| {
+                let regex = keyword(&rule).compile().ok()?;
+                Some((rule, regex))
+            })
+            .collect();
+        Keywords { rules }
+    }
+    /// every rule with a keyword found in the text
+    pub fn matching<'a>(&'a self, text: &'a str) -> impl Iterator<Item = &'a T> + 'a {
+        self.rules
+            .iter()
+            .filter(move |(_, regex)| regex.is_match(text))
+            .map(|(rule, _)| rule)
+    }
+}
+
+/// the text of a public post we recently received
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecentPost {
+    pub activitypub_id: Url,
+    pub actor: Url,
+    pub content: String,
+    pub received: i64,
+}
+
+impl From<tokio_postgres::Row> for RecentPost {
+    fn from(row: tokio_postgres::Row) -> Self {
+        let activitypub_id: String = row.get("activitypub_id");
+        let actor: String = row.get("actor");
+        RecentPost {
+            activitypub_id: Url::parse(&activitypub_id).expect("invalid url in db"),
+            actor: Url::parse(&actor).expect("invalid url in db"),
+            content: row.get("content"),
+            received: row.get("received"),
+        }
+    }
+}
+
+/// a recently received post that keywords would have matched
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KeywordPreview {
+    pub post: RecentPost,
+    /// the text of each keyword it matched
+    pub matched: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyword(kind: KeywordKind, text: &str, case_sensitive: bool) -> Keyword {
+        Keyword {
+            kind,
+            text: validate_keyword(text).unwrap(),
+            case_sensitive,
+        }
+    }
+
+    #[test]
+    fn test_keywords() -> Result<(), String> {
+        let keywords = Keywords::new(
+            vec![
+                keyword(KeywordKind::Word, "  borrow   checker ", false),
+                keyword(KeywordKind::Word, "C++", false),
+                keyword(KeywordKind::Phrase, "tokio", false),
+                keyword(KeywordKind::Word, "Rust", true),
+            ],
+            |x| x,
+        );
+        let cases = [
+            ("fighting the Borrow\nChecker again", vec!["borrow checker"]),
+            ("borrow checkers", vec![]),
+            ("modern c++, finally", vec!["C++"]),
+            ("tokio-rs and mytokio", vec!["tokio"]),
+            ("Rust, not rust or #Rust", vec!["Rust"]),
+            ("rusty rust", vec![]),
+        ];
+        for (text, expected) in cases {
+            let matched: Vec<_> = keywords.matching(text).map(|x| x.text.as_str()).collect();
+            if matched.ne(&expected) {
+                return Err(format!("{text:?} matched {matched:?}"));
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,13 @@
+pub mod consent;
+pub mod feed;
+pub mod inbox_queue;
+pub mod instance;
 pub mod instance_actor;
+pub mod keyword;
+pub mod post;
+pub mod relay_export;
+pub mod repost;
 pub mod tag;
+pub mod tag_ban;
+pub mod tag_policy;
 pub mod user;
-pub mod instance;
\ No newline at end of file
@@ -0,0 +1,26 @@
+use url::Url;
+use uuid::Uuid;
+
+pub struct Post {
+    pub id: Uuid,
+    pub activitypub_id: Url,
+    pub domain: String,
+    pub published: i64,
+    pub fetched_at: Option<i64>,
+    /// the uid of the author
+    pub actor: Uuid,
+}
+
+impl From<tokio_postgres::Row> for Post {
+    fn from(row: tokio_postgres::Row) -> Self {
+        let activitypub_id: String = row.get("activitypub_id");
+        Post {
+            id: row.get("pid"),
+            activitypub_id: Url::parse(&activitypub_id).expect("invalid url in db"),
+            domain: row.get("domain"),
+            published: row.get("published"),
+            fetched_at: row.get("fetched_at"),
+            actor: row.get("actor"),
+        }
+    }
+}
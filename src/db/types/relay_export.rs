@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{
+    repost::{AnnouncePolicy, QuotePolicy},
+    tag::Tag,
+    tag_ban::{validate_pattern, TagBanKind, TagBanRule},
+    tag_policy::TagPolicy,
+};
+
+/// the state of every tag actor, used to carry the relay over to a new
+/// domain. followers are listed for reference only, they follow the new
+/// tag actors themselves once they receive the move
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RelayExport {
+    /// the domain the export was taken from
+    pub domain: String,
+    pub tags: Vec<TagExport>,
+    pub tag_ban_rules: Vec<TagBanRuleExport>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TagExport {
+    pub name: String,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub banned: bool,
+    /// the name of the canonical tag if this tag is an alias
+    pub alias_of: Option<String>,
+    /// the expression if the tag is a feed
+    #[serde(default)]
+    pub expression: Option<String>,
+    #[serde(default)]
+    pub announce_policy: AnnouncePolicy,
+    #[serde(default)]
+    pub quote_policy: QuotePolicy,
+    #[serde(default)]
+    pub policy: TagPolicy,
+    pub followers: Vec<Url>,
+}
+
+impl TagExport {
+    /// `alias_of` is the name of the canonical tag
+    pub fn new(tag: &Tag, alias_of: Option<String>, followers: Vec<Url>) -> Self {
+        TagExport {
+            name: tag.name.clone(),
+            display_name: tag.display_name.clone(),
+            bio: tag.bio.clone(),
+            banned: tag.banned,
+            alias_of,
+            expression: tag.expression.clone(),
+            announce_policy: tag.announce_policy,
+            quote_policy: tag.quote_policy,
+            policy: tag.policy.clone(),
+            followers,
+        }
+    }
+    /// gives the tag the exported settings, aliases are set
+    /// once every tag has been imported
+    pub fn apply(&self, tag: &mut Tag) {
+        tag.display_name = self.display_name.clone();
+        tag.bio = self.bio.clone();
+        tag.banned = self.banned;
+        tag.expression = self.expression.clone();
+        tag.announce_policy = self.announce_policy;
+        tag.quote_policy = self.quote_policy;
+        tag.policy = self.policy.clone();
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TagBanRuleExport {
+    pub kind: String,
+    pub pattern: String,
+    pub reason: Option<String>,
+}
+
+impl From<TagBanRule> for TagBanRuleExport {
+    fn from(rule: TagBanRule) -> Self {
+        TagBanRuleExport {
+            kind: rule.kind.stringify().to_string(),
+            pattern: rule.pattern,
+            reason: rule.reason,
+        }
+    }
+}
+
+impl TagBanRuleExport {
+    /// the kind and pattern of the rule, none if either is no longer valid
+    pub fn validated(&self) -> Option<(TagBanKind, String)> {
+        let kind = TagBanKind::try_from(self.kind.as_str()).ok()?;
+        let pattern = validate_pattern(kind, &self.pattern).ok()?;
+        Some((kind, pattern))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::types::{
+        repost::AnnouncePolicy,
+        tag::{test_tag, Tag},
+        tag_ban::{TagBanKind, TagBanRule},
+        tag_policy::{Filter, TagPolicy},
+    };
+
+    use super::{RelayExport, TagBanRuleExport, TagExport};
+
+    #[test]
+    fn exports_import_to_the_same_tags() -> Result<(), String> {
+        let rust = Tag {
+            display_name: Some("Rust".to_string()),
+            bio: Some("posts about rust".to_string()),
+            announce_policy: AnnouncePolicy::BoostOriginal,
+            policy: TagPolicy {
+                media: Filter::Only,
+                ..TagPolicy::default()
+            },
+            ..test_tag("rust")
+        };
+        let rustlang = Tag {
+            id: 2,
+            ..test_tag("rustlang")
+        };
+        let rule = TagBanRule {
+            id: 1,
+            kind: TagBanKind::Prefix,
+            pattern: "spam".to_string(),
+            reason: Some("spam".to_string()),
+            created: 0,
+        };
+        let export = RelayExport {
+            domain: "old.example".to_string(),
+            tags: vec![
+                TagExport::new(&rust, None, Vec::new()),
+                TagExport::new(&rustlang, Some("rust".to_string()), Vec::new()),
+            ],
+            tag_ban_rules: vec![rule.into()],
+        };
+        let json = serde_json::to_string(&export).map_err(|x| x.to_string())?;
+        let imported: RelayExport = serde_json::from_str(&json).map_err(|x| x.to_string())?;
+
+        // imported onto fresh tags on the new relay
+        let tags: Vec<TagExport> = imported
+            .tags
+            .iter()
+            .enumerate()
+            .map(|(id, exported)| {
+                let mut fresh = Tag {
+                    id: id as i64 + 10,
+                    ..test_tag(&exported.name)
+                };
+                exported.apply(&mut fresh);
+                TagExport::new(&fresh, exported.alias_of.clone(), Vec::new())
+            })
+            .collect();
+        let rules: Vec<TagBanRuleExport> = imported
+            .tag_ban_rules
+            .iter()
+            .filter_map(|rule| {
+                let (kind, pattern) = rule.validated()?;
+                Some(
+                    TagBanRule {
+                        id: 2,
+                        kind,
+                        pattern,
+                        reason: rule.reason.clone(),
+                        created: 1,
+                    }
+                    .into(),
+                )
+            })
+            .collect();
+        let reexport = RelayExport {
+            domain: "old.example".to_string(),
+            tags,
+            tag_ban_rules: rules,
+        };
+        let rejson = serde_json::to_string(&reexport).map_err(|x| x.to_string())?;
+        match json == rejson {
+            true => Ok(()),
+            false => Err(format!("{json} imported as {rejson}")),
+        }
+    }
+}
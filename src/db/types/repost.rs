@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+/// what a tag does when one of its followers boosts a post
+/// carrying the tag that it has not boosted itself
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnouncePolicy {
+    #[default]
+    Ignore,
+    /// the boosted post is fetched and boosted by the tag
+    BoostOriginal,
+}
+
+impl AnnouncePolicy {
+    pub fn stringify(&self) -> &str {
+        match self {
+            AnnouncePolicy::Ignore => "ignore",
+            AnnouncePolicy::BoostOriginal => "boost_original",
+        }
+    }
+}
+
+impl TryFrom<&str> for AnnouncePolicy {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "ignore" => Ok(Self::Ignore),
+            "boost_original" => Ok(Self::BoostOriginal),
+            _ => Err(()),
+        }
+    }
+}
+
+/// what a tag does when one of its followers quotes a post carrying
+/// the tag. a quote with hashtags of its own is boosted for those
+/// hashtags as usual regardless
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotePolicy {
+    #[default]
+    Ignore,
+    /// the quoted post is boosted by the tag, unless it already was
+    BoostOriginal,
+    /// the quote is boosted by the tag as if it carried the tag
+    BoostQuote,
+}
+
+impl QuotePolicy {
+    pub fn stringify(&self) -> &str {
+        match self {
+            QuotePolicy::Ignore => "ignore",
+            QuotePolicy::BoostOriginal => "boost_original",
+            QuotePolicy::BoostQuote => "boost_quote",
+        }
+    }
+}
+
+impl TryFrom<&str> for QuotePolicy {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "ignore" => Ok(Self::Ignore),
+            "boost_original" => Ok(Self::BoostOriginal),
+            "boost_quote" => Ok(Self::BoostQuote),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AnnouncePolicy, QuotePolicy};
+
+    #[test]
+    fn policies_round_trip_through_their_column() -> Result<(), String> {
+        for policy in [AnnouncePolicy::Ignore, AnnouncePolicy::BoostOriginal] {
+            if AnnouncePolicy::try_from(policy.stringify()) != Ok(policy) {
+                return Err(format!("{:?} did not round trip", policy));
+            }
+        }
+        for policy in [
+            QuotePolicy::Ignore,
+            QuotePolicy::BoostOriginal,
+            QuotePolicy::BoostQuote,
+        ] {
+            let json = serde_json::to_string(&policy).map_err(|x| x.to_string())?;
+            if QuotePolicy::try_from(policy.stringify()) != Ok(policy)
+                || json != format!("\"{}\"", policy.stringify())
+            {
+                return Err(format!("{:?} did not round trip", policy));
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,32 +1,288 @@
+use serde::{Deserialize, Serialize};
 use url::Url;
+use uuid::Uuid;
 
-use crate::types::actors::Actor;
+use crate::types::{
+    actors::{Actor, ActorType, Endpoints},
+    core_types::OptionalArray,
+    extensions::Extensions,
+    language::{is_language_code, language_matches},
+    link::{Link, LinkType},
+    public_key::ApPublicKey,
+    publication::AttributedTo,
+};
 
+use super::{
+    feed::FeedExpr,
+    instance_actor::InstanceActor,
+    repost::{AnnouncePolicy, QuotePolicy},
+    tag_policy::TagPolicy,
+};
+
+#[derive(Clone)]
 pub struct Tag {
     pub id: i64,
     pub name: String,
     pub display_name: Option<String>,
     pub bio: Option<String>,
     pub banned: bool,
+    /// the canonical tag if this tag is an alias
+    pub alias_of: Option<i64>,
+    /// set when this is a feed rather than a plain tag
+    pub expression: Option<String>,
+    pub announce_policy: AnnouncePolicy,
+    pub quote_policy: QuotePolicy,
+    pub policy: TagPolicy,
+}
+
+/// a plain tag with every setting left at its default
+#[cfg(test)]
+pub fn test_tag(name: &str) -> Tag {
+    Tag {
+        id: 1,
+        name: name.to_string(),
+        display_name: None,
+        bio: None,
+        banned: false,
+        alias_of: None,
+        expression: None,
+        announce_policy: AnnouncePolicy::Ignore,
+        quote_policy: QuotePolicy::Ignore,
+        policy: TagPolicy::default(),
+    }
 }
 
 impl Tag {
-    pub fn to_actor(&self, _domain: &str) -> Actor {
-        todo!()
+    /// all tag actors share the instance actor's keypair. groups are
+    /// moderated by the instance actor and open to anyone's posts
+    pub fn to_actor(&self, domain: &str, instance_actor: &InstanceActor, group: bool) -> Actor {
+        let id = self.activitypub_id(domain);
+        let display_name = self.display_name.clone().unwrap_or(self.name.clone());
+        Actor {
+            type_field: match group {
+                true => ActorType::Group,
+                false => ActorType::Service,
+            },
+            id: id.clone(),
+            preferred_username: self.name.clone(),
+            summary: Some(self.bio.clone().unwrap_or(match &self.expression {
+                Some(expression) => format!("boosts follower's posts matching {expression}"),
+                None => match self.language() {
+                    Some(language) => format!(
+                        "boosts follower's posts in {language} that contain #{}",
+                        self.base_name()
+                    ),
+                    None => format!("boosts follower's posts that contain #{display_name}"),
+                },
+            })),
+            name: Some(format!("#{display_name}")),
+            url: Some(Url::parse(&format!("https://{domain}/@{}", self.name)).unwrap()),
+            public_key: ApPublicKey {
+                id: Url::parse(&self.pub_key_id(domain)).unwrap(),
+                owner: id.clone(),
+                public_key_pem: instance_actor.public_key(),
+            },
+            inbox: Url::parse(&format!("{id}/inbox")).unwrap(),
+            outbox: self.outbox(domain),
+            followers: Some(self.followers(domain)),
+            following: Some(Url::parse(&format!("{id}/following")).unwrap()),
+            endpoints: Some(Endpoints {
+                shared_inbox: Some(InstanceActor::shared_inbox(domain)),
+            }),
+            also_known_as: None,
+            moved_to: None,
+            versia_url: None,
+            attributed_to: group
+                .then(|| OptionalArray::Single(AttributedTo::Id(self.moderators(domain)))),
+            moderators: group.then(|| self.moderators(domain)),
+            posting_restricted_to_mods: group.then_some(false),
+            extensions: Extensions::default(),
+        }
+    }
+    pub fn activitypub_id(&self, domain: &str) -> Url {
+        Url::parse(&format!("https://{domain}/tags/{}", self.name))
+            .expect("generated invalid tag id")
+    }
+    /// the tag as it appears in the `tag` field of an activity
+    pub fn hashtag(&self, domain: &str) -> Link {
+        Link {
+            type_field: LinkType::Hashtag,
+            href: self.activitypub_id(domain),
+            name: Some(format!(
+                "#{}",
+                self.display_name.as_deref().unwrap_or(&self.name)
+            )),
+            hreflang: None,
+            media_type: None,
+            rel: None,
+            height: None,
+            width: None,
+        }
+    }
+    pub fn pub_key_id(&self, domain: &str) -> String {
+        format!("{}#main-key", self.activitypub_id(domain))
+    }
+    /// the id of this tag's announce of a post
+    pub fn announce_id(&self, domain: &str, pid: Uuid) -> Url {
+        Url::parse(&format!("{}/announces/{pid}", self.activitypub_id(domain))).unwrap()
+    }
+    pub fn outbox(&self, domain: &str) -> Url {
+        Url::parse(&format!("{}/outbox", self.activitypub_id(domain))).unwrap()
+    }
+    pub fn followers(&self, domain: &str) -> Url {
+        Url::parse(&format!("{}/followers", self.activitypub_id(domain))).unwrap()
+    }
+    pub fn moderators(&self, domain: &str) -> Url {
+        Url::parse(&format!("{}/moderators", self.activitypub_id(domain))).unwrap()
+    }
+    /// the parsed expression if this is a feed. feeds with an
+    /// expression that no longer parses match nothing
+    pub fn feed(&self) -> Option<FeedExpr> {
+        FeedExpr::parse(self.expression.as_deref()?).ok()
+    }
+    /// the language a scoped tag like `rust.de` only boosts posts in
+    pub fn language(&self) -> Option<&str> {
+        Tag::split_scope(&self.name).1
+    }
+    /// the tag a scoped tag narrows down, the tag itself when unscoped
+    pub fn base_name(&self) -> &str {
+        Tag::split_scope(&self.name).0
+    }
+    /// splits a tag name into the tag it narrows down and its language
+    pub fn split_scope(name: &str) -> (&str, Option<&str>) {
+        match name.split_once('.') {
+            Some((base, language)) if is_language_code(language) => (base, Some(language)),
+            _ => (name, None),
+        }
+    }
+    /// if a post in `languages` is one the tag boosts, unscoped tags boost any
+    pub fn in_scope(&self, languages: &[String]) -> bool {
+        self.language()
+            .is_none_or(|scope| languages.iter().any(|x| language_matches(scope, x)))
+    }
+    pub fn scoped_name(name: &str, language: &str) -> String {
+        format!("{}.{}", name, language).to_lowercase()
     }
-    pub fn activitypub_id(&self, _domain: &str) -> Url {
-        todo!()
+    /// gets the name of the tag an activitypub id on this instance refers to,
+    /// a `lang` parameter refers to the tag scoped to that language
+    pub fn name_from_activitypub_id(activitypub_id: &Url, domain: &str) -> Option<String> {
+        if activitypub_id.domain().ne(&Some(domain)) {
+            return None;
+        }
+        let name = activitypub_id.path().strip_prefix("/tags/")?;
+        if name.is_empty() || name.contains('/') {
+            return None;
+        }
+        let language = activitypub_id.query_pairs().find(|(key, _)| key == "lang");
+        match language {
+            Some((_, language)) if is_language_code(&language) && !name.contains('.') => {
+                Some(Tag::scoped_name(name, &language))
+            }
+            Some(_) => None,
+            None => Some(name.to_lowercase()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum TagAliasErr {
+    /// the tag is already the canonical tag
+    SameTag,
+}
+
+impl std::fmt::Display for TagAliasErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TagAliasErr::SameTag => write!(f, "SameTag"),
+        }
     }
 }
 
 impl From<tokio_postgres::Row> for Tag {
     fn from(row: tokio_postgres::Row) -> Self {
+        let announce_policy: String = row.get("announce_policy");
+        let quote_policy: String = row.get("quote_policy");
+        let policy: String = row.get("policy");
         Tag {
             id: row.get("tag_id"),
             name: row.get("tag"),
             display_name: row.get("display_name"),
             bio: row.get("bio"),
             banned: row.get("banned"),
+            alias_of: row.get("alias_of"),
+            expression: row.get("expression"),
+            announce_policy: AnnouncePolicy::try_from(announce_policy.as_str())
+                .expect("unknown announce policy in db"),
+            quote_policy: QuotePolicy::try_from(quote_policy.as_str())
+                .expect("unknown quote policy in db"),
+            policy: serde_json::from_str(&policy).expect("invalid tag policy in db"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_from_activitypub_id() -> Result<(), String> {
+        let domain = "tags.example";
+        let cases = [
+            ("https://tags.example/tags/RustLang", Some("rustlang")),
+            ("https://tags.example/tags/rust/inbox", None),
+            ("https://tags.example/tags/", None),
+            ("https://other.example/tags/rust", None),
+            ("https://tags.example/actor/ap", None),
+            ("https://tags.example/tags/rust.de", Some("rust.de")),
+            (
+                "https://tags.example/tags/Rust?lang=pt-BR",
+                Some("rust.pt-br"),
+            ),
+            ("https://tags.example/tags/rust?lang=rust", None),
+        ];
+        for (id, expected) in cases {
+            let name = Tag::name_from_activitypub_id(&Url::parse(id).unwrap(), domain);
+            if name.as_deref().ne(&expected) {
+                return Err(format!("{id} resolved to {name:?}"));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn scoped_tags_know_their_language() -> Result<(), String> {
+        let cases = [
+            ("rust", "rust", None),
+            ("rust.de", "rust", Some("de")),
+            ("rust.pt-br", "rust", Some("pt-br")),
+            ("rust.lang", "rust.lang", None),
+        ];
+        for (name, base, language) in cases {
+            let tag = test_tag(name);
+            if tag.base_name() != base || tag.language() != language {
+                return Err(format!(
+                    "{name} scoped to {:?} of {}",
+                    tag.language(),
+                    tag.base_name()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn group_actors_point_at_their_moderators() -> Result<(), String> {
+        let tag = test_tag("rust");
+        let instance_actor = InstanceActor::new(crate::cryptography::key::Algorithms::Hs2019);
+        let actor = serde_json::to_value(tag.to_actor("tags.example", &instance_actor, true))
+            .map_err(|x| x.to_string())?;
+        let moderators = serde_json::json!("https://tags.example/tags/rust/moderators");
+        match actor["type"] == "Group"
+            && actor["attributedTo"] == moderators
+            && actor["moderators"] == moderators
+        {
+            true => Ok(()),
+            false => Err(format!("unexpected group actor {actor}")),
         }
     }
 }
@@ -0,0 +1,256 @@
+use std::collections::HashSet;
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use super::tag::Tag;
+
+/// names that are used by the instance itself and can never become tags,
+/// admins can add to these with `reserved_tags` in the config
+pub const RESERVED_TAGS: &[&str] = &[
+    "instance.actor",
+    "admin",
+    "administrator",
+    "moderator",
+    "inbox",
+    "outbox",
+    "actor",
+    "tags",
+    "followers",
+    "following",
+];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TagBanKind {
+    /// matches the tag exactly
+    Exact,
+    /// matches every tag starting with the pattern
+    Prefix,
+    /// matches every tag ending with the pattern
+    Suffix,
+    /// matches every tag the regex matches anywhere in
+    Regex,
+}
+
+impl TagBanKind {
+    pub fn stringify(&self) -> &str {
+        match self {
+            TagBanKind::Exact => "exact",
+            TagBanKind::Prefix => "prefix",
+            TagBanKind::Suffix => "suffix",
+            TagBanKind::Regex => "regex",
+        }
+    }
+}
+
+impl TryFrom<&str> for TagBanKind {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "exact" => Ok(Self::Exact),
+            "prefix" => Ok(Self::Prefix),
+            "suffix" => Ok(Self::Suffix),
+            "regex" => Ok(Self::Regex),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TagBanRule {
+    pub id: i64,
+    pub kind: TagBanKind,
+    /// always lowercase, matching is case insensitive
+    pub pattern: String,
+    pub reason: Option<String>,
+    pub created: i64,
+}
+
+impl From<tokio_postgres::Row> for TagBanRule {
+    fn from(row: tokio_postgres::Row) -> Self {
+        let kind: String = row.get("kind");
+        TagBanRule {
+            id: row.get("rule_id"),
+            kind: TagBanKind::try_from(kind.as_str()).expect("unknown ban kind in db"),
+            pattern: row.get("pattern"),
+            reason: row.get("reason"),
+            created: row.get("created"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum TagBanErr {
+    InvalidRegex(String),
+    EmptyPattern,
+}
+
+impl std::fmt::Display for TagBanErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TagBanErr::InvalidRegex(x) => write!(f, "InvalidRegex: {}", x),
+            TagBanErr::EmptyPattern => write!(f, "EmptyPattern"),
+        }
+    }
+}
+
+/// lowercases and validates a pattern before it is stored
+pub fn validate_pattern(kind: TagBanKind, pattern: &str) -> Result<String, TagBanErr> {
+    let pattern = pattern.trim().trim_start_matches('#').to_lowercase();
+    if pattern.is_empty() {
+        return Err(TagBanErr::EmptyPattern);
+    }
+    if kind == TagBanKind::Regex {
+        if let Err(err) = compile(&pattern) {
+            return Err(TagBanErr::InvalidRegex(err.to_string()));
+        }
+    }
+    Ok(pattern)
+}
+
+fn compile(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(1 << 20)
+        .build()
+}
+
+#[derive(Debug)]
+pub enum TagBanMatch<'a> {
+    Reserved,
+    Rule(&'a TagBanRule),
+}
+
+/// every ban rule and reserved name, ready to be checked against tags
+pub struct TagBans {
+    rules: Vec<(TagBanRule, Option<Regex>)>,
+    reserved: Vec<String>,
+    /// tags that were banned on their own
+    banned: HashSet<String>,
+}
+
+impl TagBans {
+    pub fn new(rules: Vec<TagBanRule>, reserved: &[String], banned: Vec<String>) -> Self {
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                let regex = match rule.kind {
+                    // rules are validated when created, a bad one in the db is skipped
+                    TagBanKind::Regex => compile(&rule.pattern).ok(),
+                    _ => None,
+                };
+                (rule, regex)
+            })
+            .collect();
+        let reserved = RESERVED_TAGS
+            .iter()
+            .map(|x| x.to_string())
+            .chain(reserved.iter().map(|x| x.to_lowercase()))
+            .collect();
+        TagBans {
+            rules,
+            reserved,
+            banned: banned.into_iter().collect(),
+        }
+    }
+    /// checks a tag name against the reserved names and then every rule
+    pub fn check(&self, tag: &str) -> Option<TagBanMatch<'_>> {
+        let tag = tag.trim_start_matches('#').to_lowercase();
+        if self.reserved.contains(&tag) {
+            return Some(TagBanMatch::Reserved);
+        }
+        self.rules
+            .iter()
+            .find(|(rule, regex)| match rule.kind {
+                TagBanKind::Exact => tag.eq(&rule.pattern),
+                TagBanKind::Prefix => tag.starts_with(&rule.pattern),
+                TagBanKind::Suffix => tag.ends_with(&rule.pattern),
+                TagBanKind::Regex => regex.as_ref().is_some_and(|regex| regex.is_match(&tag)),
+            })
+            .map(|(rule, _)| TagBanMatch::Rule(rule))
+    }
+    /// a tag is banned if it was banned on its own or matches a rule,
+    /// scoped tags are also banned along with the tag they narrow down
+    pub fn is_banned(&self, tag: &Tag) -> bool {
+        tag.banned
+            || self.banned.contains(tag.base_name())
+            || self.check(&tag.name).is_some()
+            || self.check(tag.base_name()).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::types::tag::test_tag;
+
+    use super::*;
+
+    fn rule(kind: TagBanKind, pattern: &str) -> TagBanRule {
+        TagBanRule {
+            id: 0,
+            kind,
+            pattern: validate_pattern(kind, pattern).unwrap(),
+            reason: None,
+            created: 0,
+        }
+    }
+
+    #[test]
+    fn test_ban_rules() -> Result<(), String> {
+        let bans = TagBans::new(
+            vec![
+                rule(TagBanKind::Exact, "#Spam"),
+                rule(TagBanKind::Prefix, "crypto"),
+                rule(TagBanKind::Suffix, "giveaway"),
+                rule(TagBanKind::Regex, "^nft[0-9]+$"),
+            ],
+            &["Slur".to_string()],
+            Vec::new(),
+        );
+        let banned = [
+            "spam",
+            "SPAM",
+            "cryptobros",
+            "freegiveaway",
+            "nft2024",
+            "slur",
+            "inbox",
+        ];
+        let allowed = ["spammy", "notcrypto", "giveaways", "nft", "rust"];
+        for tag in banned {
+            if bans.check(tag).is_none() {
+                return Err(format!("{tag} should be banned"));
+            }
+        }
+        for tag in allowed {
+            if let Some(x) = bans.check(tag) {
+                return Err(format!("{tag} should not be banned but matched {x:?}"));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn scoped_tags_are_banned_with_their_base() -> Result<(), String> {
+        let bans = TagBans::new(Vec::new(), &[], vec!["rust".to_string()]);
+        for (name, banned) in [
+            ("rust.de", true),
+            ("rust.lang", false),
+            ("rustacean.de", false),
+        ] {
+            if bans.is_banned(&test_tag(name)) != banned {
+                return Err(format!("{name} should be banned: {banned}"));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_regex() -> Result<(), String> {
+        match validate_pattern(TagBanKind::Regex, "(unclosed") {
+            Err(TagBanErr::InvalidRegex(_)) => Ok(()),
+            x => Err(format!("expected invalid regex, got {x:?}")),
+        }
+    }
+}
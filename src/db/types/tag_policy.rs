@@ -0,0 +1,266 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{config::DAY, types::language::language_matches};
+
+/// whether posts with some property are boosted
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Filter {
+    #[default]
+    Allow,
+    Skip,
+    /// only posts with the property are boosted
+    Only,
+}
+
+impl Filter {
+    fn is_allow(&self) -> bool {
+        *self == Filter::Allow
+    }
+}
+
+/// the rules a post has to pass to be boosted by a tag, on top of
+/// matching it. kept as json on the tag, for example `#art` boosting
+/// only posts with media is `{"media": "only"}`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TagPolicy {
+    /// replies to other posts
+    #[serde(skip_serializing_if = "Filter::is_allow")]
+    pub replies: Filter,
+    /// posts marked sensitive or behind a content warning
+    #[serde(skip_serializing_if = "Filter::is_allow")]
+    pub sensitive: Filter,
+    /// posts with images, video or audio
+    #[serde(skip_serializing_if = "Filter::is_allow")]
+    pub media: Filter,
+    /// posts from accounts that are bots
+    #[serde(skip_serializing_if = "Filter::is_allow")]
+    pub bots: Filter,
+    /// the languages boosted posts are in, like `en` or `pt-BR`. a
+    /// language without a region also allows each of its regions.
+    /// posts in any language are boosted when empty
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_hashtags: Option<usize>,
+    /// accounts that don't say when they were created are boosted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_account_age_days: Option<i64>,
+}
+
+/// what a policy knows about a post and its author
+pub struct PostFacts<'a> {
+    pub reply: bool,
+    pub sensitive: bool,
+    pub media: bool,
+    pub bot: bool,
+    /// declared or detected, see
+    /// [`crate::types::postable::ApPostable::languages`]
+    pub languages: &'a [String],
+    pub hashtags: usize,
+    /// when the author's account was created
+    pub account_created: Option<i64>,
+}
+
+/// the reason a tag's policy did not boost a post
+#[derive(Debug, PartialEq, Eq)]
+pub enum PolicySkip {
+    Reply,
+    NotReply,
+    Sensitive,
+    NotSensitive,
+    Media,
+    NoMedia,
+    Bot,
+    NotBot,
+    Language(Option<String>),
+    TooManyHashtags(usize),
+    /// the age of the account in days
+    AccountTooNew(i64),
+}
+
+impl Display for PolicySkip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolicySkip::Reply => write!(f, "is a reply"),
+            PolicySkip::NotReply => write!(f, "is not a reply"),
+            PolicySkip::Sensitive => write!(f, "is sensitive"),
+            PolicySkip::NotSensitive => write!(f, "is not sensitive"),
+            PolicySkip::Media => write!(f, "has media"),
+            PolicySkip::NoMedia => write!(f, "has no media"),
+            PolicySkip::Bot => write!(f, "is by a bot"),
+            PolicySkip::NotBot => write!(f, "is not by a bot"),
+            PolicySkip::Language(Some(language)) => write!(f, "is in {language}"),
+            PolicySkip::Language(None) => write!(f, "is in an unknown language"),
+            PolicySkip::TooManyHashtags(count) => write!(f, "has {count} hashtags"),
+            PolicySkip::AccountTooNew(days) => write!(f, "is by an account {days} days old"),
+        }
+    }
+}
+
+impl TagPolicy {
+    /// checks each rule in turn, `now` is in milliseconds
+    pub fn check(&self, post: &PostFacts, now: i64) -> Result<(), PolicySkip> {
+        filter(
+            self.replies,
+            post.reply,
+            PolicySkip::Reply,
+            PolicySkip::NotReply,
+        )?;
+        filter(
+            self.sensitive,
+            post.sensitive,
+            PolicySkip::Sensitive,
+            PolicySkip::NotSensitive,
+        )?;
+        filter(
+            self.media,
+            post.media,
+            PolicySkip::Media,
+            PolicySkip::NoMedia,
+        )?;
+        filter(self.bots, post.bot, PolicySkip::Bot, PolicySkip::NotBot)?;
+        let allowed = |language: &String| {
+            self.languages
+                .iter()
+                .any(|wanted| language_matches(wanted, language))
+        };
+        if !self.languages.is_empty() && !post.languages.iter().any(allowed) {
+            return Err(PolicySkip::Language(post.languages.first().cloned()));
+        }
+        if let Some(max) = self.max_hashtags {
+            if post.hashtags > max {
+                return Err(PolicySkip::TooManyHashtags(post.hashtags));
+            }
+        }
+        if let (Some(min), Some(created)) = (self.min_account_age_days, post.account_created) {
+            let age = (now - created) / DAY;
+            if age < min {
+                return Err(PolicySkip::AccountTooNew(age));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `present` is the skip when the post has the property and
+/// `absent` when it hasn't
+fn filter(
+    filter: Filter,
+    value: bool,
+    present: PolicySkip,
+    absent: PolicySkip,
+) -> Result<(), PolicySkip> {
+    match (filter, value) {
+        (Filter::Skip, true) => Err(present),
+        (Filter::Only, false) => Err(absent),
+        _ => Ok(()),
+    }
+}
+
+/// a post a tag matched but did not boost
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SkippedPost {
+    pub activitypub_id: Url,
+    pub tag: i64,
+    pub reason: String,
+    pub skipped_at: i64,
+}
+
+impl From<tokio_postgres::Row> for SkippedPost {
+    fn from(row: tokio_postgres::Row) -> Self {
+        let activitypub_id: String = row.get("activitypub_id");
+        SkippedPost {
+            activitypub_id: Url::parse(&activitypub_id).expect("invalid url in db"),
+            tag: row.get("tag"),
+            reason: row.get("reason"),
+            skipped_at: row.get("skipped_at"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::DAY;
+
+    use super::{PolicySkip, PostFacts, TagPolicy};
+
+    fn facts(languages: &[String]) -> PostFacts<'_> {
+        PostFacts {
+            reply: false,
+            sensitive: false,
+            media: false,
+            bot: false,
+            languages,
+            hashtags: 3,
+            account_created: Some(0),
+        }
+    }
+
+    #[test]
+    fn policies_skip_with_a_reason() -> Result<(), String> {
+        let english = vec!["en-US".to_string()];
+        let post = || facts(&english);
+        let cases = [
+            (r#"{}"#, post(), Ok(())),
+            (r#"{"media": "only"}"#, post(), Err(PolicySkip::NoMedia)),
+            (
+                r#"{"replies": "skip"}"#,
+                PostFacts {
+                    reply: true,
+                    ..post()
+                },
+                Err(PolicySkip::Reply),
+            ),
+            (r#"{"languages": ["en"]}"#, post(), Ok(())),
+            (
+                r#"{"languages": ["de", "en-GB"]}"#,
+                post(),
+                Err(PolicySkip::Language(Some("en-US".to_string()))),
+            ),
+            (
+                r#"{"languages": ["en"]}"#,
+                facts(&[]),
+                Err(PolicySkip::Language(None)),
+            ),
+            (
+                r#"{"max_hashtags": 2}"#,
+                post(),
+                Err(PolicySkip::TooManyHashtags(3)),
+            ),
+            (
+                r#"{"min_account_age_days": 7}"#,
+                post(),
+                Err(PolicySkip::AccountTooNew(2)),
+            ),
+            (
+                r#"{"min_account_age_days": 7}"#,
+                PostFacts {
+                    account_created: None,
+                    ..post()
+                },
+                Ok(()),
+            ),
+        ];
+        for (policy, post, expected) in cases {
+            let parsed: TagPolicy = serde_json::from_str(policy).map_err(|x| x.to_string())?;
+            let result = parsed.check(&post, 2 * DAY + 1);
+            if result != expected {
+                return Err(format!("{policy} gave {:?}", result));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_rules_are_refused() -> Result<(), String> {
+        match serde_json::from_str::<TagPolicy>(r#"{"media": "only", "boosts": 3}"#) {
+            Ok(x) => Err(format!("parsed to {:?}", x)),
+            Err(_) => Ok(()),
+        }
+    }
+}
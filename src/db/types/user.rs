@@ -1,5 +1,126 @@
+use url::Url;
 use uuid::Uuid;
 
+use crate::{cryptography::key::Key, types::actors::Actor};
+
+use super::consent::OptOut;
+
 pub struct User {
     pub id: Uuid,
+    pub activitypub_id: Url,
+    pub url: Url,
+    pub domain: String,
+    pub username: String,
+    pub display_name: Option<String>,
+
+    pub public_key_pem: String,
+    pub public_key_id: String,
+
+    pub banned: bool,
+    pub reason: Option<String>,
+    /// how their profile asks not to be boosted, if it does
+    pub opt_out: Option<OptOut>,
+    /// a service or application rather than a person
+    pub bot: bool,
+    /// when their account was created, if their actor says
+    pub created_at: Option<i64>,
+
+    pub inbox: Url,
+    /// will be the same as the inbox if the user has no shared inbox
+    pub shared_inbox: Url,
+    pub outbox: Url,
+    pub followers: Url,
+    pub following: Url,
+    pub fetched_at: Option<i64>,
+
+    pub is_admin: bool,
+    pub site_moderator: bool,
+}
+
+/// a user of `domain` that nobody has banned and who has not opted out
+#[cfg(test)]
+pub fn test_user(username: &str, domain: &str) -> User {
+    let id = Url::parse(&format!("https://{domain}/users/{username}")).unwrap();
+    User {
+        id: Uuid::now_v7(),
+        activitypub_id: id.clone(),
+        url: id.clone(),
+        domain: domain.to_string(),
+        username: username.to_string(),
+        display_name: None,
+        public_key_pem: String::new(),
+        public_key_id: format!("{id}#main-key"),
+        banned: false,
+        reason: None,
+        opt_out: None,
+        bot: false,
+        created_at: None,
+        inbox: id.join("inbox").unwrap(),
+        shared_inbox: id.join("inbox").unwrap(),
+        outbox: id.join("outbox").unwrap(),
+        followers: id.join("followers").unwrap(),
+        following: id.join("following").unwrap(),
+        fetched_at: None,
+        is_admin: false,
+        site_moderator: false,
+    }
+}
+
+impl From<tokio_postgres::Row> for User {
+    fn from(row: tokio_postgres::Row) -> Self {
+        let url = |column: &str| -> Url {
+            let val: String = row.get(column);
+            Url::parse(&val).expect("invalid url in db")
+        };
+        User {
+            id: row.get("uid"),
+            activitypub_id: url("activitypub_id"),
+            url: url("url"),
+            domain: row.get("domain"),
+            username: row.get("username"),
+            display_name: row.get("display_name"),
+            public_key_pem: row.get("public_key_pem"),
+            public_key_id: row.get("public_key_id"),
+            banned: row.get("banned"),
+            reason: row.get("reason"),
+            opt_out: row
+                .get::<_, Option<&str>>("opt_out")
+                .map(|x| OptOut::try_from(x).expect("invalid opt out in db")),
+            bot: row.get("bot"),
+            created_at: row.get("created_at"),
+            inbox: url("inbox"),
+            shared_inbox: url("shared_inbox"),
+            outbox: url("outbox"),
+            followers: url("followers"),
+            following: url("following"),
+            fetched_at: row.get("fetched_at"),
+            is_admin: row.get("is_admin"),
+            site_moderator: row.get("site_moderator"),
+        }
+    }
+}
+
+impl User {
+    /// takes on everything the actor may change about themselves,
+    /// including rotating their key
+    pub fn update_from_actor(&mut self, actor: &Actor) {
+        self.url = actor.url.clone().unwrap_or_else(|| actor.id.clone());
+        self.username = actor.preferred_username.clone();
+        self.display_name = actor.name.clone();
+        self.public_key_pem = actor
+            .public_key
+            .public_key_pem
+            .to_pem()
+            .expect("failed to encode actor public key");
+        self.public_key_id = actor.public_key.id.to_string();
+        self.inbox = actor.inbox.clone();
+        self.shared_inbox = actor.shared_inbox().clone();
+        self.outbox = actor.outbox.clone();
+        self.followers = actor.followers.clone().unwrap_or_else(|| actor.id.clone());
+        self.following = actor.following.clone().unwrap_or_else(|| actor.id.clone());
+        self.opt_out = OptOut::of(actor);
+        self.bot = actor.is_bot();
+        self.created_at = actor.extensions.published();
+        self.fetched_at = Some(chrono::Utc::now().timestamp_millis());
+    }
 }
@@ -0,0 +1,43 @@
+use crate::{
+    config::Config,
+    db::{pg_conn::PgConn, types::instance_actor::InstanceActor},
+    protocol::ap_protocol::fetch::authorized_fetch,
+    types::actors::Actor,
+};
+
+use super::{consent::stop_boosting, InboxErr};
+
+/// keeps our copy of a user in sync with their profile. actors
+/// we have never seen are not worth storing until they follow a tag
+///
+/// the update only tells us the actor changed, what is stored is
+/// refetched from its id so that a forwarded or replayed update
+/// can't swap out the user's key. a profile that now opts out of
+/// being boosted stops their boosts
+pub async fn handle_actor_update(
+    conn: &PgConn,
+    config: &Config,
+    actor: Actor,
+) -> Result<(), InboxErr> {
+    let Some(previous) = conn.get_user_by_id(&actor.id).await else {
+        return Ok(());
+    };
+    let instance_actor = conn.get_or_init_instance_actor().await;
+    let fetched: Actor = authorized_fetch(
+        actor.id.clone(),
+        &InstanceActor::pub_key_id(&config.instance_domain),
+        &mut instance_actor.private_key(),
+    )
+    .await
+    .map_err(InboxErr::FetchErr)?;
+    if fetched.id.ne(&actor.id) {
+        return Err(InboxErr::Unauthorized);
+    }
+    let Some(user) = conn.update_user_from_actor(&fetched).await else {
+        return Ok(());
+    };
+    if let (None, Some(opt_out)) = (config.opted_out(&previous), config.opted_out(&user)) {
+        stop_boosting(conn, config, &user, opt_out).await;
+    }
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    cryptography::universal_keys::UniversalPrivate,
+    db::{
+        pg_conn::PgConn,
+        types::{instance_actor::InstanceActor, post::Post, tag::Tag},
+    },
+    protocol::ap_protocol::notify_followers::deliver_to_inboxes,
+    types::{
+        core_types::OptionalArray,
+        extensions::Extensions,
+        link::LinkSimpleOrExpanded,
+        share::{Share, ShareType, SharedObject},
+        undo::{Undo, UndoType, Undoable},
+    },
+};
+
+/// announces a post from a tag to all of the tag's followers, and
+/// records where it was delivered so it can be undone later. if the
+/// tag is relayed the instance actor announces it to relay subscribers
+///
+/// `also` are the other tags the post matched when only one tag boosts
+/// it. they are listed in the announce and their followers receive it
+/// too. inboxes the announce already reached are skipped
+#[allow(clippy::too_many_arguments)]
+pub async fn announce(
+    conn: &PgConn,
+    config: &Config,
+    private_key: &mut UniversalPrivate,
+    tag: &Tag,
+    also: &[Tag],
+    pid: Uuid,
+    object: SharedObject,
+    author: &Url,
+) {
+    let domain = &config.instance_domain;
+    let mut cc = vec![
+        LinkSimpleOrExpanded::Simple(author.clone()),
+        LinkSimpleOrExpanded::Simple(tag.followers(domain)),
+    ];
+    cc.extend(
+        also.iter()
+            .map(|other| LinkSimpleOrExpanded::Simple(other.followers(domain))),
+    );
+    let hashtags: Vec<_> = also
+        .iter()
+        .map(|other| LinkSimpleOrExpanded::Expanded(other.hashtag(domain)))
+        .collect();
+    let relayed = config.relays(tag) || also.iter().any(|other| config.relays(other));
+    let relayed_object = relayed.then(|| object.clone());
+    let announce = Share {
+        type_field: ShareType::Announce,
+        id: tag.announce_id(domain, pid),
+        actor: tag.activitypub_id(domain),
+        published: chrono::Utc::now().timestamp_millis(),
+        to: Some(OptionalArray::Single(LinkSimpleOrExpanded::Simple(
+            Url::parse("https://www.w3.org/ns/activitystreams#Public").unwrap(),
+        ))),
+        cc: Some(OptionalArray::Multiple(cc)),
+        tag: match hashtags.is_empty() {
+            true => None,
+            false => Some(OptionalArray::Multiple(hashtags)),
+        },
+        object,
+        extensions: Extensions::default(),
+    };
+
+    let delivered_before: Vec<Url> = conn
+        .get_post_deliveries(pid)
+        .await
+        .into_iter()
+        .filter(|(delivered_tag, _)| delivered_tag.id == tag.id)
+        .flat_map(|(_, inboxes)| inboxes)
+        .collect();
+    let mut inboxes = conn.tag_unique_inboxes(tag).await;
+    for other in also {
+        inboxes.extend(conn.tag_unique_inboxes(other).await);
+    }
+    inboxes.sort();
+    inboxes.dedup();
+    inboxes.retain(|inbox| !delivered_before.contains(inbox));
+
+    let delivered = deliver_to_inboxes(
+        inboxes,
+        &announce.wrap_context(),
+        &tag.pub_key_id(domain),
+        private_key,
+    )
+    .await;
+    conn.add_post_deliveries(pid, tag, &delivered).await;
+
+    if let Some(object) = relayed_object {
+        relay_announce(conn, config, private_key, pid, object, author).await;
+    }
+}
+
+/// subscribers expect posts from the relay they followed, so the
+/// instance actor announces the post to them the way relays do. it does
+/// so once however many relayed tags the post has
+async fn relay_announce(
+    conn: &PgConn,
+    config: &Config,
+    private_key: &mut UniversalPrivate,
+    pid: Uuid,
+    object: SharedObject,
+    author: &Url,
+) {
+    let domain = &config.instance_domain;
+    let announce = Share {
+        type_field: ShareType::Announce,
+        id: InstanceActor::announce_id(domain, pid),
+        actor: InstanceActor::activitypub_id(domain),
+        published: chrono::Utc::now().timestamp_millis(),
+        to: Some(OptionalArray::Single(LinkSimpleOrExpanded::Simple(
+            Url::parse("https://www.w3.org/ns/activitystreams#Public").unwrap(),
+        ))),
+        cc: Some(OptionalArray::Single(LinkSimpleOrExpanded::Simple(
+            author.clone(),
+        ))),
+        tag: None,
+        object,
+        extensions: Extensions::default(),
+    };
+    let delivered_before = conn.get_relay_deliveries(pid).await;
+    let mut inboxes = conn.relay_unique_inboxes().await;
+    inboxes.retain(|inbox| !delivered_before.contains(inbox));
+    let delivered = deliver_to_inboxes(
+        inboxes,
+        &announce.wrap_context(),
+        &InstanceActor::pub_key_id(domain),
+        private_key,
+    )
+    .await;
+    conn.add_relay_deliveries(pid, &delivered).await;
+}
+
+/// sends an undo of every announce of the post to the
+/// same inboxes the announce was delivered to
+pub async fn undo_announces(
+    conn: &PgConn,
+    config: &Config,
+    private_key: &mut UniversalPrivate,
+    post: &Post,
+) {
+    for (tag, inboxes) in conn.get_post_deliveries(post.id).await {
+        undo_announce(config, private_key, &tag, post, inboxes).await;
+    }
+    let relayed = conn.get_relay_deliveries(post.id).await;
+    if !relayed.is_empty() {
+        let domain = &config.instance_domain;
+        send_undo(
+            InstanceActor::activitypub_id(domain),
+            InstanceActor::announce_id(domain, post.id),
+            &InstanceActor::pub_key_id(domain),
+            private_key,
+            post,
+            relayed,
+        )
+        .await;
+    }
+}
+
+/// sends an undo of a single tag's announce of the post
+pub async fn undo_announce(
+    config: &Config,
+    private_key: &mut UniversalPrivate,
+    tag: &Tag,
+    post: &Post,
+    inboxes: Vec<Url>,
+) {
+    let domain = &config.instance_domain;
+    send_undo(
+        tag.activitypub_id(domain),
+        tag.announce_id(domain, post.id),
+        &tag.pub_key_id(domain),
+        private_key,
+        post,
+        inboxes,
+    )
+    .await;
+}
+
+async fn send_undo(
+    actor: Url,
+    announce_id: Url,
+    key_id: &str,
+    private_key: &mut UniversalPrivate,
+    post: &Post,
+    inboxes: Vec<Url>,
+) {
+    let undo = Undo {
+        type_field: UndoType::Undo,
+        id: Url::parse(&format!("{announce_id}/undo")).unwrap(),
+        actor: actor.clone(),
+        object: Undoable::Announce(Share {
+            type_field: ShareType::Announce,
+            id: announce_id,
+            actor,
+            published: post.fetched_at.unwrap_or(post.published),
+            to: None,
+            cc: None,
+            tag: None,
+            object: SharedObject::Id(post.activitypub_id.clone()),
+            extensions: Extensions::default(),
+        }),
+    };
+    deliver_to_inboxes(inboxes, &undo.wrap_context(), key_id, private_key).await;
+}
@@ -0,0 +1,144 @@
+//! a user's posts only reach us once they are followed back, so when
+//! they first follow a tag their recent posts with it are fetched from
+//! their outbox and boosted as if they had just been delivered, or
+//! recorded against the tag depending on [`BackfillMode`]
+
+use std::collections::HashSet;
+
+use actix_web::rt::spawn;
+use futures_util::StreamExt;
+
+use crate::{
+    config::{BackfillMode, Config, DAY},
+    cryptography::universal_keys::UniversalPrivate,
+    db::{
+        pg_conn::PgConn,
+        types::{instance_actor::InstanceActor, keyword::Keywords, tag::Tag, user::User},
+    },
+    protocol::ap_protocol::collection::{walk_collection, CollectionItem, WalkLimits},
+    types::{
+        collection::CollectionPageLink, create::Create, postable::ApPostable, share::SharedObject,
+    },
+};
+
+use super::postable::boost_postable;
+
+/// how much of an outbox is read for one backfill
+const LIMITS: WalkLimits = WalkLimits {
+    max_pages: 5,
+    max_items: 200,
+};
+
+/// backfills in a task of its own, paging through an outbox can take
+/// longer than the lease of the follow being handled. does nothing if
+/// backfilling is not enabled. must be called from within the actix runtime
+pub fn spawn_backfill(conn: PgConn, config: Config, user: User, tag: Tag) {
+    if config.backfill_days.is_none() {
+        return;
+    }
+    spawn(async move {
+        let instance_actor = conn.get_or_init_instance_actor().await;
+        let mut private_key = instance_actor.private_key();
+        backfill(&conn, &config, &mut private_key, &user, &tag).await;
+    });
+}
+
+/// boosts or records the user's public posts containing the tag, or one
+/// of its aliases, or one of its keywords from the last `backfill_days`
+/// days. feeds take the posts matching their expression or keywords
+async fn backfill(
+    conn: &PgConn,
+    config: &Config,
+    private_key: &mut UniversalPrivate,
+    user: &User,
+    tag: &Tag,
+) {
+    let Some(days) = config.backfill_days else {
+        return;
+    };
+    let cutoff = chrono::Utc::now().timestamp_millis() - days * DAY;
+    let feed = tag.feed();
+    let keywords = Keywords::new(conn.get_keywords_of_tag(tag).await, |rule| &rule.keyword);
+    // posts carry the hashtags of scoped tags without their language
+    let mut names = vec![tag.base_name().to_string()];
+    names.extend(
+        conn.get_tag_aliases(tag)
+            .await
+            .iter()
+            .map(|x| x.base_name().to_string()),
+    );
+    let key_id = InstanceActor::pub_key_id(&config.instance_domain);
+
+    let outbox = walk_collection(
+        CollectionPageLink::Id(user.outbox.clone()),
+        &key_id,
+        private_key,
+        LIMITS,
+        Some((conn, config.backfill_interval_ms)),
+    );
+    let mut outbox = std::pin::pin!(outbox);
+    while let Some(item) = outbox.next().await {
+        let item = match item {
+            Ok(CollectionItem::Object(item)) => item,
+            // bare ids would each need a fetch of their own
+            Ok(CollectionItem::Id(_)) => continue,
+            Err(err) => {
+                println!("failed to backfill {}: {}", user.activitypub_id, err);
+                return;
+            }
+        };
+        // boosts of other people's posts are skipped
+        let Ok(create) = serde_json::from_value::<Create>(item) else {
+            continue;
+        };
+        let Ok(postable) = create.object.clone().verify(&user.domain) else {
+            continue;
+        };
+        if postable.actor().ne(&user.activitypub_id) {
+            continue;
+        }
+        // outboxes are newest first
+        if postable.published() < cutoff {
+            return;
+        }
+        let present: HashSet<String> = postable
+            .hashtags()
+            .iter()
+            .filter(|x| config.hashtag_counts(x.source, tag))
+            .map(|x| x.name.to_lowercase())
+            .collect();
+        let tagged = match &feed {
+            Some(expression) => expression.matches(&present),
+            None => names.iter().any(|name| present.contains(name)),
+        } || postable
+            .text()
+            .is_some_and(|text| keywords.matching(&text).next().is_some());
+        if !postable.is_public() || !tagged {
+            continue;
+        }
+        match config.backfill_mode {
+            BackfillMode::Boost => {
+                let activity = SharedObject::Activity(Box::new(create));
+                if let Err(err) = boost_postable(conn, config, postable, Some(&activity)).await {
+                    println!("failed to backfill {}: {}", user.activitypub_id, err);
+                    return;
+                }
+            }
+            BackfillMode::Record => record(conn, user, tag, &postable).await,
+        }
+    }
+}
+
+/// stores the post against the tag without announcing it. posts that
+/// are already known are left alone
+async fn record(conn: &PgConn, user: &User, tag: &Tag, postable: &ApPostable) {
+    if conn.get_post(postable.id()).await.is_none() {
+        conn.create_post(
+            postable.id(),
+            postable.published(),
+            user,
+            std::slice::from_ref(tag),
+        )
+        .await;
+    }
+}
@@ -0,0 +1,207 @@
+//! users whose profile asks not to be boosted, through `#nobot` or
+//! `#noboost`, misskey's `noindex` or mastodon's `discoverable`. their
+//! follows are rejected and they are sent a direct message saying why
+
+use actix_web::rt::spawn;
+use url::Url;
+
+use crate::{
+    config::Config,
+    cryptography::universal_keys::UniversalPrivate,
+    db::{
+        pg_conn::PgConn,
+        types::{consent::OptOut, instance_actor::InstanceActor, tag::Tag, user::User},
+    },
+    protocol::ap_protocol::fetch::{authorized_fetch, deliver},
+    types::{
+        actors::Actor,
+        core_types::OptionalArray,
+        create::{Create, CreateType},
+        extensions::Extensions,
+        follow_and_response::{FollowRef, FollowResponse, ResponseType},
+        link::{Link, LinkSimpleOrExpanded, LinkType},
+        note::{Note, NoteType},
+        postable::ApPostable,
+    },
+};
+
+use super::{announce::undo_announces, follow::activity_id, follow_back::undo_follow_back};
+
+/// called once a user we boost opts out. every follow of theirs is
+/// rejected as if it never had been accepted, every boost of their
+/// posts is undone and the text we kept of them is forgotten. they
+/// are told why once, however many tags they followed
+pub async fn stop_boosting(conn: &PgConn, config: &Config, user: &User, opt_out: OptOut) {
+    let instance_actor = conn.get_or_init_instance_actor().await;
+    let mut private_key = instance_actor.private_key();
+    let mut unfollowed = Vec::new();
+    for follow in conn.get_user_follow_ids(user).await {
+        let Some((user, tag, ufid)) = conn.unfollow_tag(&follow).await else {
+            continue;
+        };
+        reject(config, &mut private_key, &tag, &user, FollowRef::Id(follow)).await;
+        undo_follow_back(conn, config, &mut private_key, &tag, ufid, &user).await;
+        unfollowed.push(tag);
+    }
+    tell_opted_out(config, &mut private_key, &unfollowed, user, opt_out).await;
+    for post in conn.get_user_posts(user).await {
+        undo_announces(conn, config, &mut private_key, &post).await;
+        conn.delete_post(post).await;
+    }
+    conn.delete_recent_posts_by(&user.activitypub_id).await;
+}
+
+/// users stored before opt outs were tracked are refetched once, so
+/// that the ones who had already opted out stop being boosted.
+/// must be called from within the actix runtime
+pub fn spawn_opt_out_backfill(conn: PgConn, config: Config) {
+    spawn(async move {
+        let instance_actor = conn.get_or_init_instance_actor().await;
+        let key_id = InstanceActor::pub_key_id(&config.instance_domain);
+        for user in conn.get_unchecked_opt_outs().await {
+            let fetched = match authorized_fetch::<_, Actor>(
+                user.activitypub_id.clone(),
+                &key_id,
+                &mut instance_actor.private_key(),
+            )
+            .await
+            {
+                Ok(actor) if actor.id.eq(&user.activitypub_id) => actor,
+                // tried again on the next start
+                Ok(_) => continue,
+                Err(err) => {
+                    println!(
+                        "failed to refetch {} for opt outs: {}",
+                        user.activitypub_id, err
+                    );
+                    continue;
+                }
+            };
+            let Some(user) = conn.update_user_from_actor(&fetched).await else {
+                continue;
+            };
+            if let Some(opt_out) = config.opted_out(&user) {
+                stop_boosting(&conn, &config, &user, opt_out).await;
+            }
+        }
+    });
+}
+
+/// rejects a follow of the tag, the reject always comes from the tag
+async fn reject(
+    config: &Config,
+    private_key: &mut UniversalPrivate,
+    tag: &Tag,
+    user: &User,
+    follow: FollowRef,
+) {
+    let domain = &config.instance_domain;
+    let response = FollowResponse {
+        type_field: ResponseType::Reject,
+        id: activity_id(domain),
+        actor: tag.activitypub_id(domain),
+        object: follow,
+    };
+    if let Err(err) = deliver(
+        user.inbox.clone(),
+        &response.wrap_context(),
+        &tag.pub_key_id(domain),
+        private_key,
+    )
+    .await
+    {
+        println!(
+            "failed to reject the follow of {}: {}",
+            user.activitypub_id, err
+        );
+    }
+}
+
+/// a direct message telling the user why the tags won't boost them,
+/// sent from the first of them
+pub async fn tell_opted_out(
+    config: &Config,
+    private_key: &mut UniversalPrivate,
+    tags: &[Tag],
+    user: &User,
+    opt_out: OptOut,
+) {
+    let Some(tag) = tags.first() else {
+        return;
+    };
+    let domain = &config.instance_domain;
+    let names: Vec<String> = tags.iter().map(|x| format!("#{}", x.name)).collect();
+    let message = direct_message(
+        domain,
+        tag,
+        user,
+        &format!(
+            "{} won't boost your posts because {}. follow again if you change your mind",
+            names.join(", "),
+            opt_out.describe()
+        ),
+    );
+    if let Err(err) = deliver(
+        user.inbox.clone(),
+        &message.wrap_context(),
+        &tag.pub_key_id(domain),
+        private_key,
+    )
+    .await
+    {
+        println!(
+            "failed to message {} about opting out: {}",
+            user.activitypub_id, err
+        );
+    }
+}
+
+/// a note from the tag that mentions and is only addressed to the user
+fn direct_message(domain: &str, tag: &Tag, user: &User, text: &str) -> Create {
+    let actor = tag.activitypub_id(domain);
+    let mention = format!("@{}@{}", user.username, user.domain);
+    let note = Note {
+        type_field: NoteType::Note,
+        id: activity_id(domain),
+        attributed_to: actor.clone(),
+        published: chrono::Utc::now().timestamp_millis(),
+        updated: None,
+        to: Some(OptionalArray::Single(LinkSimpleOrExpanded::Simple(
+            user.activitypub_id.clone(),
+        ))),
+        cc: None,
+        in_reply_to: None,
+        tag: Some(OptionalArray::Single(LinkSimpleOrExpanded::Expanded(
+            Link {
+                type_field: LinkType::Mention,
+                href: user.activitypub_id.clone(),
+                name: Some(mention.clone()),
+                hreflang: None,
+                media_type: None,
+                rel: None,
+                height: None,
+                width: None,
+            },
+        ))),
+        url: None,
+        summary: None,
+        sensitive: None,
+        content: Some(format!(
+            r#"<p><span class="h-card"><a href="{}" class="u-url mention">{}</a></span> {}</p>"#,
+            user.url, mention, text
+        )),
+        content_map: None,
+        attachment: None,
+        quote_url: None,
+        quote_uri: None,
+        misskey_quote: None,
+        extensions: Extensions::default(),
+    };
+    Create {
+        type_field: CreateType::Create,
+        id: Url::parse(&format!("{}#create", note.id)).expect("generated invalid create id"),
+        actor,
+        object: ApPostable::Note(note),
+        extensions: Extensions::default(),
+    }
+}
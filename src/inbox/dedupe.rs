@@ -0,0 +1,96 @@
+//! remote servers retry deliveries and the same activity reaches us
+//! through the shared inbox of every instance with a follower of the
+//! author. each activity is only queued the first time it arrives,
+//! see [`crate::inbox::queue::enqueue`]
+
+use serde::Deserialize;
+use url::Url;
+
+use crate::{
+    config::{Config, DAY},
+    db::pg_conn::PgConn,
+};
+
+use super::spawn_pruning;
+
+/// just enough of an activity to tell whether it was seen before
+#[derive(Deserialize)]
+struct ActivityIds {
+    #[serde(rename = "type")]
+    type_field: String,
+    id: Url,
+    actor: Option<Url>,
+    object: Option<serde_json::Value>,
+}
+
+/// the activity id along with the object id for creates. bare posts
+/// have no activity and arriving again is how they are updated, so
+/// they are never considered duplicates
+pub fn dedupe_ids(body: &str) -> Option<(Url, Option<Url>)> {
+    let ids: ActivityIds = serde_json::from_str(body).ok()?;
+    ids.actor.as_ref()?;
+    // updates share their object with the create
+    let object = match (ids.type_field.as_str(), ids.object) {
+        ("Create", Some(serde_json::Value::String(id))) => Url::parse(&id).ok(),
+        ("Create", Some(serde_json::Value::Object(object))) => object
+            .get("id")
+            .and_then(|id| id.as_str())
+            .and_then(|id| Url::parse(id).ok()),
+        _ => None,
+    };
+    Some((ids.id, object))
+}
+
+/// periodically forgets ids older than the retention window.
+/// must be called from within the actix runtime
+pub fn spawn_dedupe_pruning(conn: PgConn, config: Config) {
+    spawn_pruning(
+        conn,
+        config.dedupe_retention_days * DAY,
+        PgConn::prune_processed,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dedupe_ids;
+
+    #[test]
+    fn creates_are_deduped_by_object() -> Result<(), String> {
+        let create = r##"
+{
+	"id": "https://mastodon.social/users/Mastodon/statuses/1/activity",
+	"type": "Create",
+	"actor": "https://mastodon.social/users/Mastodon",
+	"object": {
+		"id": "https://mastodon.social/users/Mastodon/statuses/1",
+		"type": "Note"
+	}
+}
+        "##;
+        match dedupe_ids(create) {
+            Some((activity, Some(object)))
+                if activity.as_str().ends_with("/1/activity")
+                    && object.as_str().ends_with("/statuses/1") =>
+            {
+                Ok(())
+            }
+            x => Err(format!("unexpected ids {:?}", x)),
+        }
+    }
+
+    #[test]
+    fn bare_posts_are_never_deduped() -> Result<(), String> {
+        let note = r##"
+{
+	"id": "https://mastodon.social/users/Mastodon/statuses/1",
+	"type": "Note",
+	"attributedTo": "https://mastodon.social/users/Mastodon"
+}
+        "##;
+        match dedupe_ids(note) {
+            None => Ok(()),
+            x => Err(format!("bare post was deduped by {:?}", x)),
+        }
+    }
+}
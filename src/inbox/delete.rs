@@ -0,0 +1,37 @@
+use crate::{config::Config, db::pg_conn::PgConn, types::delete::Delete};
+
+use super::{announce::undo_announces, InboxErr};
+
+/// undoes our announces of the deleted post, or of every post of
+/// the deleted actor, and removes them from the db
+pub async fn handle_delete(conn: &PgConn, config: &Config, delete: Delete) -> Result<(), InboxErr> {
+    let object = delete.object.id();
+    let instance_actor = conn.get_or_init_instance_actor().await;
+    let mut private_key = instance_actor.private_key();
+
+    // the actor deleted themselves
+    if object.eq(&delete.actor) {
+        let Some(user) = conn.get_user_by_id(object).await else {
+            return Ok(());
+        };
+        for post in conn.get_user_posts(&user).await {
+            undo_announces(conn, config, &mut private_key, &post).await;
+        }
+        // cascades to their follows, posts and deliveries
+        conn.delete_user(user).await;
+        return Ok(());
+    }
+
+    let Some(post) = conn.get_post(object).await else {
+        return Ok(());
+    };
+    let Some(user) = conn.get_user_by_id(&delete.actor).await else {
+        return Err(InboxErr::Unauthorized);
+    };
+    if user.id.ne(&post.actor) {
+        return Err(InboxErr::Unauthorized);
+    }
+    undo_announces(conn, config, &mut private_key, &post).await;
+    conn.delete_post(post).await;
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    db::{
+        pg_conn::PgConn,
+        types::{instance_actor::InstanceActor, tag::Tag},
+    },
+    protocol::ap_protocol::fetch::deliver,
+    types::follow_and_response::{Follow, FollowRef, FollowResponse, ResponseType},
+};
+
+use super::{
+    backfill::spawn_backfill,
+    consent::tell_opted_out,
+    follow_back::send_follow_back,
+    relay::{handle_relay_follow, is_relay_follow},
+    InboxErr,
+};
+
+/// users follow a tag and are followed back so that their
+/// posts are delivered to us and can be boosted. follows of the
+/// public collection or the instance actor are relay subscriptions
+///
+/// follows of an alias are accepted by the alias but are stored
+/// against the canonical tag, which is also the one to follow back.
+/// follows of banned tags and from banned users are rejected, as are
+/// those of users that opted out of being boosted, who are told why
+pub async fn handle_follow(conn: &PgConn, config: &Config, follow: Follow) -> Result<(), InboxErr> {
+    let domain = &config.instance_domain;
+    if is_relay_follow(&follow, domain) {
+        return handle_relay_follow(conn, config, follow).await;
+    }
+    let Some(name) = Tag::name_from_activitypub_id(&follow.object, domain) else {
+        return Err(InboxErr::NotATag);
+    };
+    let instance_actor = conn.get_or_init_instance_actor().await;
+    let mut private_key = instance_actor.private_key();
+
+    let user = conn
+        .get_or_init_user_by_id(
+            &follow.actor,
+            &mut private_key,
+            &InstanceActor::pub_key_id(domain),
+        )
+        .await
+        .map_err(InboxErr::FetchErr)?;
+    if let Some(instance) = conn.get_instance(&user.domain).await {
+        if instance.blocked {
+            return Err(InboxErr::InstanceBlocked);
+        }
+    }
+    // already accepted and followed back
+    if conn.is_follow_recorded(&follow.id).await {
+        return Ok(());
+    }
+
+    // tags matching a ban rule, or scoped to a tag that does not
+    // exist, are refused without ever being created
+    let bans = conn.get_tag_bans(&config.reserved_tags).await;
+    let requested = conn.get_or_init_unbanned_tag(&name, &bans).await;
+    // the response always comes from the actor that was followed, as we
+    // serve it. a follow of `rust?lang=de` is answered by `rust.de` and a
+    // follow of an alias by the alias. tags that were refused before
+    // being created can only answer as the object of the follow
+    let (responder, responder_key_id) = match &requested {
+        Some(tag) => (tag.activitypub_id(domain), tag.pub_key_id(domain)),
+        None => (follow.object.clone(), format!("{}#main-key", follow.object)),
+    };
+    let followed = match requested {
+        Some(tag) => Some(conn.get_canonical_tag(tag).await),
+        None => None,
+    };
+    let opted_out = config.opted_out(&user);
+    let refused = user.banned || opted_out.is_some();
+    let (followed, rejected) = match followed {
+        Some(tag) if !bans.is_banned(&tag) && !refused => (Some(tag), None),
+        tag => (None, tag),
+    };
+
+    let response = FollowResponse {
+        type_field: match followed {
+            Some(_) => ResponseType::Accept,
+            None => ResponseType::Reject,
+        },
+        id: activity_id(domain),
+        actor: responder,
+        object: FollowRef::Follow(follow.clone()),
+    };
+    if let Err(err) = deliver(
+        user.inbox.clone(),
+        &response.wrap_context(),
+        &responder_key_id,
+        &mut private_key,
+    )
+    .await
+    {
+        println!("failed to respond to follow {}: {}", follow.id, err);
+    }
+    let Some(followed) = followed else {
+        if let (Some(tag), Some(opt_out)) = (rejected, opted_out) {
+            tell_opted_out(config, &mut private_key, &[tag], &user, opt_out).await;
+        }
+        return Ok(());
+    };
+
+    // a follow replacing one the user already had is not backfilled again
+    let existed = conn.is_following(&user, &followed).await;
+    let (tag, ufid) = conn.follow_tag(&user, followed, follow.id).await;
+
+    send_follow_back(conn, config, &mut private_key, &tag, ufid, &user).await;
+    if !existed {
+        spawn_backfill(conn.clone(), config.clone(), user, tag);
+    }
+    Ok(())
+}
+
+/// a unique id for an activity that is not stored
+pub fn activity_id(domain: &str) -> Url {
+    Url::parse(&format!("https://{domain}/activities/{}", Uuid::now_v7())).unwrap()
+}
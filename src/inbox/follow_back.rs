@@ -0,0 +1,199 @@
+//! users are followed back so that their posts are delivered to us.
+//! depending on [`FollowMode`] each tag follows them back or the
+//! instance actor follows them once for all of their tags. changing the
+//! mode undoes the follow-backs sent in the old one on the next start
+
+use actix_web::rt::spawn;
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    config::{Config, FollowMode},
+    cryptography::universal_keys::UniversalPrivate,
+    db::{
+        pg_conn::PgConn,
+        types::{instance_actor::InstanceActor, tag::Tag, user::User},
+    },
+    protocol::ap_protocol::fetch::deliver,
+    types::{
+        extensions::Extensions,
+        follow_and_response::{Follow, FollowType},
+        undo::{Undo, UndoType, Undoable},
+    },
+};
+
+use super::follow::activity_id;
+
+/// the tag following the user back, identified by the ufid of the user's follow
+pub fn follow_back(domain: &str, tag: &Tag, ufid: Uuid, user: &User) -> Follow {
+    Follow {
+        type_field: FollowType::Follow,
+        id: Url::parse(&format!("https://{domain}/follows/{ufid}")).unwrap(),
+        actor: tag.activitypub_id(domain),
+        object: user.activitypub_id.clone(),
+        extensions: Extensions::default(),
+    }
+}
+
+/// the instance actor following the user back, identified by its ifid
+pub fn instance_follow(domain: &str, ifid: Uuid, user: &User) -> Follow {
+    Follow {
+        type_field: FollowType::Follow,
+        id: Url::parse(&format!("https://{domain}/follows/{ifid}")).unwrap(),
+        actor: InstanceActor::activitypub_id(domain),
+        object: user.activitypub_id.clone(),
+        extensions: Extensions::default(),
+    }
+}
+
+/// called once the user follows the tag. in instance mode only
+/// the first tag the user follows sends a follow
+pub async fn send_follow_back(
+    conn: &PgConn,
+    config: &Config,
+    private_key: &mut UniversalPrivate,
+    tag: &Tag,
+    ufid: Uuid,
+    user: &User,
+) {
+    let domain = &config.instance_domain;
+    let (follow, key_id) = match config.follow_mode {
+        FollowMode::PerTag => (follow_back(domain, tag, ufid, user), tag.pub_key_id(domain)),
+        FollowMode::Instance => match conn.create_instance_follow(user).await {
+            Some(ifid) => (
+                instance_follow(domain, ifid, user),
+                InstanceActor::pub_key_id(domain),
+            ),
+            None => return,
+        },
+    };
+    send_follow(private_key, follow, &key_id, user).await;
+}
+
+/// called once the user no longer follows the tag. the instance actor
+/// only stops following once the user follows no tags at all, whichever
+/// mode it was started in
+pub async fn undo_follow_back(
+    conn: &PgConn,
+    config: &Config,
+    private_key: &mut UniversalPrivate,
+    tag: &Tag,
+    ufid: Uuid,
+    user: &User,
+) {
+    let domain = &config.instance_domain;
+    let mut undone = Vec::new();
+    if config.follow_mode == FollowMode::PerTag {
+        undone.push((follow_back(domain, tag, ufid, user), tag.pub_key_id(domain)));
+    }
+    if !conn.follows_any_tag(user).await {
+        if let Some(ifid) = conn.delete_instance_follow(user).await {
+            undone.push((
+                instance_follow(domain, ifid, user),
+                InstanceActor::pub_key_id(domain),
+            ));
+        }
+    }
+    for (follow, key_id) in undone {
+        send_undo(domain, private_key, follow, &key_id, user).await;
+    }
+}
+
+/// undoes the follow-backs sent in the follow mode of the previous run
+/// and sends them again in the configured one
+pub async fn switch_follow_mode(conn: &PgConn, config: &Config) {
+    let instance_actor = conn.get_or_init_instance_actor().await;
+    let mut private_key = instance_actor.private_key();
+    let domain = &config.instance_domain;
+    let mode = config.follow_mode.name();
+    // before the mode was recorded users were only followed back per tag
+    let previous = conn.get_follow_mode().await;
+    if previous.as_deref().unwrap_or(FollowMode::PerTag.name()) != mode {
+        match config.follow_mode {
+            FollowMode::PerTag => {
+                for (user, ifid) in conn.delete_instance_follows().await {
+                    let follow = instance_follow(domain, ifid, &user);
+                    let key_id = InstanceActor::pub_key_id(domain);
+                    send_undo(domain, &mut private_key, follow, &key_id, &user).await;
+                }
+                for (user, tag, ufid) in conn.get_all_following().await {
+                    let follow = follow_back(domain, &tag, ufid, &user);
+                    send_follow(&mut private_key, follow, &tag.pub_key_id(domain), &user).await;
+                }
+            }
+            FollowMode::Instance => {
+                for (user, tag, ufid) in conn.get_all_following().await {
+                    let follow = follow_back(domain, &tag, ufid, &user);
+                    send_undo(
+                        domain,
+                        &mut private_key,
+                        follow,
+                        &tag.pub_key_id(domain),
+                        &user,
+                    )
+                    .await;
+                    if let Some(ifid) = conn.create_instance_follow(&user).await {
+                        let follow = instance_follow(domain, ifid, &user);
+                        let key_id = InstanceActor::pub_key_id(domain);
+                        send_follow(&mut private_key, follow, &key_id, &user).await;
+                    }
+                }
+            }
+        }
+    }
+    if previous.as_deref() != Some(mode) {
+        conn.set_follow_mode(mode).await;
+    }
+}
+
+/// switches the follow mode in the background, see [`switch_follow_mode`].
+/// must be called from within the actix runtime
+pub fn spawn_follow_mode_switch(conn: PgConn, config: Config) {
+    spawn(async move { switch_follow_mode(&conn, &config).await });
+}
+
+async fn send_follow(
+    private_key: &mut UniversalPrivate,
+    follow: Follow,
+    key_id: &str,
+    user: &User,
+) {
+    if let Err(err) = deliver(
+        user.inbox.clone(),
+        &follow.wrap_context(),
+        key_id,
+        private_key,
+    )
+    .await
+    {
+        println!("failed to follow back {}: {}", user.activitypub_id, err);
+    }
+}
+
+async fn send_undo(
+    domain: &str,
+    private_key: &mut UniversalPrivate,
+    follow: Follow,
+    key_id: &str,
+    user: &User,
+) {
+    let undo = Undo {
+        type_field: UndoType::Undo,
+        id: activity_id(domain),
+        actor: follow.actor.clone(),
+        object: Undoable::Follow(follow),
+    };
+    if let Err(err) = deliver(
+        user.inbox.clone(),
+        &undo.wrap_context(),
+        key_id,
+        private_key,
+    )
+    .await
+    {
+        println!(
+            "failed to undo follow-back of {}: {}",
+            user.activitypub_id, err
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! acting on activities that have already been verified by
+//! [`crate::protocol::ap_protocol::verification::verify_post`]
+
+pub mod actor;
+pub mod announce;
+pub mod backfill;
+pub mod consent;
+pub mod dedupe;
+pub mod delete;
+pub mod follow;
+pub mod follow_back;
+pub mod move_account;
+pub mod policy;
+pub mod postable;
+pub mod queue;
+pub mod recent;
+pub mod relay;
+pub mod repost;
+pub mod undo;
+
+use std::time::Duration;
+
+use actix_web::rt::{spawn, time::sleep};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    db::pg_conn::PgConn,
+    protocol::errors::FetchErr,
+    types::{inboxable::VerifiedInboxable, share::SharedObject, update::Updatable},
+};
+
+/// how often rows older than their retention window are pruned
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// prunes rows older than `retention` milliseconds now and then every
+/// [`PRUNE_INTERVAL`]. must be called from within the actix runtime
+pub fn spawn_pruning(
+    conn: PgConn,
+    retention: i64,
+    prune: impl AsyncFn(&PgConn, i64) -> usize + 'static,
+) {
+    spawn(async move {
+        loop {
+            prune(&conn, retention).await;
+            sleep(PRUNE_INTERVAL).await;
+        }
+    });
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum InboxErr {
+    FetchErr(FetchErr),
+    /// the object of the activity is not a tag on this instance
+    NotATag,
+    InstanceBlocked,
+    /// the actor is not allowed to act on the object
+    Unauthorized,
+}
+
+impl std::fmt::Display for InboxErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InboxErr::FetchErr(x) => write!(f, "FetchErr: {}", x),
+            InboxErr::NotATag => write!(f, "NotATag"),
+            InboxErr::InstanceBlocked => write!(f, "InstanceBlocked"),
+            InboxErr::Unauthorized => write!(f, "Unauthorized"),
+        }
+    }
+}
+
+impl InboxErr {
+    /// failures that may go away on their own, everything
+    /// else is us refusing the activity
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, InboxErr::FetchErr(_))
+    }
+}
+
+pub async fn handle_inboxable(
+    conn: &PgConn,
+    config: &Config,
+    inboxable: VerifiedInboxable,
+) -> Result<(), InboxErr> {
+    match inboxable {
+        VerifiedInboxable::Postable(postable) => {
+            postable::handle_postable(conn, config, postable, None).await
+        }
+        VerifiedInboxable::Create(create) => {
+            let postable = create.object.clone();
+            let activity = SharedObject::Activity(Box::new(create));
+            postable::handle_postable(conn, config, postable, Some(&activity)).await
+        }
+        VerifiedInboxable::Update(update) => {
+            let Updatable::Postable(postable) = update.object.clone() else {
+                return Ok(());
+            };
+            let activity = SharedObject::Update(Box::new(update));
+            postable::handle_postable(conn, config, postable, Some(&activity)).await
+        }
+        VerifiedInboxable::Actor(actor) => actor::handle_actor_update(conn, config, actor).await,
+        VerifiedInboxable::Follow(follow) => follow::handle_follow(conn, config, follow).await,
+        VerifiedInboxable::Move(move_activity) => {
+            move_account::handle_move(conn, config, move_activity).await
+        }
+        VerifiedInboxable::Undo(undo) => undo::handle_undo(conn, config, undo).await,
+        VerifiedInboxable::Delete(delete) => delete::handle_delete(conn, config, delete).await,
+        // the only follow responses we get are for
+        // follow-backs which need no bookkeeping
+        VerifiedInboxable::FollowResponse(_) => Ok(()),
+        VerifiedInboxable::Unknown(_) => Ok(()),
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{
+    config::Config,
+    db::{pg_conn::PgConn, types::instance_actor::InstanceActor},
+    protocol::ap_protocol::fetch::authorized_fetch,
+    types::{actors::Actor, move_activity::Move},
+};
+
+use super::{
+    follow_back::{send_follow_back, undo_follow_back},
+    InboxErr,
+};
+
+/// a user migrating their account takes their tag follows with them.
+/// the new account must list the old one in its `alsoKnownAs`
+///
+/// the old account stops being followed back and the new one is followed
+/// back instead so that posts from the new account keep being boosted
+pub async fn handle_move(
+    conn: &PgConn,
+    config: &Config,
+    move_activity: Move,
+) -> Result<(), InboxErr> {
+    let domain = &config.instance_domain;
+    let Some(old) = conn.get_user_by_id(&move_activity.object).await else {
+        // they never followed anything
+        return Ok(());
+    };
+    let instance_actor = conn.get_or_init_instance_actor().await;
+    let mut private_key = instance_actor.private_key();
+
+    // always fetched fresh as a stored copy may predate the alias
+    let target: Actor = authorized_fetch(
+        move_activity.target.clone(),
+        &InstanceActor::pub_key_id(domain),
+        &mut private_key,
+    )
+    .await
+    .map_err(InboxErr::FetchErr)?;
+    if target.id.ne(&move_activity.target) || !target.is_also_known_as(&old.activitypub_id) {
+        return Err(InboxErr::Unauthorized);
+    }
+    let Some(target_domain) = target.id.host_str() else {
+        return Err(InboxErr::Unauthorized);
+    };
+    if let Some(instance) = conn.get_instance(target_domain).await {
+        if instance.blocked {
+            return Err(InboxErr::InstanceBlocked);
+        }
+    }
+    let Some(new) = conn.upsert_user(target).await else {
+        return Err(InboxErr::Unauthorized);
+    };
+    if new.banned {
+        return Err(InboxErr::Unauthorized);
+    }
+
+    for (tag, old_ufid, new_ufid) in conn.move_follows(&old, &new, domain).await {
+        undo_follow_back(conn, config, &mut private_key, &tag, old_ufid, &old).await;
+        if let Some(new_ufid) = new_ufid {
+            send_follow_back(conn, config, &mut private_key, &tag, new_ufid, &new).await;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,55 @@
+//! the per tag rules posts have to pass to be boosted, see
+//! [`crate::db::types::tag_policy::TagPolicy`]. each post a tag
+//! matches but skips is recorded with the reason for a few days
+
+use crate::{
+    config::{Config, DAY},
+    db::{
+        pg_conn::PgConn,
+        types::{tag::Tag, tag_policy::PostFacts, user::User},
+    },
+    types::postable::ApPostable,
+};
+
+use super::spawn_pruning;
+
+/// the tags whose policy the post passes
+pub async fn apply_policies(
+    conn: &PgConn,
+    postable: &ApPostable,
+    author: &User,
+    tags: Vec<Tag>,
+) -> Vec<Tag> {
+    let languages = postable.languages();
+    let facts = PostFacts {
+        reply: postable.in_reply_to().is_some(),
+        sensitive: postable.is_sensitive(),
+        media: postable.has_media(),
+        bot: author.bot,
+        languages: &languages,
+        hashtags: postable.hashtags().len(),
+        account_created: author.created_at,
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut passed = Vec::new();
+    for tag in tags {
+        match tag.policy.check(&facts, now) {
+            Ok(()) => passed.push(tag),
+            Err(skip) => {
+                conn.record_policy_skip(postable.id(), &tag, &skip.to_string())
+                    .await
+            }
+        }
+    }
+    passed
+}
+
+/// periodically forgets skips older than the retention window.
+/// must be called from within the actix runtime
+pub fn spawn_policy_skip_pruning(conn: PgConn, config: Config) {
+    spawn_pruning(
+        conn,
+        config.policy_skip_days * DAY,
+        PgConn::prune_policy_skips,
+    );
+}
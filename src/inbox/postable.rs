@@ -0,0 +1,506 @@
+use std::collections::{HashMap, HashSet};
+
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    config::{BoostPolicy, Config},
+    cryptography::universal_keys::UniversalPrivate,
+    db::{
+        pg_conn::PgConn,
+        types::{instance_actor::InstanceActor, post::Post, tag::Tag, user::User},
+    },
+    types::{
+        hashtag::{Hashtag, HashtagSource},
+        postable::ApPostable,
+        share::SharedObject,
+    },
+};
+
+use super::{
+    announce::{announce, undo_announce, undo_announces},
+    policy::apply_policies,
+    recent::record_recent_post,
+    repost::{handle_quote, handle_share},
+    InboxErr,
+};
+
+/// `activity` is the create or update the postable arrived in, if it had one
+pub async fn handle_postable(
+    conn: &PgConn,
+    config: &Config,
+    postable: ApPostable,
+    activity: Option<&SharedObject>,
+) -> Result<(), InboxErr> {
+    match postable {
+        // boosts don't carry tags of their own, what they boost might
+        ApPostable::Share(share) => handle_share(conn, config, share).await,
+        postable => boost_postable(conn, config, postable, activity).await,
+    }
+}
+
+/// boosts a public post from every tag it contains that its author follows,
+/// or that is relayed when the author's instance is a relay subscriber.
+/// tags that are aliases are boosted by their canonical tag and banned
+/// tags are never boosted
+///
+/// a post addressed to a tag that is a group is boosted by it whether or
+/// not its author follows the group, as that is how groups are posted to
+///
+/// tags whose policy the post fails are skipped, see [`apply_policies`]
+///
+/// a post we have already seen has been edited, so its tags are matched
+/// again. newly matching tags announce it and tags that no longer match
+/// undo their announce
+pub async fn boost_postable(
+    conn: &PgConn,
+    config: &Config,
+    postable: ApPostable,
+    activity: Option<&SharedObject>,
+) -> Result<(), InboxErr> {
+    let Some(domain) = postable.actor().domain() else {
+        return Ok(());
+    };
+    let subscribed = config.relay_subscriptions && conn.is_relay_subscriber(domain).await;
+    let addressed = addressed_tags(conn, config, &postable).await;
+    // only users that follow a tag are boosted, unless their
+    // instance relays to us or they posted to a group
+    let known = conn.get_user_by_id(postable.actor()).await;
+    if known.is_none() && !subscribed && addressed.is_empty() {
+        return Ok(());
+    }
+    // users that opted out of boosts are never boosted
+    if known
+        .as_ref()
+        .is_some_and(|user| user.banned || config.opted_out(user).is_some())
+    {
+        return Ok(());
+    }
+    if let Some(instance) = conn.get_instance(domain).await {
+        if instance.blocked {
+            return Err(InboxErr::InstanceBlocked);
+        }
+    }
+
+    let text = postable.text();
+    let mut tags = match postable.is_public() {
+        true => {
+            matching_tags(
+                conn,
+                config,
+                known.as_ref(),
+                subscribed,
+                &postable.hashtags(),
+                &postable.languages(),
+                &addressed,
+                text.as_deref(),
+            )
+            .await
+        }
+        false => Vec::new(),
+    };
+    if let (Some(user), Some(quote), true) = (&known, postable.quote(), postable.is_public()) {
+        for tag in handle_quote(conn, config, user, quote).await {
+            if !tags.iter().any(|x| x.id == tag.id) {
+                tags.push(tag);
+            }
+        }
+    }
+
+    let user = match known {
+        Some(user) => user,
+        // relayed authors are only fetched once they have something to boost
+        None => {
+            if tags.is_empty() {
+                return Ok(());
+            }
+            let instance_actor = conn.get_or_init_instance_actor().await;
+            let user = conn
+                .get_or_init_user_by_id(
+                    postable.actor(),
+                    &mut instance_actor.private_key(),
+                    &InstanceActor::pub_key_id(&config.instance_domain),
+                )
+                .await
+                .map_err(InboxErr::FetchErr)?;
+            if user.banned || config.opted_out(&user).is_some() {
+                return Ok(());
+            }
+            user
+        }
+    };
+    // only once the author is known not to have opted out
+    if let Some(text) = &text {
+        record_recent_post(conn, config, &postable, &user, text).await;
+    }
+
+    let tags = apply_policies(conn, &postable, &user, tags).await;
+
+    match conn.get_post(postable.id()).await {
+        None => {
+            if tags.is_empty() {
+                return Ok(());
+            }
+            let Some(pid) = conn
+                .create_post(postable.id(), postable.published(), &user, &tags)
+                .await
+            else {
+                // boosted by a concurrent delivery
+                return Ok(());
+            };
+            let instance_actor = conn.get_or_init_instance_actor().await;
+            let mut private_key = instance_actor.private_key();
+            boost(
+                conn,
+                config,
+                &mut private_key,
+                pid,
+                postable.id(),
+                activity,
+                postable.actor(),
+                tags,
+            )
+            .await;
+            Ok(())
+        }
+        Some(post) => {
+            if post.actor.ne(&user.id) {
+                return Err(InboxErr::Unauthorized);
+            }
+            retag_post(conn, config, post, postable.actor(), activity, tags).await;
+            Ok(())
+        }
+    }
+}
+
+/// the canonical, unbanned tags of the post that its author follows,
+/// or that are relayed when the author's instance is subscribed. tags
+/// with a keyword found in the text of the post match without the hashtag
+/// and groups match when the post is `addressed` to them. hashtags only
+/// count where [`Config::hashtag_counts`] says they do for the tag. a
+/// hashtag also matches the tags scoped to one of the post's `languages`
+///
+/// feeds match on their expression instead. following the feed or one
+/// of the tags of the post the expression asks for counts as following it
+#[allow(clippy::too_many_arguments)]
+async fn matching_tags(
+    conn: &PgConn,
+    config: &Config,
+    user: Option<&User>,
+    subscribed: bool,
+    hashtags: &[Hashtag],
+    languages: &[String],
+    addressed: &[String],
+    text: Option<&str>,
+) -> Vec<Tag> {
+    let bans = conn.get_tag_bans(&config.reserved_tags).await;
+    let keyword_tags: HashSet<i64> = match text {
+        Some(text) => conn
+            .get_keywords()
+            .await
+            .matching(text)
+            .map(|rule| rule.tag)
+            .collect(),
+        None => HashSet::new(),
+    };
+
+    // every hashtag of the post along with where it was found, and those
+    // of the tags the author follows, including the canonical names of aliases
+    let mut present = HashMap::new();
+    let mut followed = HashSet::new();
+    let mut candidates = Vec::new();
+    for hashtag in hashtags {
+        let name = hashtag.name.to_lowercase();
+        found(&mut present, &name, hashtag.source);
+        if let Some(tag) = conn.get_tag(&name).await {
+            candidates.push((Some((name.clone(), hashtag.source)), tag));
+        }
+        for tag in conn.get_scoped_tags(&name).await {
+            candidates.push((Some((name.clone(), hashtag.source)), tag));
+        }
+    }
+    for name in addressed {
+        if let Some(tag) = conn.get_tag(name).await {
+            candidates.push((None, tag));
+        }
+    }
+    for tag_id in &keyword_tags {
+        if let Some(tag) = conn.get_tag_by_id(*tag_id).await {
+            candidates.push((None, tag));
+        }
+    }
+
+    let mut tags: Vec<Tag> = Vec::new();
+    for (hashtag, tag) in candidates {
+        if bans.is_banned(&tag) || tag.expression.is_some() {
+            continue;
+        }
+        // aliases of a group can be posted to as well
+        let addressed_alias = addressed.contains(&tag.name);
+        let tag = conn.get_canonical_tag(tag).await;
+        let name = match hashtag {
+            Some((name, source)) => {
+                found(&mut present, &tag.name, source);
+                // keywords and groups are candidates of their own
+                if !config.hashtag_counts(source, &tag) {
+                    continue;
+                }
+                Some(name)
+            }
+            None => None,
+        };
+        if bans.is_banned(&tag) || tags.iter().any(|x| x.id == tag.id) {
+            continue;
+        }
+        if !tag.in_scope(languages) {
+            continue;
+        }
+        let following = match user {
+            Some(user) => conn.is_following(user, &tag).await,
+            None => false,
+        };
+        // following a scoped tag doesn't bring in feeds of the tag
+        if following && tag.language().is_none() {
+            followed.extend(name);
+            followed.insert(tag.name.clone());
+        }
+        let posted_to = config.is_group(&tag) && (addressed_alias || addressed.contains(&tag.name));
+        if following || posted_to || (subscribed && config.relays(&tag)) {
+            tags.push(tag);
+        }
+    }
+
+    for feed in conn.get_feeds().await {
+        let Some(expression) = feed.feed() else {
+            continue;
+        };
+        let hashtags: HashSet<String> = present
+            .iter()
+            .filter(|(_, source)| config.hashtag_counts(**source, &feed))
+            .map(|(name, _)| name.clone())
+            .collect();
+        let matches = expression.matches(&hashtags) || keyword_tags.contains(&feed.id);
+        if bans.is_banned(&feed) || feed.alias_of.is_some() || !matches {
+            continue;
+        }
+        let following = match user {
+            Some(user) => {
+                expression
+                    .included_tags()
+                    .iter()
+                    .any(|name| followed.contains(*name))
+                    || conn.is_following(user, &feed).await
+            }
+            None => false,
+        };
+        if following || (subscribed && config.relays(&feed)) {
+            tags.push(feed);
+        }
+    }
+    tags
+}
+
+/// keeps the most reliable place the hashtag was found
+fn found(present: &mut HashMap<String, HashtagSource>, name: &str, source: HashtagSource) {
+    let kept = present.entry(name.to_string()).or_insert(source);
+    *kept = (*kept).min(source);
+}
+
+/// the names of the groups on this instance the post is addressed to,
+/// lemmy addresses the group a post is in as its audience. addressing
+/// a tag that isn't a group is no reason to look at the post
+async fn addressed_tags(conn: &PgConn, config: &Config, postable: &ApPostable) -> Vec<String> {
+    if !config.group_actors && config.group_tags.is_empty() {
+        return Vec::new();
+    }
+    let mut addressed = Vec::new();
+    for recipient in postable.recipients() {
+        let Some(name) = Tag::name_from_activitypub_id(&recipient, &config.instance_domain) else {
+            continue;
+        };
+        let Some(tag) = conn.get_tag(&name).await else {
+            continue;
+        };
+        // aliases of a group can be posted to as well
+        if config.is_group(&conn.get_canonical_tag(tag).await) {
+            addressed.push(name);
+        }
+    }
+    addressed
+}
+
+/// announces the post according to the boost policy
+#[allow(clippy::too_many_arguments)]
+pub async fn boost(
+    conn: &PgConn,
+    config: &Config,
+    private_key: &mut UniversalPrivate,
+    pid: Uuid,
+    object: &Url,
+    activity: Option<&SharedObject>,
+    author: &Url,
+    tags: Vec<Tag>,
+) {
+    let object = |tag: &Tag| match activity {
+        Some(activity) if config.is_group(tag) => activity.clone(),
+        _ => SharedObject::Id(object.clone()),
+    };
+    match config.boost_policy {
+        BoostPolicy::EveryTag => {
+            for tag in &tags {
+                announce(
+                    conn,
+                    config,
+                    private_key,
+                    tag,
+                    &[],
+                    pid,
+                    object(tag),
+                    author,
+                )
+                .await;
+            }
+        }
+        BoostPolicy::BestTag => {
+            let tags = by_priority(tags, &config.tag_priority);
+            let Some((best, rest)) = tags.split_first() else {
+                return;
+            };
+            announce(
+                conn,
+                config,
+                private_key,
+                best,
+                rest,
+                pid,
+                object(best),
+                author,
+            )
+            .await;
+        }
+    }
+}
+
+/// orders tags by the configured priority, unlisted tags keep their order after
+fn by_priority(mut tags: Vec<Tag>, priority: &[String]) -> Vec<Tag> {
+    tags.sort_by_key(|tag| rank(tag, priority));
+    tags
+}
+
+fn rank(tag: &Tag, priority: &[String]) -> usize {
+    priority
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(&tag.name))
+        .unwrap_or(priority.len())
+}
+
+/// brings the announces of an existing post in line with the tags it now
+/// matches. a post that no longer matches any tag is forgotten entirely
+/// and only tags the edit added announce it
+async fn retag_post(
+    conn: &PgConn,
+    config: &Config,
+    post: Post,
+    author: &Url,
+    activity: Option<&SharedObject>,
+    tags: Vec<Tag>,
+) {
+    let instance_actor = conn.get_or_init_instance_actor().await;
+    let mut private_key = instance_actor.private_key();
+
+    if tags.is_empty() {
+        undo_announces(conn, config, &mut private_key, &post).await;
+        conn.delete_post(post).await;
+        return;
+    }
+
+    let current = conn.get_post_tags(post.id).await;
+    let mut deliveries = conn.get_post_deliveries(post.id).await;
+    for tag in current
+        .iter()
+        .filter(|x| !tags.iter().any(|t| t.id == x.id))
+    {
+        let inboxes = match deliveries.iter().position(|(t, _)| t.id == tag.id) {
+            Some(i) => deliveries.swap_remove(i).1,
+            None => Vec::new(),
+        };
+        undo_announce(config, &mut private_key, tag, &post, inboxes).await;
+        conn.remove_post_tag(post.id, tag).await;
+    }
+    let (added, kept): (Vec<Tag>, Vec<Tag>) = tags
+        .into_iter()
+        .partition(|tag| !current.iter().any(|x| x.id == tag.id));
+    for tag in &added {
+        conn.add_post_tag(post.id, tag).await;
+    }
+
+    // tags already on the post have announced it, only added tags announce
+    let tags = match config.boost_policy {
+        BoostPolicy::EveryTag => added,
+        // the edit may have changed which tag is best, only it keeps its
+        // announce. it announces again if it is new or tags were added
+        BoostPolicy::BestTag => {
+            let unchanged = added.is_empty();
+            let tags = by_priority(
+                kept.into_iter().chain(added).collect(),
+                &config.tag_priority,
+            );
+            let best = tags.first().map(|tag| tag.id);
+            let mut best_announced = false;
+            for (tag, inboxes) in deliveries {
+                if Some(tag.id) == best {
+                    best_announced = true;
+                    continue;
+                }
+                undo_announce(config, &mut private_key, &tag, &post, inboxes).await;
+                conn.remove_post_deliveries(post.id, &tag).await;
+            }
+            match unchanged && best_announced {
+                true => Vec::new(),
+                false => tags,
+            }
+        }
+    };
+    if tags.is_empty() {
+        return;
+    }
+
+    boost(
+        conn,
+        config,
+        &mut private_key,
+        post.id,
+        &post.activitypub_id,
+        activity,
+        author,
+        tags,
+    )
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::types::tag::{test_tag, Tag};
+
+    use super::by_priority;
+
+    #[test]
+    fn prioritized_tags_come_first() -> Result<(), String> {
+        let tags: Vec<Tag> = [(1, "programming"), (2, "rustlang"), (3, "rust")]
+            .into_iter()
+            .map(|(id, name)| Tag {
+                id,
+                ..test_tag(name)
+            })
+            .collect();
+        let priority = vec!["Rust".to_string(), "rustlang".to_string()];
+        let ordered: Vec<_> = by_priority(tags, &priority)
+            .into_iter()
+            .map(|tag| tag.id)
+            .collect();
+        match ordered.eq(&vec![3, 2, 1]) {
+            true => Ok(()),
+            false => Err(format!("unexpected order {:?}", ordered)),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! verified activities are stored and acknowledged straight away, then
+//! processed in the background so that slow fetches and fan-out never
+//! hold up the remote server delivering to us
+
+use std::time::Duration;
+
+use actix_web::rt::{spawn, time::sleep};
+
+use crate::{
+    config::Config,
+    db::{pg_conn::PgConn, types::inbox_queue::QueuedActivity},
+    protocol::ap_protocol::verification::parse_inboxable,
+    types::inboxable::VerifiedInboxable,
+};
+
+use super::{dedupe::dedupe_ids, handle_inboxable};
+
+/// how long a worker may hold an activity before it is considered crashed
+const LEASE: i64 = 5 * 60 * 1000;
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const BASE_BACKOFF: i64 = 30 * 1000;
+const MAX_BACKOFF: i64 = 6 * 60 * 60 * 1000;
+
+/// the body is queued as received rather than as what it deserialized to,
+/// so that nothing we don't model is lost. `origin` is the domain that
+/// signed it
+///
+/// activities seen before are dropped. they are only recorded as seen
+/// once queued, so a failed enqueue is retried rather than dropped
+pub async fn enqueue(conn: &PgConn, body: &str, origin: &str) {
+    conn.enqueue_activity(body, origin, dedupe_ids(body)).await;
+}
+
+/// starts `config.inbox_workers` workers, which bounds how many
+/// activities are processed at once. must be called from within
+/// the actix runtime
+pub fn spawn_inbox_workers(conn: PgConn, config: Config) {
+    for _ in 0..config.inbox_workers.max(1) {
+        spawn(worker(conn.clone(), config.clone()));
+    }
+}
+
+async fn worker(conn: PgConn, config: Config) {
+    loop {
+        match conn.claim_queued_activity(LEASE).await {
+            Some(queued) => process(&conn, &config, queued).await,
+            None => sleep(POLL_INTERVAL).await,
+        }
+    }
+}
+
+async fn process(conn: &PgConn, config: &Config, queued: QueuedActivity) {
+    let inboxable = match read_queued(&queued).await {
+        Ok(x) => x,
+        Err(err) => {
+            let error = format!("unreadable activity: {err}");
+            conn.dead_letter_queued_activity(queued.id, &error).await;
+            return;
+        }
+    };
+
+    // handled in its own task so that a panic only fails this activity
+    let handled = {
+        let (conn, config) = (conn.clone(), config.clone());
+        spawn(async move { handle_inboxable(&conn, &config, inboxable).await }).await
+    };
+    let error = match handled {
+        Ok(Ok(())) => {
+            conn.complete_queued_activity(queued.id).await;
+            return;
+        }
+        Ok(Err(err)) if !err.is_retryable() => {
+            // refused rather than failed, retrying would change nothing
+            println!("refused queued activity {}: {}", queued.id, err);
+            conn.complete_queued_activity(queued.id).await;
+            return;
+        }
+        Ok(Err(err)) => err.to_string(),
+        Err(_) => "processing panicked".to_string(),
+    };
+
+    if queued.attempts >= config.inbox_max_attempts {
+        println!("dead lettering queued activity {}: {}", queued.id, error);
+        conn.dead_letter_queued_activity(queued.id, &error).await;
+        return;
+    }
+    let next_attempt = chrono::Utc::now().timestamp_millis() + backoff(queued.attempts);
+    conn.retry_queued_activity(queued.id, next_attempt, &error)
+        .await;
+}
+
+/// parses the body again and verifies it for the domain that signed it
+async fn read_queued(queued: &QueuedActivity) -> Result<VerifiedInboxable, String> {
+    let Some(origin) = &queued.origin else {
+        return serde_json::from_str(&queued.activity).map_err(|x| x.to_string());
+    };
+    let inboxable =
+        parse_inboxable(&queued.activity).map_err(|x| serde_json::to_string(&x).unwrap())?;
+    inboxable
+        .verify(origin)
+        .await
+        .map_err(|x| serde_json::to_string(&x).unwrap())
+}
+
+/// doubles with every attempt
+fn backoff(attempts: i32) -> i64 {
+    let exponent = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    BASE_BACKOFF.saturating_mul(1 << exponent).min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{
+        db::types::inbox_queue::QueuedActivity,
+        types::{
+            inboxable::VerifiedInboxable,
+            postable::ApPostable,
+            update::{Updatable, Update},
+        },
+    };
+
+    use super::{backoff, read_queued, BASE_BACKOFF, MAX_BACKOFF};
+
+    fn queued(activity: &str, origin: Option<&str>) -> QueuedActivity {
+        QueuedActivity {
+            id: Uuid::now_v7(),
+            activity: activity.to_string(),
+            origin: origin.map(str::to_string),
+            received: 0,
+            attempts: 1,
+            next_attempt: 0,
+            last_error: None,
+        }
+    }
+
+    #[actix_web::test]
+    async fn queued_bodies_are_verified_again() -> Result<(), String> {
+        let create = r##"
+{
+	"type": "Create",
+	"id": "https://mastodon.social/users/Mastodon/statuses/1/activity",
+	"actor": "https://mastodon.social/users/Mastodon",
+	"object": {
+		"type": "Note",
+		"id": "https://mastodon.social/users/Mastodon/statuses/1",
+		"attributedTo": "https://mastodon.social/users/Mastodon",
+		"published": "2024-07-11T18:44:32Z",
+		"to": ["https://www.w3.org/ns/activitystreams#Public"],
+		"directMessage": false
+	}
+}
+        "##;
+        if read_queued(&queued(create, Some("evil.example")))
+            .await
+            .is_ok()
+        {
+            return Err("verified for a domain that did not sign it".to_string());
+        }
+        match read_queued(&queued(create, Some("mastodon.social"))).await? {
+            VerifiedInboxable::Create(create) => match create.object {
+                ApPostable::Note(note) if note.extensions.0.contains_key("directMessage") => Ok(()),
+                x => Err(format!("unexpected object {:?}", x)),
+            },
+            x => Err(format!("restored as the wrong activity: {:?}", x)),
+        }
+    }
+
+    #[actix_web::test]
+    async fn edits_are_kept_whole() -> Result<(), String> {
+        let update = r##"
+{
+	"type": "Update",
+	"id": "https://mastodon.social/users/Mastodon/statuses/1#updates/1",
+	"actor": "https://mastodon.social/users/Mastodon",
+	"object": {
+		"type": "Note",
+		"id": "https://mastodon.social/users/Mastodon/statuses/1",
+		"attributedTo": "https://mastodon.social/users/Mastodon",
+		"published": "2024-07-11T18:44:32Z",
+		"to": ["https://www.w3.org/ns/activitystreams#Public"]
+	}
+}
+        "##;
+        // groups announce the update rather than the edited post
+        match read_queued(&queued(update, Some("mastodon.social"))).await? {
+            VerifiedInboxable::Update(Update {
+                object: Updatable::Postable(ApPostable::Note(_)),
+                ..
+            }) => Ok(()),
+            x => Err(format!("restored as the wrong activity: {:?}", x)),
+        }
+    }
+
+    #[test]
+    fn queued_post_round_trips() -> Result<(), String> {
+        let note = r##"
+{
+	"type": "Note",
+	"id": "https://mastodon.social/users/Mastodon/statuses/112769333503182077",
+	"attributedTo": "https://mastodon.social/users/Mastodon",
+	"published": "2024-07-11T18:44:32Z",
+	"to": ["https://www.w3.org/ns/activitystreams#Public"],
+	"tag": [
+		{
+			"type": "Hashtag",
+			"href": "https://mastodon.social/tags/hiring",
+			"name": "#hiring"
+		}
+	]
+}
+        "##;
+        let queued: VerifiedInboxable = serde_json::from_str(note).map_err(|x| x.to_string())?;
+        let stored = serde_json::to_string(&queued).map_err(|x| x.to_string())?;
+        let restored: VerifiedInboxable =
+            serde_json::from_str(&stored).map_err(|x| format!("{x}: {stored}"))?;
+        match restored {
+            VerifiedInboxable::Postable(ApPostable::Note(note)) => match note.get_tags() {
+                Some(tags) if tags.eq(&vec!["#hiring".to_string()]) => Ok(()),
+                x => Err(format!("unexpected tags {:?}", x)),
+            },
+            x => Err(format!("restored as the wrong activity: {:?}", x)),
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_until_capped() -> Result<(), String> {
+        let expected = [
+            (1, BASE_BACKOFF),
+            (2, BASE_BACKOFF * 2),
+            (3, BASE_BACKOFF * 4),
+            (100, MAX_BACKOFF),
+        ];
+        for (attempts, delay) in expected {
+            if backoff(attempts) != delay {
+                return Err(format!(
+                    "attempt {attempts} waited {} instead of {delay}",
+                    backoff(attempts)
+                ));
+            }
+        }
+        Ok(())
+    }
+}
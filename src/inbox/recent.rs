@@ -0,0 +1,79 @@
+//! the text of public posts is kept for a few days so that admins can
+//! see what a keyword would match before adding it to a tag, see
+//! [`PgConn::preview_keywords`]
+
+use crate::{
+    config::{Config, DAY},
+    db::{pg_conn::PgConn, types::user::User},
+    types::postable::ApPostable,
+};
+
+use super::spawn_pruning;
+
+/// the author must already be known, so that the text of authors who
+/// opted out is never stored
+pub async fn record_recent_post(
+    conn: &PgConn,
+    config: &Config,
+    postable: &ApPostable,
+    author: &User,
+    text: &str,
+) {
+    if keeps_text(config, postable, author) {
+        conn.record_recent_post(postable.id(), postable.actor(), text)
+            .await;
+    }
+}
+
+fn keeps_text(config: &Config, postable: &ApPostable, author: &User) -> bool {
+    config.keyword_preview_days > 0
+        && postable.is_public()
+        && !author.banned
+        && config.opted_out(author).is_none()
+}
+
+/// periodically forgets the text of posts older than the preview window.
+/// must be called from within the actix runtime
+pub fn spawn_recent_post_pruning(conn: PgConn, config: Config) {
+    spawn_pruning(
+        conn,
+        config.keyword_preview_days * DAY,
+        PgConn::prune_recent_posts,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::test_config,
+        db::types::{consent::OptOut, user::test_user},
+        types::postable::ApPostable,
+    };
+
+    use super::keeps_text;
+
+    #[test]
+    fn opted_out_authors_leave_no_text() -> Result<(), String> {
+        let note = r##"
+{
+	"type": "Note",
+	"id": "https://relayed.example/users/alice/statuses/1",
+	"attributedTo": "https://relayed.example/users/alice",
+	"published": "2024-07-11T18:44:32Z",
+	"to": ["https://www.w3.org/ns/activitystreams#Public"],
+	"content": "<p>posts about #rust</p>"
+}
+        "##;
+        let postable: ApPostable = serde_json::from_str(note).map_err(|x| x.to_string())?;
+        let config = test_config();
+        let mut author = test_user("alice", "relayed.example");
+        if !keeps_text(&config, &postable, &author) {
+            return Err("the text of a public post was not kept".to_string());
+        }
+        author.opt_out = Some(OptOut::NoBot);
+        match keeps_text(&config, &postable, &author) {
+            true => Err("kept the text of an author who opted out".to_string()),
+            false => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! instances can subscribe to us the way they would to a relay.
+//! mastodon follows the public collection and litepub follows the
+//! relay's actor, both are sent to the instance actor
+//!
+//! subscribers receive the announces of the relayed tags, see
+//! [`Config::relays`], and the public posts they deliver to us are
+//! matched against those tags as if their authors followed them
+
+use crate::{
+    config::Config,
+    db::{pg_conn::PgConn, types::instance_actor::InstanceActor},
+    protocol::ap_protocol::fetch::deliver,
+    types::follow_and_response::{Follow, FollowRef, FollowResponse, ResponseType},
+};
+
+use super::{follow::activity_id, InboxErr};
+
+const PUBLIC: &str = "https://www.w3.org/ns/activitystreams#Public";
+
+/// whether the follow is a subscription to us as a relay
+pub fn is_relay_follow(follow: &Follow, domain: &str) -> bool {
+    follow.object.as_str().eq(PUBLIC) || follow.object.eq(&InstanceActor::activitypub_id(domain))
+}
+
+/// accepts the subscription, or rejects it when relay subscriptions
+/// are turned off or the subscriber is banned
+pub async fn handle_relay_follow(
+    conn: &PgConn,
+    config: &Config,
+    follow: Follow,
+) -> Result<(), InboxErr> {
+    let domain = &config.instance_domain;
+    let instance_actor = conn.get_or_init_instance_actor().await;
+    let mut private_key = instance_actor.private_key();
+
+    let user = conn
+        .get_or_init_user_by_id(
+            &follow.actor,
+            &mut private_key,
+            &InstanceActor::pub_key_id(domain),
+        )
+        .await
+        .map_err(InboxErr::FetchErr)?;
+    if let Some(instance) = conn.get_instance(&user.domain).await {
+        if instance.blocked {
+            return Err(InboxErr::InstanceBlocked);
+        }
+    }
+    let accepted = config.relay_subscriptions && !user.banned;
+    if accepted {
+        conn.create_relay_subscription(&user, &follow.id).await;
+    }
+
+    // subscriptions are always answered by the instance actor, mastodon
+    // matches the response on the follow rather than who followed
+    let response = FollowResponse {
+        type_field: match accepted {
+            true => ResponseType::Accept,
+            false => ResponseType::Reject,
+        },
+        id: activity_id(domain),
+        actor: InstanceActor::activitypub_id(domain),
+        object: FollowRef::Id(follow.id.clone()),
+    };
+    if let Err(err) = deliver(
+        user.inbox.clone(),
+        &response.wrap_context(),
+        &InstanceActor::pub_key_id(domain),
+        &mut private_key,
+    )
+    .await
+    {
+        println!("failed to respond to relay follow {}: {}", follow.id, err);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{context::ContextWrap, follow_and_response::Follow};
+
+    use super::is_relay_follow;
+
+    #[test]
+    fn mastodon_relay_follow_is_recognized() -> Result<(), String> {
+        // the follow mastodon sends when a relay is added in its admin panel
+        let follow = r##"
+{
+	"@context": "https://www.w3.org/ns/activitystreams",
+	"id": "https://mastodon.example/2a8b3f1c-5d2e-4c8a-9f3b-7e6d5c4b3a21",
+	"type": "Follow",
+	"actor": "https://mastodon.example/actor",
+	"object": "https://www.w3.org/ns/activitystreams#Public"
+}
+        "##;
+        let follow: ContextWrap<Follow> =
+            serde_json::from_str(follow).map_err(|x| x.to_string())?;
+        let mut tag_follow = follow.item.clone();
+        tag_follow.object = url::Url::parse("https://tags.example/tags/rust").unwrap();
+        match (
+            is_relay_follow(&follow.item, "tags.example"),
+            is_relay_follow(&tag_follow, "tags.example"),
+        ) {
+            (true, false) => Ok(()),
+            x => Err(format!("unexpected relay follow detection {:?}", x)),
+        }
+    }
+}
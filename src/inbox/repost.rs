@@ -0,0 +1,311 @@
+//! followers boosting or quoting posts that carry a tag they follow.
+//! what happens is up to each tag's [`AnnouncePolicy`] and
+//! [`QuotePolicy`], by default nothing does. a post a tag has
+//! boosted is never boosted again this way
+
+use url::Url;
+
+use crate::{
+    config::Config,
+    db::{
+        pg_conn::PgConn,
+        types::{
+            instance_actor::InstanceActor,
+            repost::{AnnouncePolicy, QuotePolicy},
+            tag::Tag,
+            user::User,
+        },
+    },
+    protocol::ap_protocol::fetch::authorized_fetch,
+    types::{
+        postable::ApPostable,
+        share::{Share, SharedObject},
+        update::Updatable,
+    },
+};
+
+use super::{policy::apply_policies, postable::boost, InboxErr};
+
+/// how many boosts of boosts are followed to reach the original
+const MAX_DEPTH: usize = 2;
+
+/// a follower boosting a post, the post is boosted by the followed
+/// tags it carries that boost originals
+pub async fn handle_share(conn: &PgConn, config: &Config, share: Share) -> Result<(), InboxErr> {
+    let Some(booster) = conn.get_user_by_id(&share.actor).await else {
+        return Ok(());
+    };
+    if booster.banned {
+        return Ok(());
+    }
+    if !wanted(conn, &booster, Repost::Boost).await {
+        return Ok(());
+    }
+    let original = match share.object {
+        SharedObject::Id(id) => id,
+        // an embedded post is not signed by its author so it is fetched
+        SharedObject::Activity(create) => create.object.id().clone(),
+        SharedObject::Update(update) => match update.object {
+            Updatable::Postable(postable) => postable.id().clone(),
+            Updatable::Actor(_) => return Ok(()),
+        },
+    };
+    let Some(original) = fetch_original(conn, config, original).await else {
+        return Ok(());
+    };
+    let tags = followed_tags(conn, config, &booster, &original)
+        .await
+        .into_iter()
+        .filter(|tag| wants_repost(Repost::Boost, tag))
+        .collect();
+    boost_original(conn, config, original, tags).await
+}
+
+/// a follower quoting a post. the quoted post is boosted by the followed
+/// tags it carries that boost originals, and the tags that boost quotes
+/// are returned for the quote to be boosted by
+pub async fn handle_quote(conn: &PgConn, config: &Config, quoter: &User, quote: &Url) -> Vec<Tag> {
+    if is_local(config, quote) {
+        return Vec::new();
+    }
+    let tags = match conn.get_post(quote).await {
+        // boosted already, so we know its tags without a fetch
+        Some(post) => {
+            let mut tags = Vec::new();
+            for tag in conn.get_post_tags(post.id).await {
+                if conn.is_following(quoter, &tag).await {
+                    tags.push(tag);
+                }
+            }
+            tags
+        }
+        None => {
+            if !wanted(conn, quoter, Repost::Quote).await {
+                return Vec::new();
+            }
+            let Some(original) = fetch_original(conn, config, quote.clone()).await else {
+                return Vec::new();
+            };
+            let (originals, tags): (Vec<_>, Vec<_>) =
+                followed_tags(conn, config, quoter, &original)
+                    .await
+                    .into_iter()
+                    .partition(|tag| tag.quote_policy == QuotePolicy::BoostOriginal);
+            if let Err(err) = boost_original(conn, config, original, originals).await {
+                println!(
+                    "failed to boost the post quoted by {}: {}",
+                    quoter.activitypub_id, err
+                );
+            }
+            tags
+        }
+    };
+    tags.into_iter()
+        .filter(|tag| tag.quote_policy == QuotePolicy::BoostQuote)
+        .collect()
+}
+
+/// how a follower reposted a post
+#[derive(Clone, Copy)]
+enum Repost {
+    Boost,
+    Quote,
+}
+
+/// whether the tag does anything when one of its followers reposts a
+/// post carrying it
+fn wants_repost(repost: Repost, tag: &Tag) -> bool {
+    match repost {
+        Repost::Boost => tag.announce_policy == AnnouncePolicy::BoostOriginal,
+        Repost::Quote => tag.quote_policy != QuotePolicy::Ignore,
+    }
+}
+
+/// whether any tag the user follows does anything with their reposts,
+/// what they repost is only fetched when one does
+async fn wanted(conn: &PgConn, user: &User, repost: Repost) -> bool {
+    conn.get_followed_tags(user)
+        .await
+        .iter()
+        .any(|tag| wants_repost(repost, tag))
+}
+
+/// our own announces, boosting them would boost what they already boost
+fn is_local(config: &Config, id: &Url) -> bool {
+    id.domain() == Some(config.instance_domain.as_str())
+}
+
+/// fetches a public post that no tag has boosted yet, following boosts
+/// of boosts to the post they boost
+async fn fetch_original(conn: &PgConn, config: &Config, mut id: Url) -> Option<ApPostable> {
+    let instance_actor = conn.get_or_init_instance_actor().await;
+    let mut private_key = instance_actor.private_key();
+    let key_id = InstanceActor::pub_key_id(&config.instance_domain);
+    for _ in 0..MAX_DEPTH {
+        if is_local(config, &id) || conn.get_post(&id).await.is_some() {
+            return None;
+        }
+        let domain = id.domain()?.to_string();
+        let fetched: ApPostable = authorized_fetch(id, &key_id, &mut private_key).await.ok()?;
+        match fetched.verify(&domain).ok()? {
+            ApPostable::Share(share) => id = share.object.id().clone(),
+            postable => return postable.is_public().then_some(postable),
+        }
+    }
+    None
+}
+
+/// the canonical, unbanned tags of the post that the user follows
+async fn followed_tags(
+    conn: &PgConn,
+    config: &Config,
+    user: &User,
+    postable: &ApPostable,
+) -> Vec<Tag> {
+    let bans = conn.get_tag_bans(&config.reserved_tags).await;
+    let languages = postable.languages();
+    let mut candidates = Vec::new();
+    for hashtag in postable.hashtags() {
+        let name = hashtag.name.to_lowercase();
+        if let Some(tag) = conn.get_tag(&name).await {
+            candidates.push((hashtag.source, tag));
+        }
+        for tag in conn.get_scoped_tags(&name).await {
+            candidates.push((hashtag.source, tag));
+        }
+    }
+    let mut tags: Vec<Tag> = Vec::new();
+    for (source, tag) in candidates {
+        if bans.is_banned(&tag) || tag.expression.is_some() {
+            continue;
+        }
+        let tag = conn.get_canonical_tag(tag).await;
+        if bans.is_banned(&tag) || tags.iter().any(|x| x.id == tag.id) {
+            continue;
+        }
+        if !config.hashtag_counts(source, &tag) {
+            continue;
+        }
+        if tag.in_scope(&languages) && conn.is_following(user, &tag).await {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// boosts a post on behalf of whoever boosted or quoted it, its author
+/// does not need to follow the tags
+async fn boost_original(
+    conn: &PgConn,
+    config: &Config,
+    original: ApPostable,
+    tags: Vec<Tag>,
+) -> Result<(), InboxErr> {
+    if tags.is_empty() {
+        return Ok(());
+    }
+    let Some(domain) = original.actor().domain() else {
+        return Ok(());
+    };
+    if conn
+        .get_instance(domain)
+        .await
+        .is_some_and(|instance| instance.blocked)
+    {
+        return Ok(());
+    }
+    let instance_actor = conn.get_or_init_instance_actor().await;
+    let mut private_key = instance_actor.private_key();
+    let author = conn
+        .get_or_init_user_by_id(
+            original.actor(),
+            &mut private_key,
+            &InstanceActor::pub_key_id(&config.instance_domain),
+        )
+        .await
+        .map_err(InboxErr::FetchErr)?;
+    if author.banned || config.opted_out(&author).is_some() {
+        return Ok(());
+    }
+    let tags = apply_policies(conn, &original, &author, tags).await;
+    if tags.is_empty() {
+        return Ok(());
+    }
+    let Some(pid) = conn
+        .create_post(original.id(), original.published(), &author, &tags)
+        .await
+    else {
+        // boosted by a concurrent delivery
+        return Ok(());
+    };
+    boost(
+        conn,
+        config,
+        &mut private_key,
+        pid,
+        original.id(),
+        None,
+        original.actor(),
+        tags,
+    )
+    .await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::types::{
+        repost::{AnnouncePolicy, QuotePolicy},
+        tag::{test_tag, Tag},
+    };
+
+    use super::{wants_repost, Repost};
+
+    #[test]
+    fn reposts_are_only_wanted_by_tags_that_act_on_them() -> Result<(), String> {
+        let cases = [
+            (
+                Repost::Boost,
+                AnnouncePolicy::Ignore,
+                QuotePolicy::BoostQuote,
+                false,
+            ),
+            (
+                Repost::Boost,
+                AnnouncePolicy::BoostOriginal,
+                QuotePolicy::Ignore,
+                true,
+            ),
+            (
+                Repost::Quote,
+                AnnouncePolicy::BoostOriginal,
+                QuotePolicy::Ignore,
+                false,
+            ),
+            (
+                Repost::Quote,
+                AnnouncePolicy::Ignore,
+                QuotePolicy::BoostOriginal,
+                true,
+            ),
+            (
+                Repost::Quote,
+                AnnouncePolicy::Ignore,
+                QuotePolicy::BoostQuote,
+                true,
+            ),
+        ];
+        for (i, (repost, announce_policy, quote_policy, expected)) in cases.into_iter().enumerate()
+        {
+            let tag = Tag {
+                announce_policy,
+                quote_policy,
+                ..test_tag("rust")
+            };
+            if wants_repost(repost, &tag) != expected {
+                return Err(format!("case {i} was not {expected}"));
+            }
+        }
+        Ok(())
+    }
+}
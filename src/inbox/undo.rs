@@ -0,0 +1,54 @@
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    db::{
+        pg_conn::PgConn,
+        types::{tag::Tag, user::User},
+    },
+    types::{
+        follow_and_response::Follow,
+        undo::{Undo, Undoable},
+    },
+};
+
+use super::{follow_back::undo_follow_back, InboxErr};
+
+pub async fn handle_undo(conn: &PgConn, config: &Config, undo: Undo) -> Result<(), InboxErr> {
+    match undo.object {
+        Undoable::Follow(follow) => {
+            let unfollowed = match conn.unfollow_tag(&follow.id).await {
+                Some(unfollowed) => Some(unfollowed),
+                None => unfollow_moved(conn, config, &follow).await,
+            };
+            // the follow may never have been accepted
+            let Some((user, tag, ufid)) = unfollowed else {
+                // or was a relay subscription
+                conn.delete_relay_subscription(&follow.id).await;
+                return Ok(());
+            };
+            let instance_actor = conn.get_or_init_instance_actor().await;
+            let mut private_key = instance_actor.private_key();
+            undo_follow_back(conn, config, &mut private_key, &tag, ufid, &user).await;
+            Ok(())
+        }
+        // boosts of other users are never stored, an original
+        // boosted because of one stays boosted
+        Undoable::Announce(_) => Ok(()),
+    }
+}
+
+/// follows moved over from another account have an id the new account
+/// never saw, so its undo is matched on who unfollowed which tag instead
+async fn unfollow_moved(
+    conn: &PgConn,
+    config: &Config,
+    follow: &Follow,
+) -> Option<(User, Tag, Uuid)> {
+    let domain = &config.instance_domain;
+    let name = Tag::name_from_activitypub_id(&follow.object, domain)?;
+    let user = conn.get_user_by_id(&follow.actor).await?;
+    let tag = conn.get_canonical_tag(conn.get_tag(&name).await?).await;
+    let ufid = conn.unfollow_issued(&user, &tag, domain).await?;
+    Some((user, tag, ufid))
+}
@@ -1,6 +1,7 @@
 pub mod config;
 pub mod cryptography;
 pub mod db;
+pub mod inbox;
 pub mod protocol;
 pub mod routes;
 pub mod types;
@@ -1,3 +1,50 @@
-fn main() {
-    println!("Hello, world!");
+use actix_web::{web::Data, App, HttpServer};
+use tags::{
+    config::get_config,
+    inbox::{
+        consent::spawn_opt_out_backfill, dedupe::spawn_dedupe_pruning,
+        follow_back::spawn_follow_mode_switch, policy::spawn_policy_skip_pruning,
+        queue::spawn_inbox_workers, recent::spawn_recent_post_pruning,
+    },
+    routes::{
+        activitypub::routes::get_activitypub_routes, admin::routes::get_admin_routes,
+        well_known::routes::get_well_known_routes,
+    },
+};
+
+mod embedded {
+    refinery::embed_migrations!("migrations");
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    let config = get_config().expect("failed to load config");
+    let conn = config.create_conn();
+
+    let mut client = conn.db.get().await.expect("failed to get client");
+    embedded::migrations::runner()
+        .run_async(&mut **client)
+        .await
+        .expect("failed to run migrations");
+    drop(client);
+
+    spawn_inbox_workers(conn.clone(), config.clone());
+    spawn_dedupe_pruning(conn.clone(), config.clone());
+    spawn_recent_post_pruning(conn.clone(), config.clone());
+    spawn_policy_skip_pruning(conn.clone(), config.clone());
+    spawn_follow_mode_switch(conn.clone(), config.clone());
+    spawn_opt_out_backfill(conn.clone(), config.clone());
+
+    let bind = (config.bind_address.clone(), config.port);
+    HttpServer::new(move || {
+        App::new()
+            .app_data(Data::new(config.clone()))
+            .app_data(Data::new(conn.clone()))
+            .configure(get_activitypub_routes)
+            .service(get_well_known_routes())
+            .service(get_admin_routes())
+    })
+    .bind(bind)?
+    .run()
+    .await
 }
@@ -0,0 +1,204 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    time::Duration,
+};
+
+use actix_web::rt::time::sleep;
+use futures_util::{stream, Stream};
+use url::Url;
+
+use crate::{
+    cryptography::key::PrivateKey,
+    db::pg_conn::PgConn,
+    protocol::errors::FetchErr,
+    types::collection::{Collection, CollectionPageLink},
+};
+
+use super::fetch::authorized_fetch;
+
+/// how much of a collection is walked before giving up
+#[derive(Clone, Copy, Debug)]
+pub struct WalkLimits {
+    /// pages fetched, embedded pages are free
+    pub max_pages: usize,
+    pub max_items: usize,
+}
+
+/// an item of a collection, which may be embedded or just its id
+#[derive(Clone, Debug)]
+pub enum CollectionItem {
+    Id(Url),
+    Object(serde_json::Value),
+}
+
+impl CollectionItem {
+    fn from_value(value: serde_json::Value) -> Self {
+        match &value {
+            serde_json::Value::String(id) => match Url::parse(id) {
+                Ok(id) => CollectionItem::Id(id),
+                Err(_) => CollectionItem::Object(value),
+            },
+            _ => CollectionItem::Object(value),
+        }
+    }
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            CollectionItem::Id(id) => Some(id.as_str()),
+            CollectionItem::Object(object) => object.get("id").and_then(|id| id.as_str()),
+        }
+    }
+}
+
+struct Walk<'a, K: PrivateKey> {
+    next: Option<CollectionPageLink>,
+    buffer: VecDeque<CollectionItem>,
+    seen_items: HashSet<String>,
+    seen_pages: HashSet<Url>,
+    yielded: usize,
+    limits: WalkLimits,
+    key_id: &'a str,
+    private_key: &'a mut K,
+    throttle: Option<(&'a PgConn, i64)>,
+}
+
+/// walks a collection page by page, following `first` and then `next`,
+/// and yields every item once. the collection and its pages may be
+/// embedded or fetched by their id
+///
+/// when `throttle` is given fetches to the collection's instance are
+/// spaced at least that many milliseconds apart. a failed fetch is
+/// yielded as the last item
+pub fn walk_collection<'a, K: PrivateKey>(
+    collection: CollectionPageLink,
+    key_id: &'a str,
+    private_key: &'a mut K,
+    limits: WalkLimits,
+    throttle: Option<(&'a PgConn, i64)>,
+) -> impl Stream<Item = Result<CollectionItem, FetchErr>> + 'a {
+    let walk = Walk {
+        next: Some(collection),
+        buffer: VecDeque::new(),
+        seen_items: HashSet::new(),
+        seen_pages: HashSet::new(),
+        yielded: 0,
+        limits,
+        key_id,
+        private_key,
+        throttle,
+    };
+    stream::unfold(walk, |mut walk| async move {
+        loop {
+            if walk.yielded >= walk.limits.max_items {
+                return None;
+            }
+            if let Some(item) = walk.buffer.pop_front() {
+                if let Some(id) = item.id() {
+                    if !walk.seen_items.insert(id.to_string()) {
+                        continue;
+                    }
+                }
+                walk.yielded += 1;
+                return Some((Ok(item), walk));
+            }
+            let mut page = match walk.next.take()? {
+                CollectionPageLink::Page(page) => *page,
+                CollectionPageLink::Id(id) => {
+                    // a page linking back to an earlier one would loop forever
+                    if walk.seen_pages.len() >= walk.limits.max_pages
+                        || !walk.seen_pages.insert(id.clone())
+                    {
+                        return None;
+                    }
+                    match fetch_page(&mut walk, id).await {
+                        Ok(page) => page,
+                        Err(err) => return Some((Err(err), walk)),
+                    }
+                }
+            };
+            walk.buffer.extend(
+                page.take_items()
+                    .into_iter()
+                    .map(CollectionItem::from_value),
+            );
+            walk.next = page.next.take().or(page.first.take());
+        }
+    })
+}
+
+async fn fetch_page<K: PrivateKey>(
+    walk: &mut Walk<'_, K>,
+    id: Url,
+) -> Result<Collection, FetchErr> {
+    if let (Some((conn, interval)), Some(domain)) = (walk.throttle, id.domain()) {
+        let wait = conn.wait_for_fetch_slot(domain, interval).await;
+        sleep(Duration::from_millis(wait as u64)).await;
+    }
+    authorized_fetch(id, walk.key_id, walk.private_key).await
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+
+    use crate::{
+        cryptography::{
+            key::{Algorithms, PrivateKey},
+            universal_keys::UniversalPrivate,
+        },
+        types::collection::{Collection, CollectionPageLink},
+    };
+
+    use super::{walk_collection, WalkLimits};
+
+    #[actix_web::test]
+    async fn embedded_pages_are_walked_and_deduped() -> Result<(), String> {
+        let collection = r##"
+{
+	"id": "https://example.com/users/alice/featured",
+	"type": "OrderedCollection",
+	"first": {
+		"type": "OrderedCollectionPage",
+		"orderedItems": [
+			"https://example.com/users/alice/statuses/1",
+			{ "id": "https://example.com/users/alice/statuses/2", "type": "Note" }
+		],
+		"next": {
+			"type": "OrderedCollectionPage",
+			"orderedItems": [
+				"https://example.com/users/alice/statuses/2",
+				"https://example.com/users/alice/statuses/3",
+				"https://example.com/users/alice/statuses/4"
+			]
+		}
+	}
+}
+        "##;
+        let collection: Collection = serde_json::from_str(collection).map_err(|x| x.to_string())?;
+        let mut key = UniversalPrivate::generate(Algorithms::Hs2019);
+        let limits = WalkLimits {
+            max_pages: 0,
+            max_items: 3,
+        };
+        let walk = walk_collection(
+            CollectionPageLink::Page(Box::new(collection)),
+            "https://example.com/actor#main-key",
+            &mut key,
+            limits,
+            None,
+        );
+        let ids: Vec<String> = walk
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .map(|item| item.map(|item| item.id().unwrap_or_default().to_string()))
+            .collect::<Result<_, _>>()
+            .map_err(|x| x.to_string())?;
+        let expected: Vec<String> = (1..=3)
+            .map(|x| format!("https://example.com/users/alice/statuses/{x}"))
+            .collect();
+        match ids.eq(&expected) {
+            true => Ok(()),
+            false => Err(format!("unexpected items {:?}", ids)),
+        }
+    }
+}
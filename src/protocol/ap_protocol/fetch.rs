@@ -1,6 +1,6 @@
 use super::super::errors::FetchErr;
-use reqwest::header::{ACCEPT, DATE, HOST};
-use serde::Deserialize;
+use reqwest::header::{ACCEPT, CONTENT_TYPE, DATE, HOST};
+use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
 use url::Url;
 
@@ -19,7 +19,11 @@ pub async fn authorized_fetch<T: PrivateKey, F: for<'a> Deserialize<'a>>(
     private_key: &mut T,
 ) -> Result<F, FetchErr> {
     let algorithm = private_key.algorithm();
-    let path = object_id.path();
+    // the query is part of the request target, collection pages rely on it
+    let path = match object_id.query() {
+        Some(query) => format!("{}?{}", object_id.path(), query),
+        None => object_id.path().to_string(),
+    };
     let Some(fetch_domain) = object_id.host_str() else {
         return Err(FetchErr::InvalidUrl(object_id.as_str().to_string()));
     };
@@ -95,7 +99,7 @@ pub async fn ap_post<T: PrivateKey>(
     let signature = private_key.sign(signed_string.as_bytes());
 
     let header = format!(
-        r#"keyId="{key_id}",algorithm="{algorithm}",headers="(request-target) host date accept",signature="{signature}""#
+        r#"keyId="{key_id}",algorithm="{algorithm}",headers="(request-target) host date digest accept",signature="{signature}""#
     );
     let fetch_domain = fetch_domain.to_string();
 
@@ -107,6 +111,7 @@ pub async fn ap_post<T: PrivateKey>(
         .header("Digest", digest)
         .header("Signature", header)
         .header(ACCEPT, "application/activity+json")
+        .header(CONTENT_TYPE, "application/activity+json")
         .body(object.to_owned());
 
     // dbg!(&client);
@@ -119,17 +124,31 @@ pub async fn ap_post<T: PrivateKey>(
         Err(x) => return Err(FetchErr::RequestErr(x.to_string())),
     };
 
-    if res.status() == 201 {
-        return Ok(());
+    if res.status().is_success() {
+        Ok(())
     } else {
-        return Err(FetchErr::RequestErr(format!(
+        Err(FetchErr::RequestErr(format!(
             "post got status: {} with body: {}",
             res.status(),
             res.text().await.unwrap_or("".to_string())
-        )));
+        )))
     }
 }
 
+/// serializes and signs an activity and posts it to the provided inbox
+pub async fn deliver<T: PrivateKey, A: Serialize>(
+    inbox: Url,
+    activity: &A,
+    key_id: &str,
+    private_key: &mut T,
+) -> Result<(), FetchErr> {
+    let body = serde_json::to_string(activity).expect("failed to serialize activity");
+    // mastodon only accepts sha-256 digests regardless of the signing algorithm
+    let digest = Algorithms::RsaSha256.hash(body.as_bytes());
+    let algorithm = private_key.algorithm();
+    ap_post(inbox, &body, &digest, key_id, private_key, algorithm).await
+}
+
 pub async fn webfinger_actor<T: PrivateKey>(
     key_id: &str,
     private_key: &mut T,
@@ -160,5 +179,5 @@ pub async fn webfinger_actor<T: PrivateKey>(
         return Err(FetchErr::VerifyErr(VerifyRequestErr::NoAuthority));
     }
 
-    todo!()
+    Ok(fetched)
 }
@@ -1,4 +1,6 @@
+pub mod collection;
 pub mod fetch;
+pub mod move_relay;
 pub mod notify_followers;
 pub mod signature;
 pub mod verification;
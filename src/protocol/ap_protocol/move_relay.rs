@@ -0,0 +1,46 @@
+use crate::{
+    config::Config,
+    db::pg_conn::PgConn,
+    inbox::follow::activity_id,
+    types::move_activity::{Move, MoveType},
+};
+
+use super::notify_followers::notify_followers;
+
+/// tells the followers of every tag that it has moved to its counterpart
+/// on `new_domain` so that they follow it there instead. the relay on the
+/// new domain must already list this one in its `previous_domains` or
+/// the moves are refused, and this relay should set `moved_to_domain`
+///
+/// aliases have no followers of their own and banned tags are left behind.
+/// returns the number of tags that were moved
+pub async fn move_relay(conn: &PgConn, config: &Config, new_domain: &str) -> usize {
+    let domain = &config.instance_domain;
+    let instance_actor = conn.get_or_init_instance_actor().await;
+    let mut private_key = instance_actor.private_key();
+    let bans = conn.get_tag_bans(&config.reserved_tags).await;
+
+    let mut moved = 0;
+    for tag in conn.get_tags().await {
+        if tag.alias_of.is_some() || bans.is_banned(&tag) {
+            continue;
+        }
+        let move_activity = Move {
+            type_field: MoveType::Move,
+            id: activity_id(domain),
+            actor: tag.activitypub_id(domain),
+            object: tag.activitypub_id(domain),
+            target: tag.activitypub_id(new_domain),
+        };
+        notify_followers(
+            conn,
+            domain,
+            &tag,
+            &move_activity.wrap_context(),
+            &mut private_key,
+        )
+        .await;
+        moved += 1;
+    }
+    moved
+}
@@ -1 +1,41 @@
+use serde::Serialize;
+use url::Url;
 
+use crate::{
+    cryptography::key::PrivateKey,
+    db::{pg_conn::PgConn, types::tag::Tag},
+};
+
+use super::fetch::deliver;
+
+/// sends an activity from a tag actor to the shared inbox of every
+/// one of its followers. failed deliveries are logged and skipped
+///
+/// returns the inboxes the activity was delivered to
+pub async fn notify_followers<T: PrivateKey, A: Serialize>(
+    conn: &PgConn,
+    domain: &str,
+    tag: &Tag,
+    activity: &A,
+    private_key: &mut T,
+) -> Vec<Url> {
+    let inboxes = conn.tag_unique_inboxes(tag).await;
+    deliver_to_inboxes(inboxes, activity, &tag.pub_key_id(domain), private_key).await
+}
+
+/// returns the inboxes the activity was delivered to
+pub async fn deliver_to_inboxes<T: PrivateKey, A: Serialize>(
+    inboxes: Vec<Url>,
+    activity: &A,
+    key_id: &str,
+    private_key: &mut T,
+) -> Vec<Url> {
+    let mut delivered = Vec::new();
+    for inbox in inboxes {
+        match deliver(inbox.clone(), activity, key_id, private_key).await {
+            Ok(()) => delivered.push(inbox),
+            Err(err) => println!("failed to deliver to {inbox}: {err}"),
+        }
+    }
+    delivered
+}
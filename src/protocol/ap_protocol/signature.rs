@@ -153,8 +153,8 @@ pub fn get_signature_headers(signature_header: &str) -> HashMap<String, String>
         .filter_map(|pair| {
             pair.split_once('=').map(|(key, value)| {
                 (
-                    key.replace(|c| strip_values(c), ""),
-                    value.replace(|c| strip_values(c), ""),
+                    key.replace(strip_values, ""),
+                    value.replace(strip_values, ""),
                 )
             })
         })
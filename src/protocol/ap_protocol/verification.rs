@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    cryptography::key::{Algorithms, PrivateKey, PublicKey},
+    cryptography::{
+        key::{Algorithms, PrivateKey, PublicKey},
+        universal_keys::UniversalPublic,
+    },
     types::{
         actors::Actor,
         inboxable::{Inboxable, InboxableVerifyErr, VerifiedInboxable},
@@ -24,7 +27,8 @@ pub enum RequestVerificationError {
     ActorFetchFailed(FetchErr),
     ActorFetchBodyFailed,
     SignatureVerifyFailed,
-    BodyDeserializeErr,
+    /// why the body is not an activity we support
+    BodyDeserializeErr(String),
     ContentErr(InboxableVerifyErr),
     SignatureErr(SignatureErr),
 }
@@ -32,14 +36,24 @@ pub enum RequestVerificationError {
 /// verifys a request and returns the Inboxable if its valid
 /// create activites are stripped and turned into their inner
 /// postable so we don't have to deal with the added complexity
+///
+/// `object` is the already deserialized `body`, see [`parse_inboxable`]
+///
+/// `cached_key` is the stored key of the signer if we have one. the
+/// signer is only fetched when there is no cached key or it fails to
+/// verify the request, so actors that have since been deleted can
+/// still be verified and rotated keys are still picked up
+#[allow(clippy::too_many_arguments)]
 pub async fn verify_post<K: PrivateKey, H: Headers>(
     request_headers: &H,
     body: &str,
+    object: Inboxable,
     path: &str,
     instance_domain: &str,
     instance_key_id: &str,
     instance_private_key: &mut K,
     algorithm: Algorithms,
+    cached_key: Option<UniversalPublic>,
 ) -> Result<VerifiedInboxable, RequestVerificationError> {
     //check digest matches
 
@@ -61,29 +75,12 @@ pub async fn verify_post<K: PrivateKey, H: Headers>(
         Err(x) => return Err(RequestVerificationError::SignatureErr(x)),
     };
 
-    let object: Result<Inboxable, _> = serde_json::from_str(body);
-    let Ok(object) = object else {
-        println!("deserialize failure\n{}", body);
-        return Err(RequestVerificationError::BodyDeserializeErr);
-    };
     let generated_digest = algorithm.hash(body.as_bytes());
 
     if !digest.eq(&generated_digest) {
         return Err(RequestVerificationError::DigestDoesNotMatch);
     }
 
-    let fetched: Result<Actor, FetchErr> = authorized_fetch(
-        signature.signature_header.key_id.clone(),
-        instance_key_id,
-        instance_private_key,
-    )
-    .await;
-
-    let actor = match fetched {
-        Ok(x) => x,
-        Err(x) => return Err(RequestVerificationError::ActorFetchFailed(x)),
-    };
-
     let Some(_) = request_headers.get("date") else {
         return Err(RequestVerificationError::SignatureErr(SignatureErr::NoDate));
     };
@@ -94,13 +91,34 @@ pub async fn verify_post<K: PrivateKey, H: Headers>(
         Err(x) => return Err(RequestVerificationError::SignatureErr(x)),
     };
 
-    let accepted = actor.public_key.public_key_pem.verify(
-        comparison_string.as_bytes(),
-        &signature.signature_header.signature,
-    );
+    let cached_accepted = cached_key.is_some_and(|key| {
+        key.verify(
+            comparison_string.as_bytes(),
+            &signature.signature_header.signature,
+        )
+    });
 
-    if !accepted {
-        return Err(RequestVerificationError::SignatureVerifyFailed);
+    if !cached_accepted {
+        let fetched: Result<Actor, FetchErr> = authorized_fetch(
+            signature.signature_header.key_id.clone(),
+            instance_key_id,
+            instance_private_key,
+        )
+        .await;
+
+        let actor = match fetched {
+            Ok(x) => x,
+            Err(x) => return Err(RequestVerificationError::ActorFetchFailed(x)),
+        };
+
+        let accepted = actor.public_key.public_key_pem.verify(
+            comparison_string.as_bytes(),
+            &signature.signature_header.signature,
+        );
+
+        if !accepted {
+            return Err(RequestVerificationError::SignatureVerifyFailed);
+        }
     }
 
     let object = match object
@@ -119,6 +137,11 @@ pub async fn verify_post<K: PrivateKey, H: Headers>(
     Ok(object)
 }
 
+pub fn parse_inboxable(body: &str) -> Result<Inboxable, RequestVerificationError> {
+    serde_json::from_str(body)
+        .map_err(|err| RequestVerificationError::BodyDeserializeErr(err.to_string()))
+}
+
 pub async fn verify_get<K: PrivateKey, H: Headers>(
     request_headers: &H,
     path: &str,
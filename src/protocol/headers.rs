@@ -18,6 +18,20 @@ impl Headers for ReqwestHeaders {
     }
 }
 
+pub struct ActixHeaders {
+    pub headermap: actix_web::http::header::HeaderMap,
+}
+
+impl Headers for ActixHeaders {
+    fn get(&self, key: &str) -> Option<String> {
+        let val = self.headermap.get(key).map(|x| x.to_str())?;
+        match val {
+            Ok(x) => Some(x.to_string()),
+            Err(_) => None,
+        }
+    }
+}
+
 pub struct HashMapHeaders {
     pub headermap: HashMap<String, String>,
 }
@@ -1,7 +1,7 @@
 use reqwest::StatusCode;
 use url::Url;
 
-use crate::protocol::{errors::FetchErr, webfinger::RelWrap};
+use crate::protocol::errors::FetchErr;
 
 use super::webfinger::WebfingerResult;
 
@@ -0,0 +1,108 @@
+use actix_web::{
+    error::ErrorNotFound,
+    get,
+    web::{self, Data},
+    HttpResponse, Result,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    db::{pg_conn::PgConn, types::tag::Tag},
+    types::{core_types::OptionalArray, language::is_language_code},
+};
+
+#[get("/actor/ap")]
+async fn instance_actor(
+    state: Data<crate::config::Config>,
+    conn: Data<PgConn>,
+) -> Result<HttpResponse> {
+    let actor = conn
+        .get_or_init_instance_actor()
+        .await
+        .to_actor(&state.instance_domain);
+    let actor = serde_json::to_string(&actor.wrap_context()).unwrap();
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/activity+json; charset=utf-8")
+        .body(actor))
+}
+
+#[derive(Deserialize, Debug)]
+struct Scope {
+    lang: Option<String>,
+}
+
+/// aliases advertise the canonical tag they have moved to and
+/// canonical tags list their aliases as `alsoKnownAs`, along with
+/// their counterparts on any domain the relay has moved from
+///
+/// a `lang` parameter gives the tag scoped to that language, once it
+/// has been created by being looked up through webfinger or followed
+#[get("/tags/{tag}")]
+async fn tag_actor(
+    state: Data<crate::config::Config>,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+    scope: web::Query<Scope>,
+) -> Result<HttpResponse> {
+    let domain = &state.instance_domain;
+    let bans = conn.get_tag_bans(&state.reserved_tags).await;
+    let name = path.into_inner();
+    let Some(tag) = conn.get_tag(&name).await else {
+        return Err(ErrorNotFound("tag not found"));
+    };
+    if bans.is_banned(&tag) {
+        return Err(ErrorNotFound("tag not found"));
+    }
+    let tag = match scope.into_inner().lang {
+        Some(language) => {
+            if !is_language_code(&language) || tag.language().is_some() {
+                return Err(ErrorNotFound("tag not found"));
+            }
+            match conn.get_tag(&Tag::scoped_name(&name, &language)).await {
+                Some(scoped) if !bans.is_banned(&scoped) => scoped,
+                _ => return Err(ErrorNotFound("tag not found")),
+            }
+        }
+        None => tag,
+    };
+    let mut actor = tag.to_actor(
+        domain,
+        &conn.get_or_init_instance_actor().await,
+        state.is_group(&tag),
+    );
+
+    // once the relay has moved everything points at the new domain
+    let moved_domain = state.moved_to_domain.as_deref();
+    match tag.alias_of {
+        Some(_) => {
+            let canonical = conn.get_canonical_tag(tag).await;
+            actor.moved_to = Some(canonical.activitypub_id(moved_domain.unwrap_or(domain)));
+        }
+        None => {
+            let also_known_as: Vec<_> = conn
+                .get_tag_aliases(&tag)
+                .await
+                .iter()
+                .map(|alias| alias.activitypub_id(domain))
+                .chain(
+                    state
+                        .previous_domains
+                        .iter()
+                        .map(|previous| tag.activitypub_id(previous)),
+                )
+                .collect();
+            if !also_known_as.is_empty() {
+                actor.also_known_as = Some(OptionalArray::Multiple(also_known_as));
+            }
+            actor.moved_to = moved_domain.map(|moved| tag.activitypub_id(moved));
+        }
+    }
+
+    let actor = serde_json::to_string(&actor.wrap_context()).unwrap();
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/activity+json; charset=utf-8")
+        .body(actor))
+}
@@ -0,0 +1,164 @@
+use actix_web::{
+    error::ErrorNotFound,
+    get,
+    web::{self, Data},
+    HttpResponse, Result,
+};
+use serde::Deserialize;
+use url::Url;
+
+use crate::{
+    config::Config,
+    db::{
+        pg_conn::PgConn,
+        types::{instance_actor::InstanceActor, tag::Tag},
+    },
+    types::{
+        collection::{Collection, CollectionPageLink, CollectionType},
+        context::{Context, ContextWrap},
+        core_types::OptionalArray,
+        extensions::Extensions,
+        link::LinkSimpleOrExpanded,
+        share::{Share, ShareType, SharedObject},
+    },
+};
+
+#[derive(Deserialize, Debug)]
+struct PageQuery {
+    /// pages start at 1, without one the collection itself is served
+    page: Option<i64>,
+}
+
+/// tags and feeds that can be served, banned ones are hidden
+async fn get_servable_tag(config: &Config, conn: &PgConn, name: &str) -> Result<Tag> {
+    let Some(tag) = conn.get_tag(name).await else {
+        return Err(ErrorNotFound("tag not found"));
+    };
+    if conn
+        .get_tag_bans(&config.reserved_tags)
+        .await
+        .is_banned(&tag)
+    {
+        return Err(ErrorNotFound("tag not found"));
+    }
+    Ok(tag)
+}
+
+fn activity_json(collection: Collection) -> HttpResponse {
+    let collection = ContextWrap {
+        context: Context::Single("https://www.w3.org/ns/activitystreams".to_owned()),
+        item: collection,
+    };
+    HttpResponse::Ok()
+        .content_type("application/activity+json; charset=utf-8")
+        .body(serde_json::to_string(&collection).unwrap())
+}
+
+/// the announces of the tag, newest first
+#[get("/tags/{tag}/outbox")]
+async fn tag_outbox(
+    state: Data<Config>,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+    query: web::Query<PageQuery>,
+) -> Result<HttpResponse> {
+    let domain = &state.instance_domain;
+    let tag = get_servable_tag(&state, &conn, &path.into_inner()).await?;
+    let outbox = tag.outbox(domain);
+    let page_size = state.outbox_pagnation_size as i64;
+
+    let Some(page) = query.page.filter(|page| *page > 0) else {
+        let total = conn.count_tag_announces(&tag).await;
+        return Ok(activity_json(Collection {
+            type_field: CollectionType::OrderedCollection,
+            id: Some(outbox.clone()),
+            total_items: Some(total as u64),
+            first: Some(CollectionPageLink::Id(page_id(&outbox, 1))),
+            next: None,
+            items: None,
+            ordered_items: None,
+        }));
+    };
+
+    let (total, posts) = conn.get_tag_announces(&tag, page_size, page - 1).await;
+    let announces = posts
+        .into_iter()
+        .map(|post| {
+            let announce = Share {
+                type_field: ShareType::Announce,
+                id: tag.announce_id(domain, post.id),
+                actor: tag.activitypub_id(domain),
+                published: post.fetched_at.unwrap_or(post.published),
+                to: Some(OptionalArray::Single(LinkSimpleOrExpanded::Simple(
+                    Url::parse("https://www.w3.org/ns/activitystreams#Public").unwrap(),
+                ))),
+                cc: Some(OptionalArray::Single(LinkSimpleOrExpanded::Simple(
+                    tag.followers(domain),
+                ))),
+                tag: None,
+                object: SharedObject::Id(post.activitypub_id),
+                extensions: Extensions::default(),
+            };
+            serde_json::to_value(announce).unwrap()
+        })
+        .collect();
+    Ok(activity_json(Collection {
+        type_field: CollectionType::OrderedCollectionPage,
+        id: Some(page_id(&outbox, page)),
+        total_items: None,
+        first: None,
+        next: match page * page_size < total {
+            true => Some(CollectionPageLink::Id(page_id(&outbox, page + 1))),
+            false => None,
+        },
+        items: None,
+        ordered_items: Some(announces),
+    }))
+}
+
+/// only the number of followers is public
+#[get("/tags/{tag}/followers")]
+async fn tag_followers(
+    state: Data<Config>,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let tag = get_servable_tag(&state, &conn, &path.into_inner()).await?;
+    Ok(activity_json(Collection {
+        type_field: CollectionType::OrderedCollection,
+        id: Some(tag.followers(&state.instance_domain)),
+        total_items: Some(conn.count_tag_followers(&tag).await as u64),
+        first: None,
+        next: None,
+        items: None,
+        ordered_items: None,
+    }))
+}
+
+/// the instance actor moderates every group
+#[get("/tags/{tag}/moderators")]
+async fn tag_moderators(
+    state: Data<Config>,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let domain = &state.instance_domain;
+    let tag = get_servable_tag(&state, &conn, &path.into_inner()).await?;
+    if !state.is_group(&tag) {
+        return Err(ErrorNotFound("tag is not a group"));
+    }
+    let moderator = InstanceActor::activitypub_id(domain).to_string();
+    Ok(activity_json(Collection {
+        type_field: CollectionType::OrderedCollection,
+        id: Some(tag.moderators(domain)),
+        total_items: Some(1),
+        first: None,
+        next: None,
+        items: None,
+        ordered_items: Some(vec![serde_json::Value::String(moderator)]),
+    }))
+}
+
+fn page_id(collection: &Url, page: i64) -> Url {
+    Url::parse(&format!("{collection}?page={page}")).unwrap()
+}
@@ -0,0 +1,117 @@
+use actix_web::{
+    error::ErrorBadRequest,
+    post,
+    web::{self, Data},
+    HttpRequest, HttpResponse, Result,
+};
+
+use crate::{
+    config::Config,
+    cryptography::key::Algorithms,
+    db::{pg_conn::PgConn, types::instance_actor::InstanceActor},
+    inbox::queue::enqueue,
+    protocol::{
+        ap_protocol::{
+            signature::SignatureHeader,
+            verification::{parse_inboxable, verify_post},
+        },
+        headers::{ActixHeaders, Headers},
+    },
+    types::inboxable::VerifiedInboxable,
+};
+
+#[post("/inbox")]
+async fn shared_inbox(
+    request: HttpRequest,
+    body: web::Bytes,
+    state: Data<Config>,
+    conn: Data<PgConn>,
+) -> Result<HttpResponse> {
+    inbox(request, body, state, conn).await
+}
+
+/// the instance actor's inbox, it receives the responses to its
+/// follows when users are followed back by the instance actor
+#[post("/actor/ap/inbox")]
+async fn instance_inbox(
+    request: HttpRequest,
+    body: web::Bytes,
+    state: Data<Config>,
+    conn: Data<PgConn>,
+) -> Result<HttpResponse> {
+    inbox(request, body, state, conn).await
+}
+
+/// tags have no use for their own inbox so it
+/// is handled exactly the same as the shared inbox
+#[post("/tags/{tag}/inbox")]
+async fn tag_inbox(
+    request: HttpRequest,
+    body: web::Bytes,
+    state: Data<Config>,
+    conn: Data<PgConn>,
+) -> Result<HttpResponse> {
+    inbox(request, body, state, conn).await
+}
+
+/// activities are verified while the sender waits and
+/// processed later by the inbox workers. activities we have
+/// no use for are acknowledged once verified and only counted
+async fn inbox(
+    request: HttpRequest,
+    body: web::Bytes,
+    state: Data<Config>,
+    conn: Data<PgConn>,
+) -> Result<HttpResponse> {
+    let Ok(body) = String::from_utf8(body.to_vec()) else {
+        return Err(ErrorBadRequest("body is not utf8"));
+    };
+    let object = match parse_inboxable(&body) {
+        Ok(x) => x,
+        Err(x) => return Err(ErrorBadRequest(serde_json::to_string(&x).unwrap())),
+    };
+    let kind = object.kind();
+    let instance_actor = conn.get_or_init_instance_actor().await;
+    let mut private_key = instance_actor.private_key();
+    let headers = ActixHeaders {
+        headermap: request.headers().clone(),
+    };
+    let signature = headers
+        .get("Signature")
+        .and_then(|signature| SignatureHeader::parse(&signature).ok());
+    let cached_key = match &signature {
+        Some(signature) => conn.get_user_public_key(signature.key_id.as_str()).await,
+        None => None,
+    };
+
+    let verified = verify_post(
+        &headers,
+        &body,
+        object,
+        request.path(),
+        &state.instance_domain,
+        &InstanceActor::pub_key_id(&state.instance_domain),
+        &mut private_key,
+        Algorithms::RsaSha256,
+        cached_key,
+    )
+    .await;
+    let verified = match verified {
+        Ok(x) => x,
+        Err(x) => return Err(ErrorBadRequest(serde_json::to_string(&x).unwrap())),
+    };
+
+    let supported = !matches!(verified, VerifiedInboxable::Unknown(_));
+    conn.count_inbox_activity(&kind, supported).await;
+    if !supported {
+        return Ok(HttpResponse::Accepted().finish());
+    }
+
+    // verified so there is a signature, whose domain the
+    // activity is verified against again when processed
+    let Some(signature) = signature else {
+        return Err(ErrorBadRequest("no signature"));
+    };
+    enqueue(&conn, &body, &signature.key_domain).await;
+    Ok(HttpResponse::Accepted().finish())
+}
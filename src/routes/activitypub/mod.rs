@@ -0,0 +1,4 @@
+pub mod actors;
+pub mod collections;
+pub mod inbox;
+pub mod routes;
@@ -0,0 +1,18 @@
+use actix_web::web::ServiceConfig;
+
+use super::{
+    actors::{instance_actor, tag_actor},
+    collections::{tag_followers, tag_moderators, tag_outbox},
+    inbox::{instance_inbox, shared_inbox, tag_inbox},
+};
+
+pub fn get_activitypub_routes(cfg: &mut ServiceConfig) {
+    cfg.service(instance_actor)
+        .service(tag_actor)
+        .service(tag_outbox)
+        .service(tag_followers)
+        .service(tag_moderators)
+        .service(shared_inbox)
+        .service(instance_inbox)
+        .service(tag_inbox);
+}
@@ -0,0 +1,44 @@
+use actix_web::{
+    dev::Payload,
+    error::{ErrorNotFound, ErrorUnauthorized},
+    http::header::AUTHORIZATION,
+    web::Data,
+    FromRequest, HttpRequest, Result,
+};
+use futures_util::future::{ready, Ready};
+
+use crate::{config::Config, cryptography::digest::sha256_hash};
+
+/// taken by every admin route, the request must carry the configured
+/// `admin_token` as a bearer token. without one the admin api 404s
+pub struct Admin;
+
+impl FromRequest for Admin {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(request: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(authorize(request))
+    }
+}
+
+fn authorize(request: &HttpRequest) -> Result<Admin> {
+    let token = request
+        .app_data::<Data<Config>>()
+        .and_then(|config| config.admin_token.clone())
+        .filter(|token| !token.is_empty());
+    let Some(token) = token else {
+        return Err(ErrorNotFound("not found"));
+    };
+    let given = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|x| x.to_str().ok())
+        .and_then(|x| x.strip_prefix("Bearer "));
+    // compared as digests so that how long it takes says nothing of the token
+    let expected = sha256_hash(token.as_bytes());
+    match given.is_some_and(|given| sha256_hash(given.as_bytes()) == expected) {
+        true => Ok(Admin),
+        false => Err(ErrorUnauthorized("invalid admin token")),
+    }
+}
@@ -0,0 +1,71 @@
+use actix_web::{
+    delete,
+    error::ErrorBadRequest,
+    get, post, put,
+    web::{self, Data},
+    HttpResponse, Result,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{pg_conn::PgConn, types::tag_ban::TagBanKind};
+
+use super::{auth::Admin, json};
+
+#[derive(Deserialize)]
+struct NewRule {
+    kind: TagBanKind,
+    pattern: String,
+    reason: Option<String>,
+}
+
+#[get("/ban_rules")]
+async fn get_ban_rules(_: Admin, conn: Data<PgConn>) -> Result<HttpResponse> {
+    Ok(json(&conn.get_tag_ban_rules().await))
+}
+
+/// adding a rule that already exists updates its reason
+#[post("/ban_rules")]
+async fn add_ban_rule(
+    _: Admin,
+    conn: Data<PgConn>,
+    rule: web::Json<NewRule>,
+) -> Result<HttpResponse> {
+    let rule = rule.into_inner();
+    match conn
+        .add_tag_ban_rule(rule.kind, &rule.pattern, rule.reason)
+        .await
+    {
+        Ok(rule) => Ok(json(&rule)),
+        Err(err) => Err(ErrorBadRequest(err.to_string())),
+    }
+}
+
+#[delete("/ban_rules/{rule_id}")]
+async fn remove_ban_rule(
+    _: Admin,
+    conn: Data<PgConn>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse> {
+    conn.remove_tag_ban_rule(path.into_inner()).await;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Serialize, Deserialize)]
+struct Banned {
+    banned: bool,
+}
+
+/// bans or unbans the tag on its own, along with its scoped tags. a
+/// tag that does not exist yet is created banned
+#[put("/tags/{name}/banned")]
+async fn set_tag_banned(
+    _: Admin,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+    body: web::Json<Banned>,
+) -> Result<HttpResponse> {
+    conn.set_tag_banned(&path, body.banned).await;
+    Ok(json(&Banned {
+        banned: body.banned,
+    }))
+}
@@ -0,0 +1,63 @@
+use actix_web::{
+    delete,
+    error::ErrorBadRequest,
+    get, put,
+    web::{self, Data},
+    HttpResponse, Result,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{pg_conn::PgConn, types::tag::Tag};
+
+use super::{auth::Admin, json};
+
+#[derive(Serialize)]
+struct Feed {
+    name: String,
+    expression: Option<String>,
+}
+
+impl From<Tag> for Feed {
+    fn from(tag: Tag) -> Self {
+        Feed {
+            name: tag.name,
+            expression: tag.expression,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FeedExpression {
+    expression: String,
+}
+
+#[get("/feeds")]
+async fn get_feeds(_: Admin, conn: Data<PgConn>) -> Result<HttpResponse> {
+    let feeds: Vec<Feed> = conn.get_feeds().await.into_iter().map(Feed::from).collect();
+    Ok(json(&feeds))
+}
+
+/// creates the feed, or changes its expression if it exists
+#[put("/feeds/{name}")]
+async fn put_feed(
+    _: Admin,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+    body: web::Json<FeedExpression>,
+) -> Result<HttpResponse> {
+    match conn.create_feed(&path, &body.expression).await {
+        Ok(feed) => Ok(json(&Feed::from(feed))),
+        Err(err) => Err(ErrorBadRequest(err.to_string())),
+    }
+}
+
+/// the feed goes back to being a plain tag
+#[delete("/feeds/{name}")]
+async fn delete_feed(
+    _: Admin,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    conn.remove_feed(&path).await;
+    Ok(HttpResponse::NoContent().finish())
+}
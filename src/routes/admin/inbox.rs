@@ -0,0 +1,17 @@
+use actix_web::{get, web::Data, HttpResponse, Result};
+
+use crate::db::pg_conn::PgConn;
+
+use super::{auth::Admin, json};
+
+/// how far behind the inbox workers are
+#[get("/inbox/queue")]
+async fn inbox_queue(_: Admin, conn: Data<PgConn>) -> Result<HttpResponse> {
+    Ok(json(&conn.queue_depth().await))
+}
+
+/// how many activities of each type the inbox has accepted
+#[get("/inbox/activities")]
+async fn inbox_activities(_: Admin, conn: Data<PgConn>) -> Result<HttpResponse> {
+    Ok(json(&conn.get_inbox_activity_counts().await))
+}
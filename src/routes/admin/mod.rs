@@ -0,0 +1,19 @@
+//! the admin api, only reachable with the `admin_token` from the config,
+//! see [`auth::Admin`]
+
+pub mod auth;
+pub mod bans;
+pub mod feeds;
+pub mod inbox;
+pub mod relay;
+pub mod routes;
+pub mod tags;
+
+use actix_web::HttpResponse;
+use serde::Serialize;
+
+fn json<T: Serialize>(value: &T) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("application/json; charset=utf-8")
+        .body(serde_json::to_string(value).unwrap())
+}
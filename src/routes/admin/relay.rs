@@ -0,0 +1,44 @@
+use actix_web::{
+    error::ErrorBadRequest,
+    get, post, rt,
+    web::{self, Data},
+    HttpResponse, Result,
+};
+
+use crate::{
+    config::Config,
+    db::{pg_conn::PgConn, types::relay_export::RelayExport},
+    protocol::ap_protocol::move_relay::move_relay,
+};
+
+use super::{auth::Admin, json};
+
+/// every tag, alias and ban rule, to be imported on the new domain
+#[get("/relay/export")]
+async fn export_relay(_: Admin, state: Data<Config>, conn: Data<PgConn>) -> Result<HttpResponse> {
+    Ok(json(&conn.export_relay(&state.instance_domain).await))
+}
+
+#[post("/relay/import")]
+async fn import_relay(
+    _: Admin,
+    conn: Data<PgConn>,
+    export: web::Json<RelayExport>,
+) -> Result<HttpResponse> {
+    conn.import_relay(export.into_inner()).await;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// moves every tag to `moved_to_domain`, which has to be set first so that
+/// the tag actors point there. the moves are sent in the background
+#[post("/relay/move")]
+async fn move_relay_to(_: Admin, state: Data<Config>, conn: Data<PgConn>) -> Result<HttpResponse> {
+    let Some(new_domain) = state.moved_to_domain.clone() else {
+        return Err(ErrorBadRequest("moved_to_domain is not set"));
+    };
+    rt::spawn(async move {
+        let moved = move_relay(&conn, &state, &new_domain).await;
+        println!("moved {moved} tags to {new_domain}");
+    });
+    Ok(HttpResponse::Accepted().finish())
+}
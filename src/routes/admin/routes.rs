@@ -0,0 +1,42 @@
+use actix_web::web;
+
+use super::{
+    bans::{add_ban_rule, get_ban_rules, remove_ban_rule, set_tag_banned},
+    feeds::{delete_feed, get_feeds, put_feed},
+    inbox::{inbox_activities, inbox_queue},
+    relay::{export_relay, import_relay, move_relay_to},
+    tags::{
+        add_tag_keyword, get_policy_skips, get_tag_keywords, get_tag_policy, merge_tag,
+        preview_keywords, remove_tag_keyword, set_repost_policies, set_tag_policy, unmerge_tag,
+    },
+};
+
+/// exports carry every tag so they are allowed to be large
+const MAX_JSON_SIZE: usize = 16 * 1024 * 1024;
+
+pub fn get_admin_routes() -> actix_web::Scope {
+    web::scope("/admin")
+        .app_data(web::JsonConfig::default().limit(MAX_JSON_SIZE))
+        .service(export_relay)
+        .service(import_relay)
+        .service(move_relay_to)
+        .service(inbox_queue)
+        .service(inbox_activities)
+        .service(get_feeds)
+        .service(put_feed)
+        .service(delete_feed)
+        .service(get_ban_rules)
+        .service(add_ban_rule)
+        .service(remove_ban_rule)
+        .service(set_tag_banned)
+        .service(merge_tag)
+        .service(unmerge_tag)
+        .service(get_tag_keywords)
+        .service(add_tag_keyword)
+        .service(remove_tag_keyword)
+        .service(preview_keywords)
+        .service(set_repost_policies)
+        .service(get_tag_policy)
+        .service(set_tag_policy)
+        .service(get_policy_skips)
+}
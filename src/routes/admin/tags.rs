@@ -0,0 +1,257 @@
+use actix_web::{
+    delete,
+    error::{ErrorBadRequest, ErrorNotFound},
+    get, post, put,
+    web::{self, Data},
+    HttpResponse, Result,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    db::{
+        pg_conn::PgConn,
+        types::{
+            keyword::Keyword,
+            repost::{AnnouncePolicy, QuotePolicy},
+            tag::{Tag, TagAliasErr},
+            tag_policy::TagPolicy,
+        },
+    },
+    inbox::follow_back::undo_follow_back,
+};
+
+use super::{auth::Admin, json};
+
+/// how many recent posts a keyword preview is tried against by default
+const PREVIEW_LIMIT: i64 = 500;
+/// how many skipped posts are listed by default
+const SKIP_LIMIT: i64 = 100;
+
+/// the canonical tag, settings of aliases belong to the tag they alias
+async fn existing_tag(conn: &PgConn, name: &str) -> Result<Tag> {
+    match conn.get_tag(name).await {
+        Some(tag) => Ok(conn.get_canonical_tag(tag).await),
+        None => Err(ErrorNotFound("no such tag")),
+    }
+}
+
+#[get("/tags/{name}/keywords")]
+async fn get_tag_keywords(
+    _: Admin,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let tag = existing_tag(&conn, &path).await?;
+    Ok(json(&conn.get_keywords_of_tag(&tag).await))
+}
+
+/// adding a keyword the tag already has updates its case sensitivity
+#[post("/tags/{name}/keywords")]
+async fn add_tag_keyword(
+    _: Admin,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+    keyword: web::Json<Keyword>,
+) -> Result<HttpResponse> {
+    let tag = existing_tag(&conn, &path).await?;
+    let keyword = keyword.into_inner();
+    match conn
+        .add_tag_keyword(&tag, keyword.kind, &keyword.text, keyword.case_sensitive)
+        .await
+    {
+        Ok(keyword) => Ok(json(&keyword)),
+        Err(err) => Err(ErrorBadRequest(err.to_string())),
+    }
+}
+
+#[delete("/keywords/{keyword_id}")]
+async fn remove_tag_keyword(
+    _: Admin,
+    conn: Data<PgConn>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse> {
+    conn.remove_tag_keyword(path.into_inner()).await;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Deserialize)]
+struct PreviewQuery {
+    limit: Option<i64>,
+}
+
+/// the recent posts the keywords would match, to try them before adding them
+#[post("/keywords/preview")]
+async fn preview_keywords(
+    _: Admin,
+    conn: Data<PgConn>,
+    query: web::Query<PreviewQuery>,
+    keywords: web::Json<Vec<Keyword>>,
+) -> Result<HttpResponse> {
+    let limit = query
+        .limit
+        .unwrap_or(PREVIEW_LIMIT)
+        .clamp(1, 10 * PREVIEW_LIMIT);
+    Ok(json(
+        &conn.preview_keywords(keywords.into_inner(), limit).await,
+    ))
+}
+
+#[derive(Serialize, Deserialize)]
+struct RepostPolicies {
+    announce_policy: AnnouncePolicy,
+    quote_policy: QuotePolicy,
+}
+
+/// what the tag does when its followers boost or quote posts carrying it
+#[put("/tags/{name}/repost_policies")]
+async fn set_repost_policies(
+    _: Admin,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+    policies: web::Json<RepostPolicies>,
+) -> Result<HttpResponse> {
+    let tag = existing_tag(&conn, &path).await?;
+    let Some(tag) = conn
+        .set_tag_repost_policies(&tag.name, policies.announce_policy, policies.quote_policy)
+        .await
+    else {
+        return Err(ErrorNotFound("no such tag"));
+    };
+    Ok(json(&RepostPolicies {
+        announce_policy: tag.announce_policy,
+        quote_policy: tag.quote_policy,
+    }))
+}
+
+#[derive(Serialize, Deserialize)]
+struct Alias {
+    alias_of: String,
+}
+
+/// makes the tag an alias of another, its follows and aliases move
+/// over to the canonical tag
+#[put("/tags/{name}/alias_of")]
+async fn merge_tag(
+    _: Admin,
+    config: Data<Config>,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+    alias: web::Json<Alias>,
+) -> Result<HttpResponse> {
+    // refused before either tag is created
+    if path.eq_ignore_ascii_case(&alias.alias_of) {
+        return Err(ErrorBadRequest(TagAliasErr::SameTag.to_string()));
+    }
+    let (canonical, dropped) = match conn.merge_tags(&path, &alias.alias_of).await {
+        Ok(merged) => merged,
+        Err(err) => return Err(ErrorBadRequest(err.to_string())),
+    };
+    // users that followed both only keep their follow of the canonical tag
+    let instance_actor = conn.get_or_init_instance_actor().await;
+    let mut private_key = instance_actor.private_key();
+    for (user, tag, ufid) in dropped {
+        undo_follow_back(&conn, &config, &mut private_key, &tag, ufid, &user).await;
+    }
+    Ok(json(&Alias {
+        alias_of: canonical.name,
+    }))
+}
+
+/// the tag goes back to being handled on its own
+#[delete("/tags/{name}/alias_of")]
+async fn unmerge_tag(
+    _: Admin,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    conn.remove_tag_alias(&path).await;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("/tags/{name}/policy")]
+async fn get_tag_policy(
+    _: Admin,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let tag = existing_tag(&conn, &path).await?;
+    Ok(json(&tag.policy))
+}
+
+/// replaces the rules posts have to pass to be boosted by the tag
+#[put("/tags/{name}/policy")]
+async fn set_tag_policy(
+    _: Admin,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+    policy: web::Json<TagPolicy>,
+) -> Result<HttpResponse> {
+    let tag = existing_tag(&conn, &path).await?;
+    let Some(tag) = conn.set_tag_policy(&tag.name, policy.into_inner()).await else {
+        return Err(ErrorNotFound("no such tag"));
+    };
+    Ok(json(&tag.policy))
+}
+
+#[derive(Deserialize)]
+struct SkipQuery {
+    limit: Option<i64>,
+}
+
+/// the posts the tag most recently matched but did not boost, and why
+#[get("/tags/{name}/policy_skips")]
+async fn get_policy_skips(
+    _: Admin,
+    conn: Data<PgConn>,
+    path: web::Path<String>,
+    query: web::Query<SkipQuery>,
+) -> Result<HttpResponse> {
+    let tag = existing_tag(&conn, &path).await?;
+    let limit = query.limit.unwrap_or(SKIP_LIMIT).clamp(1, 10 * SKIP_LIMIT);
+    Ok(json(&conn.get_policy_skips(&tag, limit).await))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        http::StatusCode,
+        test::{self, TestRequest},
+        web::Data,
+        App,
+    };
+
+    use crate::{config::test_config, routes::admin::routes::get_admin_routes};
+
+    #[actix_web::test]
+    async fn tags_are_not_merged_into_themselves() -> Result<(), String> {
+        let mut config = test_config();
+        config.admin_token = Some("secret".to_string());
+        let conn = config.create_conn();
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(config))
+                .app_data(Data::new(conn))
+                .service(get_admin_routes()),
+        )
+        .await;
+        let merge = |token: &str| {
+            TestRequest::put()
+                .uri("/admin/tags/Rust/alias_of")
+                .insert_header(("Authorization", format!("Bearer {token}")))
+                .set_json(serde_json::json!({ "alias_of": "rust" }))
+                .to_request()
+        };
+        let cases = [
+            ("wrong", StatusCode::UNAUTHORIZED),
+            ("secret", StatusCode::BAD_REQUEST),
+        ];
+        for (token, expected) in cases {
+            let status = test::call_service(&app, merge(token)).await.status();
+            if status != expected {
+                return Err(format!("merging with {token} gave {status}"));
+            }
+        }
+        Ok(())
+    }
+}
@@ -1 +1,3 @@
+pub mod activitypub;
+pub mod admin;
 pub mod well_known;
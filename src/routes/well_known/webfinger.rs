@@ -1,6 +1,6 @@
 use crate::{
     db::{pg_conn::PgConn, types::instance_actor::InstanceActor},
-    types::webfinger::*,
+    types::{language::is_language_code, webfinger::*},
 };
 use actix_web::{
     error::{ErrorBadRequest, ErrorNotFound},
@@ -92,10 +92,24 @@ async fn webfinger(
         true => InstanceActor::activitypub_id(&state.instance_domain),
         //not the instance actor
         false => {
-            if !preferred_username.chars().all(char::is_alphanumeric) {
+            // tags scoped to a language are named like `rust.de`
+            let (base, language) = match preferred_username.split_once('.') {
+                Some((base, language)) => (base, Some(language)),
+                None => (preferred_username.as_str(), None),
+            };
+            if base.is_empty() || !base.chars().all(char::is_alphanumeric) {
                 return Err(ErrorNotFound("preferred username not alphanumeric"));
             }
-            let tag = conn.get_or_init_tag(&preferred_username, false).await;
+            if language.is_some_and(|x| !is_language_code(x)) {
+                return Err(ErrorNotFound("tag scoped to an unknown language"));
+            }
+            let bans = conn.get_tag_bans(&state.reserved_tags).await;
+            let Some(tag) = conn
+                .get_or_init_unbanned_tag(&preferred_username, &bans)
+                .await
+            else {
+                return Err(ErrorNotFound("tag not found"));
+            };
             tag.activitypub_id(&state.instance_domain)
         }
     };
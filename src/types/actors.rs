@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 use super::{
     context::{Context, ContextItem, ContextMapItem, ContextWrap},
+    core_types::OptionalArray,
+    extensions::Extensions,
     public_key::ApPublicKey,
+    publication::AttributedTo,
 };
 use serde::{Deserialize, Serialize};
 use url::Url;
@@ -67,8 +70,39 @@ pub struct Actor {
     pub followers: Option<Url>,
     pub following: Option<Url>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoints: Option<Endpoints>,
+
+    /// other actors that represent the same entity, used to verify
+    /// account migrations and to link aliases of the same tag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub also_known_as: Option<OptionalArray<Url>>,
+    /// the actor this actor has been moved to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moved_to: Option<Url>,
+
+    /// groups point at the collection of their moderators (FEP-1b12),
+    /// peertube channels embed the account that owns them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributed_to: Option<OptionalArray<AttributedTo>>,
+    /// the moderators collection as mbin names it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moderators: Option<Url>,
+    /// whether only the moderators of a group may post to it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub posting_restricted_to_mods: Option<bool>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub versia_url: Option<Url>,
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Endpoints {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shared_inbox: Option<Url>,
 }
 
 impl Actor {
@@ -148,6 +182,28 @@ impl Actor {
             item: self,
         }
     }
+    /// mastodon marks bot accounts as services, and others
+    /// send automated accounts as applications
+    pub fn is_bot(&self) -> bool {
+        matches!(self.type_field, ActorType::Service | ActorType::Application)
+    }
+    /// the shared inbox if the actor has one, otherwise their inbox
+    pub fn shared_inbox(&self) -> &Url {
+        match &self.endpoints {
+            Some(Endpoints {
+                shared_inbox: Some(shared_inbox),
+            }) => shared_inbox,
+            _ => &self.inbox,
+        }
+    }
+    /// used to verify that both sides agree to an account migration
+    pub fn is_also_known_as(&self, id: &Url) -> bool {
+        match &self.also_known_as {
+            Some(OptionalArray::Single(x)) => x.eq(id),
+            Some(OptionalArray::Multiple(x)) => x.contains(id),
+            None => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum CollectionType {
+    Collection,
+    OrderedCollection,
+    CollectionPage,
+    OrderedCollectionPage,
+}
+
+/// a collection or a single page of one. mastodon only embeds the
+/// first page, further pages have to be fetched by following `next`
+///
+/// items are left as json as collections can hold anything
+///
+/// https://www.w3.org/TR/activitystreams-vocabulary/#dfn-collection
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Collection {
+    #[serde(rename = "type")]
+    pub type_field: CollectionType,
+    pub id: Option<Url>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_items: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first: Option<CollectionPageLink>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<CollectionPageLink>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ordered_items: Option<Vec<serde_json::Value>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum CollectionPageLink {
+    Id(Url),
+    Page(Box<Collection>),
+}
+
+impl Collection {
+    /// the items of the page, whether ordered or not
+    pub fn take_items(&mut self) -> Vec<serde_json::Value> {
+        let mut items = self.ordered_items.take().unwrap_or_default();
+        items.extend(self.items.take().unwrap_or_default());
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Collection, CollectionPageLink};
+
+    #[test]
+    fn test_deserialize_outbox() -> Result<(), String> {
+        // the shape of a mastodon outbox and its first page
+        let outbox = r##"
+{
+	"@context": "https://www.w3.org/ns/activitystreams",
+	"id": "https://mastodon.social/users/Mastodon/outbox",
+	"type": "OrderedCollection",
+	"totalItems": 2,
+	"first": "https://mastodon.social/users/Mastodon/outbox?page=true",
+	"last": "https://mastodon.social/users/Mastodon/outbox?min_id=0&page=true"
+}
+        "##;
+        let page = r##"
+{
+	"@context": "https://www.w3.org/ns/activitystreams",
+	"id": "https://mastodon.social/users/Mastodon/outbox?page=true",
+	"type": "OrderedCollectionPage",
+	"next": "https://mastodon.social/users/Mastodon/outbox?max_id=112769333503182077&page=true",
+	"prev": "https://mastodon.social/users/Mastodon/outbox?min_id=112769333503182078&page=true",
+	"partOf": "https://mastodon.social/users/Mastodon/outbox",
+	"orderedItems": [
+		{
+			"id": "https://mastodon.social/users/Mastodon/statuses/112769333503182078/activity",
+			"type": "Announce",
+			"actor": "https://mastodon.social/users/Mastodon",
+			"published": "2024-07-12T10:00:00Z",
+			"to": ["https://www.w3.org/ns/activitystreams#Public"],
+			"object": "https://mastodon.social/users/Gargron/statuses/112769333503100000"
+		},
+		"https://mastodon.social/users/Mastodon/statuses/112769333503182077/activity"
+	]
+}
+        "##;
+        let outbox: Collection = serde_json::from_str(outbox).map_err(|x| x.to_string())?;
+        if !matches!(outbox.first, Some(CollectionPageLink::Id(_))) {
+            return Err(format!("unexpected first page {:?}", outbox.first));
+        }
+        let mut page: Collection = serde_json::from_str(page).map_err(|x| x.to_string())?;
+        match page.take_items().len() {
+            2 => Ok(()),
+            x => Err(format!("expected 2 items but got {x}")),
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use super::{context::ContextWrap, postable::ApPostable};
+use super::{context::ContextWrap, extensions::Extensions, postable::ApPostable};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum CreateType {
@@ -37,11 +37,18 @@ pub struct Create {
     pub actor: Url,
     /// a validated object should always be a concrete postable
     pub object: ApPostable,
+    #[serde(flatten)]
+    pub extensions: Extensions,
 }
 
 impl Create {
+    /// takes on the context of the object it creates
     pub fn wrap_context(self) -> ContextWrap<Self> {
-        todo!()
+        let context = self.object.clone().wrap_context().context;
+        ContextWrap {
+            context,
+            item: self,
+        }
     }
 }
 
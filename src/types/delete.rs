@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use super::{extensions::Extensions, tombstone::Tombstone};
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum DeleteType {
     Delete,
@@ -56,12 +58,34 @@ pub enum DeleteType {
 pub struct Delete {
     #[serde(rename = "type")]
     pub type_field: DeleteType,
+    pub id: Url,
     pub actor: Url, //TODO
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub origin: Option<String>, //TODO
 
-    pub object: Url,
+    pub object: DeleteObject,
+
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// deletes of actors usually just have the id while
+/// deletes of posts usually embed a [`Tombstone`]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum DeleteObject {
+    Id(Url),
+    Tombstone(Tombstone),
+}
+
+impl DeleteObject {
+    pub fn id(&self) -> &Url {
+        match self {
+            DeleteObject::Id(id) => id,
+            DeleteObject::Tombstone(tombstone) => &tombstone.id,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -101,4 +125,43 @@ mod tests {
             )),
         }
     }
+
+    #[test]
+    fn deserialize_delete_tombstone() -> Result<(), String> {
+        let example = r##"
+{
+  "@context": [
+    "https://www.w3.org/ns/activitystreams",
+    {
+      "ostatus": "http://ostatus.org#",
+      "atomUri": "ostatus:atomUri"
+    }
+  ],
+  "id": "https://mastodon.social/users/Hibur/statuses/112769333503182077#delete",
+  "type": "Delete",
+  "actor": "https://mastodon.social/users/Hibur",
+  "to": [
+    "https://www.w3.org/ns/activitystreams#Public"
+  ],
+  "object": {
+    "id": "https://mastodon.social/users/Hibur/statuses/112769333503182077",
+    "type": "Tombstone",
+    "atomUri": "https://mastodon.social/users/Hibur/statuses/112769333503182077"
+  }
+}
+        "##;
+
+        let deserialized: Result<ContextWrap<Delete>, serde_json::Error> =
+            serde_json::from_str(example);
+        match deserialized {
+            Ok(x) => match x.item.object {
+                super::DeleteObject::Tombstone(_) => Ok(()),
+                super::DeleteObject::Id(_) => Err("tombstone parsed as an id".to_string()),
+            },
+            Err(x) => Err(format!(
+                "Delete activity deserialize failed with response: {}",
+                x
+            )),
+        }
+    }
 }
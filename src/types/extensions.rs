@@ -0,0 +1,138 @@
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use url::Url;
+
+/// the properties of an object that its type does not model, kept so
+/// that the object serializes back to what was received and vendor
+/// extensions can still be read
+///
+/// flattened into the type it extends after every other field, where
+/// it holds everything not modeled above
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct Extensions(pub Map<String, Value>);
+
+/// objects fetched on their own carry their `@context`, which
+/// [`super::context::ContextWrap`] adds back when they are served
+impl<'de> Deserialize<'de> for Extensions {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut map = Map::deserialize(deserializer)?;
+        map.remove("@context");
+        Ok(Extensions(map))
+    }
+}
+
+impl Extensions {
+    /// the property as the given type, none if it is missing or differently shaped
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        serde_json::from_value(self.0.get(key)?.clone()).ok()
+    }
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.0.get(key)?.as_str()
+    }
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.0.get(key)?.as_bool()
+    }
+    pub fn get_url(&self, key: &str) -> Option<Url> {
+        Url::parse(self.get_str(key)?).ok()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // ---------------- posts ------------------
+
+    /// the mfm source of a post from misskey and its forks
+    pub fn misskey_content(&self) -> Option<&str> {
+        self.get_str("_misskey_content")
+    }
+    /// mastodon's thread id, shared by every post of a conversation
+    pub fn conversation(&self) -> Option<&str> {
+        self.get_str("conversation")
+    }
+    /// the ostatus id mastodon still sends alongside the activitypub id
+    pub fn atom_uri(&self) -> Option<&str> {
+        self.get_str("atomUri")
+    }
+    /// the number of people that voted in a poll
+    pub fn voters_count(&self) -> Option<u64> {
+        self.0.get("votersCount")?.as_u64()
+    }
+
+    // ---------------- actors ------------------
+
+    /// whether the actor wants to be suggested to others
+    pub fn discoverable(&self) -> Option<bool> {
+        self.get_bool("discoverable")
+    }
+    /// whether the actor's posts may be indexed for search
+    pub fn indexable(&self) -> Option<bool> {
+        self.get_bool("indexable")
+    }
+    /// when the actor's account was created
+    pub fn published(&self) -> Option<i64> {
+        let published = chrono::DateTime::parse_from_rfc3339(self.get_str("published")?).ok()?;
+        Some(published.timestamp_millis())
+    }
+    /// misskey's opt out of search engines and crawlers
+    pub fn noindex(&self) -> Option<bool> {
+        self.get_bool("noindex")
+    }
+    pub fn manually_approves_followers(&self) -> Option<bool> {
+        self.get_bool("manuallyApprovesFollowers")
+    }
+    /// the collection of posts the actor has pinned
+    pub fn featured(&self) -> Option<Url> {
+        self.get_url("featured")
+    }
+    /// the actor's profile fields, as property values
+    pub fn attachment(&self) -> Vec<Value> {
+        match self.0.get("attachment") {
+            Some(Value::Array(items)) => items.clone(),
+            Some(item) => vec![item.clone()],
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{context::ContextWrap, follow_and_response::Follow};
+
+    #[test]
+    fn test_unknown_fields_round_trip() -> Result<(), String> {
+        let follow = r##"
+{
+	"@context": "https://www.w3.org/ns/activitystreams",
+	"id": "https://misskey.example/follows/9qxyz/tags",
+	"type": "Follow",
+	"actor": "https://misskey.example/users/9abc",
+	"object": "https://tags.example/tags/rust",
+	"_misskey_note": "follows from misskey carry extra fields",
+	"nested": {
+		"list": [1, 2, 3]
+	}
+}
+        "##;
+        let original: serde_json::Value =
+            serde_json::from_str(follow).map_err(|x| x.to_string())?;
+        let follow: ContextWrap<Follow> =
+            serde_json::from_str(follow).map_err(|x| x.to_string())?;
+        if follow.item.extensions.get_str("_misskey_note").is_none()
+            || follow.item.extensions.0.contains_key("@context")
+        {
+            return Err(format!(
+                "unexpected extensions {:?}",
+                follow.item.extensions
+            ));
+        }
+        let serialized = serde_json::to_value(&follow).map_err(|x| x.to_string())?;
+        match serialized.eq(&original) {
+            true => Ok(()),
+            false => Err(format!("follow serialized to {serialized}")),
+        }
+    }
+}
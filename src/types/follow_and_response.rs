@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use super::context::{Context, ContextWrap};
+use super::extensions::Extensions;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ResponseType {
     /// Indicates that the actor accepts the object. The target property
@@ -46,7 +49,35 @@ pub struct FollowResponse {
     pub type_field: ResponseType,
     pub id: Url,
     pub actor: Url,
-    pub object: Url,
+    pub object: FollowRef,
+}
+
+/// the follow being responded to. mastodon embeds it and lemmy
+/// only accepts responses that do
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum FollowRef {
+    Id(Url),
+    Follow(Follow),
+}
+
+impl FollowRef {
+    pub fn id(&self) -> &Url {
+        match self {
+            FollowRef::Id(id) => id,
+            FollowRef::Follow(follow) => &follow.id,
+        }
+    }
+}
+
+impl FollowResponse {
+    pub fn wrap_context(self) -> ContextWrap<Self> {
+        ContextWrap {
+            context: Context::Single("https://www.w3.org/ns/activitystreams".to_owned()),
+            item: self,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -106,4 +137,16 @@ pub struct Follow {
     pub id: Url,
     pub actor: Url,
     pub object: Url,
+
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+impl Follow {
+    pub fn wrap_context(self) -> ContextWrap<Self> {
+        ContextWrap {
+            context: Context::Single("https://www.w3.org/ns/activitystreams".to_owned()),
+            item: self,
+        }
+    }
 }
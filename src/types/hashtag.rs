@@ -0,0 +1,131 @@
+//! the hashtags of a post. most software lists them in the `tag` field,
+//! but some older misskey forks, bridges and blogs only have them in
+//! the content, so they are looked for there as well
+
+use serde::{Deserialize, Serialize};
+
+use super::html::{strip_html, tag_links};
+
+/// where a hashtag of a post was found, from most to least reliable
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum HashtagSource {
+    /// the `tag` field of the post
+    Tag,
+    /// a link marked `rel="tag"` in the content
+    Link,
+    /// a `#word` in the text of the content
+    Text,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Hashtag {
+    /// without the leading `#`, as written
+    pub name: String,
+    pub source: HashtagSource,
+}
+
+/// the hashtags of the `tag` field merged with those found in the html
+/// `contents`, which are the content in every language it is in. a
+/// hashtag found more than once keeps its most reliable source
+pub fn merge_hashtags(tag: Option<Vec<String>>, contents: &[&str]) -> Vec<Hashtag> {
+    let structured = tag
+        .unwrap_or_default()
+        .into_iter()
+        .map(|name| (name, HashtagSource::Tag));
+    let found = contents.iter().flat_map(|html| {
+        tag_links(html)
+            .into_iter()
+            .map(|name| (name, HashtagSource::Link))
+            .chain(
+                text_hashtags(&strip_html(html))
+                    .into_iter()
+                    .map(|name| (name, HashtagSource::Text)),
+            )
+    });
+
+    let mut hashtags: Vec<Hashtag> = Vec::new();
+    for (name, source) in structured.chain(found) {
+        let name = name.trim_start_matches('#');
+        // the tag field is taken at its word
+        let valid = match source {
+            HashtagSource::Tag => !name.is_empty(),
+            _ => is_hashtag(name),
+        };
+        if !valid {
+            continue;
+        }
+        if hashtags
+            .iter()
+            .any(|x| x.name.to_lowercase() == name.to_lowercase())
+        {
+            continue;
+        }
+        hashtags.push(Hashtag {
+            name: name.to_string(),
+            source,
+        });
+    }
+    hashtags
+}
+
+/// a hashtag is word characters with at least one letter, so
+/// that issue numbers like #12 are not hashtags
+fn is_hashtag(name: &str) -> bool {
+    name.chars().all(is_word) && name.chars().any(char::is_alphabetic)
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// the `#word` tokens of plain text. a `#` within a word or after a
+/// slash is part of a url or an anchor rather than a hashtag
+fn text_hashtags(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut previous = None;
+    for (i, c) in text.char_indices() {
+        let starts_word = !previous.is_some_and(|p: char| is_word(p) || p == '/' || p == '#');
+        if c == '#' && starts_word {
+            let name: String = text[i + 1..].chars().take_while(|c| is_word(*c)).collect();
+            if is_hashtag(&name) {
+                names.push(name);
+            }
+        }
+        previous = Some(c);
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_hashtags, Hashtag, HashtagSource};
+
+    #[test]
+    fn content_hashtags_fill_in_the_tag_field() -> Result<(), String> {
+        let content = r#"<p>notes on <a href="https://mastodon.social/tags/Rust" class="mention hashtag" rel="tag">#<span>Rust</span></a> and #Tokio, see issue #12 and https://example.com/#anchor</p><p><a href="https://blog.example/tag/async" rel="tag">async</a> #日本語</p>"#;
+        let hashtags = merge_hashtags(Some(vec!["#tokio".to_string()]), &[content]);
+        let expected = vec![
+            Hashtag {
+                name: "tokio".to_string(),
+                source: HashtagSource::Tag,
+            },
+            Hashtag {
+                name: "Rust".to_string(),
+                source: HashtagSource::Link,
+            },
+            Hashtag {
+                name: "async".to_string(),
+                source: HashtagSource::Link,
+            },
+            Hashtag {
+                name: "日本語".to_string(),
+                source: HashtagSource::Text,
+            },
+        ];
+        match hashtags.eq(&expected) {
+            true => Ok(()),
+            false => Err(format!("unexpected hashtags {:?}", hashtags)),
+        }
+    }
+}
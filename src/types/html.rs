@@ -0,0 +1,164 @@
+//! post content is html, matching against it needs the plain text
+
+/// tags that separate their text from the text around them, anything
+/// else is inline like the spans mastodon wraps hashtags in
+const BLOCK_TAGS: &[&str] = &[
+    "br",
+    "p",
+    "div",
+    "li",
+    "ul",
+    "ol",
+    "blockquote",
+    "pre",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "tr",
+    "td",
+];
+
+/// the text of html content with tags removed and entities decoded.
+/// block elements become line breaks so their words stay apart
+pub fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        decode_entities(&rest[..start], &mut text);
+        let opens_tag = rest[start + 1..]
+            .starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!');
+        let end = rest[start..].find('>');
+        let (true, Some(end)) = (opens_tag, end) else {
+            // a stray < rather than a tag
+            text.push('<');
+            rest = &rest[start + 1..];
+            continue;
+        };
+        let tag = &rest[start + 1..start + end];
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if BLOCK_TAGS.contains(&name.as_str()) && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        rest = &rest[start + end + 1..];
+    }
+    decode_entities(rest, &mut text);
+    text
+}
+
+/// the text of every link marked `rel="tag"`, which is how content
+/// marks up its hashtags. the leading `#` is left out
+pub fn tag_links(html: &str) -> Vec<String> {
+    // ascii lowercasing keeps byte offsets the same as the original
+    let lower = html.to_ascii_lowercase();
+    let mut links = Vec::new();
+    let mut pos = 0;
+    while let Some(start) = lower[pos..].find("<a").map(|x| x + pos) {
+        let Some(end) = lower[start..].find('>').map(|x| x + start) else {
+            break;
+        };
+        pos = end + 1;
+        let attributes = &lower[start + 2..end];
+        if !attributes.starts_with(|c: char| c.is_ascii_whitespace()) || !is_tag_rel(attributes) {
+            continue;
+        }
+        let close = lower[pos..]
+            .find("</a")
+            .map(|x| x + pos)
+            .unwrap_or(html.len());
+        let text = strip_html(&html[pos..close]);
+        let name = text.trim().trim_start_matches('#').trim();
+        if !name.is_empty() {
+            links.push(name.to_string());
+        }
+        pos = close;
+    }
+    links
+}
+
+/// if the rel attribute of a tag lists `tag`
+fn is_tag_rel(attributes: &str) -> bool {
+    attributes
+        .match_indices("rel=")
+        .filter(|(i, _)| attributes[..*i].ends_with(|c: char| c.is_ascii_whitespace()))
+        .any(|(i, _)| {
+            let value = &attributes[i + 4..];
+            let value = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or_default(),
+                _ => value.split_ascii_whitespace().next().unwrap_or_default(),
+            };
+            value.split_ascii_whitespace().any(|x| x == "tag")
+        })
+}
+
+fn decode_entities(input: &str, out: &mut String) {
+    let mut rest = input;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => match entity.strip_prefix('#') {
+                    Some(hex) if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16)
+                        .ok()
+                        .and_then(char::from_u32),
+                    Some(dec) => dec.parse().ok().and_then(char::from_u32),
+                    None => None,
+                },
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{strip_html, tag_links};
+
+    #[test]
+    fn test_strip_html() -> Result<(), String> {
+        let html = r#"<p>Async &amp; <a href="https://mastodon.social/tags/Rust" class="mention hashtag" rel="tag">#<span>Rust</span></a> is&nbsp;fun</p><p>see &#8220;tokio&#x201D;<br />and &unknown; a < b</p>"#;
+        let text = strip_html(html);
+        let expected = "\nAsync & #Rust is fun\nsee \u{201C}tokio\u{201D}\nand &unknown; a < b\n";
+        match text.eq(expected) {
+            true => Ok(()),
+            false => Err(format!("unexpected text {text:?}")),
+        }
+    }
+
+    #[test]
+    fn test_tag_links() -> Result<(), String> {
+        let html = r#"<p><a href="https://mastodon.social/tags/Rust" class="mention hashtag" rel="tag">#<span>Rust</span></a> <A HREF="https://blog.example/tag/tokio" REL='tag'>tokio</A> <a href="https://example.com" rel="nofollow">#not</a> <a data-rel="tag">#nope</a></p>"#;
+        let links = tag_links(html);
+        match links.eq(&vec!["Rust".to_string(), "tokio".to_string()]) {
+            true => Ok(()),
+            false => Err(format!("unexpected links {links:?}")),
+        }
+    }
+}
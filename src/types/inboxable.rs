@@ -1,16 +1,24 @@
 use super::{
+    actors::Actor,
     create::Create,
     delete::Delete,
     follow_and_response::{Follow, FollowResponse},
+    move_activity::Move,
     postable::ApPostable,
+    undo::{Undo, Undoable},
+    update::{Updatable, Update},
 };
 // use crate::cryptography::key::Algorithms;
 // use crate::cryptography::key::PrivateKey;
 // use crate::protocol::ap_protocol::fetch::authorized_fetch;
 use crate::protocol::errors::FetchErr;
-use serde::{Deserialize, Serialize};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// deserialized by the `type` of the activity, so an activity we
+/// support that is malformed reports why and anything else is
+/// [`Inboxable::Unknown`] rather than an error
+#[derive(Serialize, Debug, Clone)]
 #[serde(untagged)]
 #[allow(clippy::large_enum_variant)]
 pub enum Inboxable {
@@ -26,23 +34,127 @@ pub enum Inboxable {
     /// the post on its own as well down the road
     Postable(ApPostable),
     Create(Create),
+    Update(Update),
     Delete(Delete),
     Follow(Follow),
     FollowResponse(FollowResponse),
+    Move(Move),
+    Undo(Undo),
+    /// an activity we have no use for, such as a like or a block, or
+    /// an accept or undo of one. holds its type for [`Inboxable::kind`]
+    Unknown(String),
+}
+
+impl<'de> Deserialize<'de> for Inboxable {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        let Some(kind) = type_of(&value) else {
+            return Err(D::Error::missing_field("type"));
+        };
+        let inboxable = match kind.as_str() {
+            "Note" | "Article" | "Question" | "Announce" | "Page" | "Video" | "Event" | "Audio" => {
+                serde_json::from_value(value).map(Inboxable::Postable)
+            }
+            "Create" => serde_json::from_value(value).map(Inboxable::Create),
+            "Update" => serde_json::from_value(value).map(Inboxable::Update),
+            "Delete" => serde_json::from_value(value).map(Inboxable::Delete),
+            "Follow" | "Unfollow" => serde_json::from_value(value).map(Inboxable::Follow),
+            "Move" => serde_json::from_value(value).map(Inboxable::Move),
+            // only responses to and undos of the activities we send are of interest
+            "Accept" | "Reject" | "Undo" => {
+                let inner = value.get("object").and_then(type_of);
+                match (kind.as_str(), inner.as_deref()) {
+                    ("Accept" | "Reject", None | Some("Follow")) => {
+                        serde_json::from_value(value).map(Inboxable::FollowResponse)
+                    }
+                    ("Undo", Some("Announce" | "Follow")) => {
+                        serde_json::from_value(value).map(Inboxable::Undo)
+                    }
+                    (_, inner) => {
+                        return Ok(Inboxable::Unknown(format!(
+                            "{kind} {}",
+                            inner.unwrap_or("Link")
+                        )))
+                    }
+                }
+            }
+            _ => return Ok(Inboxable::Unknown(kind)),
+        };
+        inboxable.map_err(|err| D::Error::custom(format!("invalid {kind}: {err}")))
+    }
+}
+
+/// the activity types counted by name, those of activitystreams and the
+/// ones in common use. any other type is counted as "other" so that
+/// senders can't add counts of their own making
+const COUNTED_KINDS: &[&str] = &[
+    "Accept",
+    "Add",
+    "Announce",
+    "Arrive",
+    "Block",
+    "Create",
+    "Delete",
+    "Dislike",
+    "EmojiReact",
+    "Flag",
+    "Follow",
+    "Ignore",
+    "Invite",
+    "Join",
+    "Leave",
+    "Like",
+    "Link",
+    "Listen",
+    "Move",
+    "Offer",
+    "Question",
+    "Read",
+    "Reject",
+    "Remove",
+    "TentativeAccept",
+    "TentativeReject",
+    "Travel",
+    "Undo",
+    "Update",
+    "View",
+];
+
+/// the type of an object, the first one when it has several
+fn type_of(value: &Value) -> Option<String> {
+    match value.get("type")? {
+        Value::String(kind) => Some(kind.clone()),
+        Value::Array(kinds) => kinds.iter().find_map(|x| x.as_str()).map(str::to_string),
+        _ => None,
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(untagged)]
 #[allow(clippy::large_enum_variant)]
 pub enum VerifiedInboxable {
-    /// Creates and updates are simplified into just a postable
+    /// posts sent on their own are simplified into just a postable
     ///
     /// we need to check if the thing already exists in the db
     /// anyway, might as well use that to determine the db logic
     Postable(ApPostable),
+    /// an actor updating their own profile
+    Actor(Actor),
     Delete(Delete),
     Follow(Follow),
     FollowResponse(FollowResponse),
+    Move(Move),
+    Undo(Undo),
+    /// a create of a verified postable, handled like the postable
+    /// itself but kept whole for groups to announce (FEP-1b12)
+    Create(Create),
+    /// an edit of a verified postable, kept whole like [`Self::Create`]
+    Update(Update),
+    /// acknowledged without being queued
+    Unknown(String),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -52,6 +164,35 @@ pub enum InboxableVerifyErr {
 }
 
 impl Inboxable {
+    /// the type of the activity, for counting what the inbox receives
+    pub fn kind(&self) -> String {
+        match self {
+            Inboxable::Postable(ApPostable::Note(note)) => format!("{:?}", note.type_field),
+            Inboxable::Postable(ApPostable::Question(_)) => "Question".to_string(),
+            Inboxable::Postable(ApPostable::Share(_)) => "Announce".to_string(),
+            Inboxable::Postable(ApPostable::Publication(publication)) => {
+                format!("{:?}", publication.type_field)
+            }
+            Inboxable::Create(_) => "Create".to_string(),
+            Inboxable::Update(_) => "Update".to_string(),
+            Inboxable::Delete(_) => "Delete".to_string(),
+            Inboxable::Follow(_) => "Follow".to_string(),
+            Inboxable::FollowResponse(response) => format!("{:?}", response.type_field),
+            Inboxable::Move(_) => "Move".to_string(),
+            Inboxable::Undo(Undo {
+                object: Undoable::Announce(_),
+                ..
+            }) => "Undo Announce".to_string(),
+            Inboxable::Undo(Undo {
+                object: Undoable::Follow(_),
+                ..
+            }) => "Undo Follow".to_string(),
+            Inboxable::Unknown(kind) => match kind.split(' ').all(|x| COUNTED_KINDS.contains(&x)) {
+                true => kind.clone(),
+                false => "other".to_string(),
+            },
+        }
+    }
     pub async fn verify(
         self,
         origin_domain: &str,
@@ -62,7 +203,7 @@ impl Inboxable {
         match self {
             Inboxable::Postable(postable) => match postable.verify(origin_domain) {
                 Ok(x) => Ok(VerifiedInboxable::Postable(x)),
-                Err(x) => return Err(x),
+                Err(x) => Err(x),
             },
             Inboxable::Create(create) => Ok({
                 // let postable = match create.object {
@@ -81,14 +222,41 @@ impl Inboxable {
                 //         }
                 //     }
                 // };
-                match create.object.verify(origin_domain) {
-                    Ok(x) => VerifiedInboxable::Postable(x),
+                // the create is kept for groups to announce
+                match create.object.clone().verify(origin_domain) {
+                    Ok(_) => VerifiedInboxable::Create(create),
                     Err(x) => return Err(x),
                 }
             }),
+            Inboxable::Update(update) => {
+                if update.actor.domain().ne(&Some(origin_domain))
+                    || update.id.domain().ne(&Some(origin_domain))
+                {
+                    return Err(InboxableVerifyErr::ForgedAttribution);
+                }
+                match update.object {
+                    Updatable::Postable(postable) => {
+                        let postable = postable.verify(origin_domain)?;
+                        if postable.actor().ne(&update.actor) {
+                            return Err(InboxableVerifyErr::ForgedAttribution);
+                        }
+                        Ok(VerifiedInboxable::Update(Update {
+                            object: Updatable::Postable(postable),
+                            ..update
+                        }))
+                    }
+                    Updatable::Actor(actor) => {
+                        if actor.id.ne(&update.actor) {
+                            return Err(InboxableVerifyErr::ForgedAttribution);
+                        }
+                        Ok(VerifiedInboxable::Actor(actor))
+                    }
+                }
+            }
             Inboxable::Delete(delete) => {
                 if delete.actor.domain().ne(&Some(origin_domain))
-                    || delete.object.domain().ne(&Some(origin_domain))
+                    || delete.id.domain().ne(&Some(origin_domain))
+                    || delete.object.id().domain().ne(&Some(origin_domain))
                 {
                     return Err(InboxableVerifyErr::ForgedAttribution);
                 }
@@ -102,6 +270,31 @@ impl Inboxable {
                 }
                 Ok(VerifiedInboxable::Follow(follow))
             }
+            Inboxable::Move(move_activity) => {
+                // only moving yourself is supported, the target
+                // is verified when the move is handled
+                if move_activity.actor.domain().ne(&Some(origin_domain))
+                    || move_activity.id.domain().ne(&Some(origin_domain))
+                    || move_activity.object.ne(&move_activity.actor)
+                {
+                    return Err(InboxableVerifyErr::ForgedAttribution);
+                }
+                Ok(VerifiedInboxable::Move(move_activity))
+            }
+            Inboxable::Undo(undo) => {
+                let (undone_id, undone_actor) = match &undo.object {
+                    Undoable::Announce(share) => (&share.id, &share.actor),
+                    Undoable::Follow(follow) => (&follow.id, &follow.actor),
+                };
+                if undo.actor.domain().ne(&Some(origin_domain))
+                    || undo.id.domain().ne(&Some(origin_domain))
+                    || undone_id.domain().ne(&Some(origin_domain))
+                    || undone_actor.ne(&undo.actor)
+                {
+                    return Err(InboxableVerifyErr::ForgedAttribution);
+                }
+                Ok(VerifiedInboxable::Undo(undo))
+            }
             Inboxable::FollowResponse(follow_response) => {
                 if follow_response.actor.domain().ne(&Some(origin_domain))
                     || follow_response.id.domain().ne(&Some(origin_domain))
@@ -110,6 +303,108 @@ impl Inboxable {
                 }
                 Ok(VerifiedInboxable::FollowResponse(follow_response))
             }
+            // nothing is done with it, so there is nothing to verify
+            Inboxable::Unknown(kind) => Ok(VerifiedInboxable::Unknown(kind)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Inboxable;
+
+    #[test]
+    fn test_unsupported_activities_are_unknown() -> Result<(), String> {
+        let like = r##"
+{
+	"@context": "https://www.w3.org/ns/activitystreams",
+	"id": "https://mastodon.social/users/Mastodon#likes/3924",
+	"type": "Like",
+	"actor": "https://mastodon.social/users/Mastodon",
+	"object": "https://tags.example/tags/rust/announces/0190f4c0-2b5e-7c6e-9a4b-3c1a2f9e8d7b"
+}
+        "##;
+        let undo_like = r##"
+{
+	"@context": "https://www.w3.org/ns/activitystreams",
+	"id": "https://mastodon.social/users/Mastodon#likes/3924/undo",
+	"type": "Undo",
+	"actor": "https://mastodon.social/users/Mastodon",
+	"object": {
+		"id": "https://mastodon.social/users/Mastodon#likes/3924",
+		"type": "Like",
+		"actor": "https://mastodon.social/users/Mastodon",
+		"object": "https://tags.example/tags/rust/announces/0190f4c0-2b5e-7c6e-9a4b-3c1a2f9e8d7b"
+	}
+}
+        "##;
+        for (body, expected) in [(like, "Like"), (undo_like, "Undo Like")] {
+            let inboxable: Inboxable = serde_json::from_str(body).map_err(|x| x.to_string())?;
+            match inboxable {
+                Inboxable::Unknown(kind) if kind.eq(expected) => {}
+                x => return Err(format!("{expected} deserialized to {:?}", x)),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_types_are_counted_as_other() -> Result<(), String> {
+        for (kind, expected) in [
+            ("Like", "Like"),
+            ("Undo Like", "Undo Like"),
+            ("Spam1", "other"),
+            ("Undo Spam2", "other"),
+        ] {
+            let counted = Inboxable::Unknown(kind.to_string()).kind();
+            if counted.ne(expected) {
+                return Err(format!("{kind} was counted as {counted}"));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_embedded_accept() -> Result<(), String> {
+        // mastodon embeds the follow it accepts
+        let accept = r##"
+{
+	"@context": "https://www.w3.org/ns/activitystreams",
+	"id": "https://mastodon.social/users/Mastodon#accepts/follows/2218",
+	"type": "Accept",
+	"actor": "https://mastodon.social/users/Mastodon",
+	"object": {
+		"id": "https://tags.example/follows/0190f4c0-2b5e-7c6e-9a4b-3c1a2f9e8d7b",
+		"type": "Follow",
+		"actor": "https://tags.example/tags/rust",
+		"object": "https://mastodon.social/users/Mastodon"
+	}
+}
+        "##;
+        let inboxable: Inboxable = serde_json::from_str(accept).map_err(|x| x.to_string())?;
+        match inboxable {
+            Inboxable::FollowResponse(response)
+                if response.object.id().as_str()
+                    == "https://tags.example/follows/0190f4c0-2b5e-7c6e-9a4b-3c1a2f9e8d7b" =>
+            {
+                Ok(())
+            }
+            x => Err(format!("accept deserialized to {:?}", x)),
+        }
+    }
+
+    #[test]
+    fn test_malformed_activity_names_its_type() -> Result<(), String> {
+        let follow = r##"
+{
+	"id": "https://mastodon.social/users/Mastodon#follows/2218",
+	"type": "Follow",
+	"actor": "https://mastodon.social/users/Mastodon"
+}
+        "##;
+        match serde_json::from_str::<Inboxable>(follow) {
+            Err(err) if err.to_string().starts_with("invalid Follow") => Ok(()),
+            x => Err(format!("malformed follow deserialized to {:?}", x)),
         }
     }
 }
@@ -0,0 +1,203 @@
+//! the languages of posts. most software declares the language of a post
+//! through its content map, for the rest a rough guess is made from the
+//! script of its text, or from the most common words of a few languages
+
+/// short words that are frequent in one language and rare in the
+/// others. words shared by several languages count towards each
+const COMMON_WORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "of", "to", "in", "that", "it", "with", "for", "this",
+            "was", "you", "not", "have", "be", "on",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ich", "ein", "eine", "mit", "auf", "den",
+            "zu", "sich", "auch", "es", "wir", "für",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "des", "une", "un", "pas", "que", "pour", "dans",
+            "avec", "je", "sur", "du", "nous", "qui",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "y", "es", "que", "de", "en", "un", "una", "por", "con",
+            "para", "no", "del", "pero", "muy",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "as", "e", "é", "que", "de", "em", "um", "uma", "não", "para", "com", "do",
+            "da", "mais", "você", "muito",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "lo", "gli", "e", "è", "che", "di", "un", "una", "non", "per", "con", "del",
+            "della", "sono", "anche", "questo", "ma",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "is", "van", "niet", "dat", "ik", "op", "te", "met", "zijn",
+            "voor", "ook", "maar", "wij", "naar",
+        ],
+    ),
+];
+
+/// a guess is only made from at least this many common words
+const MIN_COMMON_WORDS: usize = 2;
+
+/// if `code` looks like a language tag, such as `de`, `pt-br` or `zh-hant`
+pub fn is_language_code(code: &str) -> bool {
+    let mut subtags = code.split('-');
+    let primary = subtags.next().unwrap_or_default();
+    (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags
+            .all(|x| (2..=8).contains(&x.len()) && x.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// if a post in `language` is in the `wanted` language. a wanted
+/// language without a region or script also takes in all of them,
+/// `pt` matches `pt-BR` but `pt-BR` does not match `pt-PT`
+pub fn language_matches(wanted: &str, language: &str) -> bool {
+    let primary = language.split(['-', '_']).next().unwrap_or(language);
+    wanted.eq_ignore_ascii_case(language) || wanted.eq_ignore_ascii_case(primary)
+}
+
+/// a guess at the language of plain text, none if it is too
+/// short or too mixed to tell
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    by_script(text).or_else(|| by_common_words(text))
+}
+
+/// languages with a script of their own
+fn by_script(text: &str) -> Option<&'static str> {
+    let mut letters = 0;
+    let (mut kana, mut hangul, mut han, mut cyrillic, mut ukrainian) = (0, 0, 0, 0, 0);
+    let (mut greek, mut arabic, mut hebrew, mut thai) = (0, 0, 0, 0);
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        match c {
+            '\u{3040}'..='\u{30ff}' => kana += 1,
+            '\u{ac00}'..='\u{d7af}' | '\u{1100}'..='\u{11ff}' => hangul += 1,
+            '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}' => han += 1,
+            'і' | 'ї' | 'є' | 'ґ' | 'І' | 'Ї' | 'Є' | 'Ґ' => {
+                cyrillic += 1;
+                ukrainian += 1;
+            }
+            '\u{0400}'..='\u{04ff}' => cyrillic += 1,
+            '\u{0370}'..='\u{03ff}' => greek += 1,
+            '\u{0600}'..='\u{06ff}' => arabic += 1,
+            '\u{0590}'..='\u{05ff}' => hebrew += 1,
+            '\u{0e00}'..='\u{0e7f}' => thai += 1,
+            _ => (),
+        }
+    }
+    let most = |count: i32| count * 2 > letters;
+    if kana > 0 && most(kana + han) {
+        Some("ja")
+    } else if most(hangul) {
+        Some("ko")
+    } else if most(han) {
+        Some("zh")
+    } else if most(cyrillic) {
+        Some(match ukrainian {
+            0 => "ru",
+            _ => "uk",
+        })
+    } else if most(greek) {
+        Some("el")
+    } else if most(arabic) {
+        Some("ar")
+    } else if most(hebrew) {
+        Some("he")
+    } else if most(thai) {
+        Some("th")
+    } else {
+        None
+    }
+}
+
+/// the language with the most common words, as long as
+/// no other language has as many
+fn by_common_words(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|x| !x.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let mut scores: Vec<(&str, usize)> = COMMON_WORDS
+        .iter()
+        .map(|(language, common)| {
+            let score = words
+                .iter()
+                .filter(|x| common.contains(&x.as_str()))
+                .count();
+            (*language, score)
+        })
+        .collect();
+    scores.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    match scores.as_slice() {
+        [(language, best), (_, second), ..] if *best >= MIN_COMMON_WORDS && best > second => {
+            Some(language)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_language, is_language_code, language_matches};
+
+    #[test]
+    fn test_detect_language() -> Result<(), String> {
+        let cases = [
+            ("Rust 1.80 ist da und bringt LazyCell mit, ich freue mich auf die neuen Features #rust", Some("de")),
+            ("The new release is out and it comes with LazyCell, have a look at the notes #rust", Some("en")),
+            ("Je suis ravi de la nouvelle version, elle est disponible pour tout le monde", Some("fr")),
+            ("新しいバージョンがリリースされました", Some("ja")),
+            ("Вийшла нова версія, і вона чудова", Some("uk")),
+            ("Rust 1.80 #rust", None),
+        ];
+        for (text, expected) in cases {
+            let detected = detect_language(text);
+            if detected != expected {
+                return Err(format!(
+                    "expected {:?} for {}, got {:?}",
+                    expected, text, detected
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn regions_match_their_language() -> Result<(), String> {
+        let matches = [
+            language_matches("pt", "pt-BR"),
+            language_matches("pt-br", "pt-BR"),
+            !language_matches("pt-PT", "pt-BR"),
+            !language_matches("de", "en"),
+            is_language_code("zh-hant"),
+            !is_language_code("rust"),
+            !is_language_code("d"),
+        ];
+        match matches.iter().all(|x| *x) {
+            true => Ok(()),
+            false => Err(format!("unexpected matches {:?}", matches)),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use super::core_types::OptionalArray;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum LinkType {
     Link,
@@ -22,13 +24,32 @@ pub struct Link {
 
     pub href: Url,
     pub name: Option<String>,
-    // pub hreflang: Option<String>,
-    // pub media_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hreflang: Option<String>,
+    /// quotes are links with the activitystreams media type, see
+    /// [`super::note::Note::quote_url`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rel: Option<OptionalArray<String>>,
 
-    // pub height: Option<u32>,
-    // pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
     // pub preview: Option<String>, //TODO
-    // pub rel: Option<String>,     //TODO
+}
+
+impl Link {
+    /// links to an activitypub object rather than a web page
+    pub fn is_object_link(&self) -> bool {
+        matches!(self.type_field, LinkType::Link)
+            && self.media_type.as_deref().is_some_and(|media_type| {
+                media_type.starts_with("application/activity+json")
+                    || (media_type.starts_with("application/ld+json")
+                        && media_type.contains("https://www.w3.org/ns/activitystreams"))
+            })
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -47,6 +68,71 @@ impl LinkSimpleOrExpanded {
     }
 }
 
+/// an entry of a `tag` field that may not be a link, such as the
+/// hashtags without an href some software sends, or custom emoji
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum TagItem {
+    Link(LinkSimpleOrExpanded),
+    Object(serde_json::Map<String, serde_json::Value>),
+}
+
+impl TagItem {
+    /// the name of the tag if it is a hashtag
+    pub fn hashtag(&self) -> Option<&str> {
+        match self {
+            TagItem::Link(LinkSimpleOrExpanded::Expanded(link)) => match link.type_field {
+                LinkType::Hashtag => link.name.as_deref(),
+                _ => None,
+            },
+            TagItem::Link(LinkSimpleOrExpanded::Simple(_)) => None,
+            TagItem::Object(object) => match object.get("type")?.as_str()? {
+                "Hashtag" => object.get("name")?.as_str(),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// the names of every hashtag in a `tag` field
+pub fn get_hashtags(tag: &Option<OptionalArray<LinkSimpleOrExpanded>>) -> Option<Vec<String>> {
+    if let Some(tags) = tag {
+        let tags = tags.clone().into_array();
+        let tags = tags.into_iter().filter_map(|link| {
+            if let LinkSimpleOrExpanded::Expanded(link) = link {
+                if matches!(link.type_field, LinkType::Hashtag) {
+                    if let Some(name) = link.name {
+                        return Some(name);
+                    }
+                }
+            }
+            None
+        });
+        return Some(tags.collect());
+    }
+    None
+}
+
+/// if the public collection is one of the direct recipients
+pub fn addresses_public(to: &Option<OptionalArray<LinkSimpleOrExpanded>>) -> bool {
+    if let Some(to) = to {
+        let to = match to {
+            OptionalArray::Single(x) => &vec![x.clone()],
+            OptionalArray::Multiple(items) => items,
+        };
+        for recipient in to {
+            if recipient
+                .get_url()
+                .as_str()
+                .eq("https://www.w3.org/ns/activitystreams#Public")
+            {
+                return true;
+            }
+        }
+    }
+    false
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -1,16 +1,25 @@
 pub mod actors;
+pub mod collection;
 pub mod context;
 pub mod core_types;
 pub mod create;
 pub mod delete;
+pub mod extensions;
 pub mod follow_and_response;
+pub mod hashtag;
+pub mod html;
 pub mod inboxable;
+pub mod language;
 pub mod link;
+pub mod move_activity;
 pub mod note;
 pub mod postable;
 pub mod public_key;
+pub mod publication;
 pub mod question;
 pub mod serde_fns;
 pub mod share;
 pub mod tombstone;
+pub mod undo;
+pub mod update;
 pub mod webfinger;
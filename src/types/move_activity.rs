@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::context::{Context, ContextWrap};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum MoveType {
+    Move,
+}
+
+/// Indicates that the actor has moved object from origin to target.
+///
+/// used for account migrations where the actor moves themselves to
+/// a new actor. the target lists the actor in its `alsoKnownAs`
+/// to prove the move was intended
+///
+/// ```json
+/// {
+///   "@context": "https://www.w3.org/ns/activitystreams",
+///   "type": "Move",
+///   "id": "https://old.example/users/alice#moves/1",
+///   "actor": "https://old.example/users/alice",
+///   "object": "https://old.example/users/alice",
+///   "target": "https://new.example/users/alice"
+/// }
+/// ```
+///
+/// https://www.w3.org/TR/activitystreams-vocabulary/#dfn-move
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Move {
+    #[serde(rename = "type")]
+    pub type_field: MoveType,
+    pub id: Url,
+    pub actor: Url,
+    pub object: Url,
+    pub target: Url,
+}
+
+impl Move {
+    pub fn wrap_context(self) -> ContextWrap<Self> {
+        ContextWrap {
+            context: Context::Single("https://www.w3.org/ns/activitystreams".to_owned()),
+            item: self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::context::ContextWrap;
+
+    use super::Move;
+
+    #[test]
+    fn test_deserialize_move() -> Result<(), String> {
+        // an account migration in the shape mastodon sends them
+        let test_move = r##"
+{
+	"@context": "https://www.w3.org/ns/activitystreams",
+	"id": "https://mastodon.social/users/Mastodon#moves/1",
+	"type": "Move",
+	"actor": "https://mastodon.social/users/Mastodon",
+	"object": "https://mastodon.social/users/Mastodon",
+	"target": "https://mastodon.online/users/Mastodon"
+}
+        "##;
+        let deserialized: Result<ContextWrap<Move>, serde_json::Error> =
+            serde_json::from_str(test_move);
+        match deserialized {
+            Ok(_) => Ok(()),
+            Err(x) => Err(format!(
+                "move activity deserialize failed with response: {}",
+                x
+            )),
+        }
+    }
+}
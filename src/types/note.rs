@@ -1,8 +1,15 @@
 use std::collections::HashMap;
 
 use super::context::{ContextItem, ContextMapItem};
-use super::serde_fns::{deserialize_time, serialize_time};
-use super::{context::Context, core_types::OptionalArray, link::LinkSimpleOrExpanded};
+use super::serde_fns::{
+    deserialize_optional_time, deserialize_time, serialize_optional_time, serialize_time_auto,
+};
+use super::{
+    context::Context,
+    core_types::OptionalArray,
+    extensions::Extensions,
+    link::{addresses_public, get_hashtags, LinkSimpleOrExpanded},
+};
 use serde::{Deserialize, Serialize};
 use url::Url;
 
@@ -35,17 +42,95 @@ pub struct Note {
     pub attributed_to: Url,
 
     #[serde(deserialize_with = "deserialize_time")]
-    #[serde(serialize_with = "serialize_time")]
+    #[serde(serialize_with = "serialize_time_auto")]
     pub published: i64,
 
+    /// set once the note has been edited
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_optional_time")]
+    #[serde(serialize_with = "serialize_optional_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated: Option<i64>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub to: Option<OptionalArray<LinkSimpleOrExpanded>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cc: Option<OptionalArray<LinkSimpleOrExpanded>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_reply_to: Option<Url>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tag: Option<OptionalArray<LinkSimpleOrExpanded>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<OptionalArray<LinkSimpleOrExpanded>>,
+
+    /// the content warning when the note is sensitive
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensitive: Option<bool>,
+
+    /// html
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// the content keyed by its language
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_map: Option<HashMap<String, String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachment: Option<OptionalArray<Attachment>>,
+
+    /// the post this note quotes, see [`Note::quote`]. software that
+    /// quotes usually sends every one of these names at once
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_url: Option<Url>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_uri: Option<Url>,
+    #[serde(rename = "_misskey_quote")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub misskey_quote: Option<Url>,
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum AttachmentType {
+    /// mastodon sends every kind of media as a document
+    Document,
+    Image,
+    Video,
+    Audio,
+    Link,
+}
+
+/// media attached to a post
+///
+/// https://www.w3.org/TR/activitystreams-vocabulary/#dfn-attachment
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    #[serde(rename = "type")]
+    pub type_field: AttachmentType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<OptionalArray<LinkSimpleOrExpanded>>,
+    /// the alt text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// a placeholder to show while the media loads
+    ///
+    /// https://blurha.sh
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blurhash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub focal_point: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
 }
 
 impl Note {
@@ -88,39 +173,46 @@ impl Note {
         ])
     }
     pub fn get_tags(&self) -> Option<Vec<String>> {
-        if let Some(tags) = &self.tag {
-            let tags = tags.clone().into_array();
-            let tags = tags.into_iter().filter_map(|link| {
-                if let LinkSimpleOrExpanded::Expanded(link) = link {
-                    if matches!(link.type_field, super::link::LinkType::Hashtag) {
-                        if let Some(name) = link.name {
-                            return Some(name);
-                        }
-                    }
-                }
-                None
-            });
-            return Some(tags.collect());
-        }
-        return None;
+        get_hashtags(&self.tag)
     }
     pub fn is_public(&self) -> bool {
-        if let Some(to) = &self.to {
-            let to = match to {
-                OptionalArray::Single(x) => &vec![x.clone()],
-                OptionalArray::Multiple(items) => items,
-            };
-            for recipient in to {
-                if recipient
-                    .get_url()
-                    .as_str()
-                    .eq("https://www.w3.org/ns/activitystreams#Public")
-                {
-                    return true;
-                }
-            }
+        addresses_public(&self.to)
+    }
+    /// the declared language, which is only known when the
+    /// content map holds a single language
+    pub fn language(&self) -> Option<&str> {
+        let content_map = self.content_map.as_ref()?;
+        match content_map.len() {
+            1 => content_map.keys().next().map(|x| x.as_str()),
+            _ => None,
         }
-        false
+    }
+    /// the post this note quotes, by any of the names quotes go by or
+    /// as a link tag with the activitystreams media type (FEP-e232)
+    pub fn quote(&self) -> Option<&Url> {
+        if let Some(quote) = self
+            .quote_url
+            .as_ref()
+            .or(self.quote_uri.as_ref())
+            .or(self.misskey_quote.as_ref())
+        {
+            return Some(quote);
+        }
+        let tags = match &self.tag {
+            Some(OptionalArray::Single(tag)) => std::slice::from_ref(tag),
+            Some(OptionalArray::Multiple(tags)) => tags.as_slice(),
+            None => &[],
+        };
+        tags.iter().find_map(|tag| match tag {
+            LinkSimpleOrExpanded::Expanded(link) if link.is_object_link() => Some(&link.href),
+            _ => None,
+        })
+    }
+    pub fn attachments(&self) -> Vec<Attachment> {
+        self.attachment
+            .clone()
+            .map(|x| x.into_array())
+            .unwrap_or_default()
     }
 }
 
@@ -258,11 +350,140 @@ mod tests {
         "##;
         let deserialized: Result<ContextWrap<Note>, serde_json::Error> =
             serde_json::from_str(test_note);
-        let _deserialized = match deserialized {
+        let deserialized = match deserialized {
             Ok(x) => x,
             Err(x) => return Err(format!("note deserialize failed with response: {}", x)),
         };
+        let note = &deserialized.item;
+        if note.sensitive.ne(&Some(false)) || note.language().ne(&Some("en")) {
+            return Err(format!("note fields deserialized wrong: {:?}", note));
+        }
 
-        Ok(())
+        // every field that is kept serializes back to what was received
+        let original: serde_json::Value =
+            serde_json::from_str(test_note).map_err(|x| x.to_string())?;
+        let serialized = serde_json::to_value(&deserialized).map_err(|x| x.to_string())?;
+        let Some(serialized) = serialized.as_object() else {
+            return Err("note did not serialize to an object".to_string());
+        };
+        for (key, value) in serialized {
+            if original.get(key).ne(&Some(value)) {
+                return Err(format!("{key} did not round trip, got {value}"));
+            }
+        }
+        // and nothing that was received is lost, modeled or not
+        let Some(original) = original.as_object() else {
+            return Err("note fixture is not an object".to_string());
+        };
+        if let Some(key) = original
+            .iter()
+            .find(|(key, value)| !value.is_null() && !serialized.contains_key(*key))
+            .map(|(key, _)| key)
+        {
+            return Err(format!("{key} was dropped"));
+        }
+        match note.extensions.atom_uri().is_some() && note.extensions.conversation().is_some() {
+            true => Ok(()),
+            false => Err(format!("extensions read wrong: {:?}", note.extensions)),
+        }
+    }
+
+    #[test]
+    fn test_deserialize_note_with_media() -> Result<(), String> {
+        // a reply behind a content warning with an image and a quote,
+        // in the shape fedibird sends them
+        let test_note = r##"
+{
+	"@context": [
+		"https://www.w3.org/ns/activitystreams",
+		{
+			"sensitive": "as:sensitive",
+			"toot": "http://joinmastodon.org/ns#",
+			"blurhash": "toot:blurhash",
+			"focalPoint": {
+				"@container": "@list",
+				"@id": "toot:focalPoint"
+			},
+			"fedibird": "http://fedibird.com/ns#",
+			"quoteUri": "fedibird:quoteUri"
+		}
+	],
+	"id": "https://fedibird.example/users/alice/statuses/113000000000000001",
+	"type": "Note",
+	"summary": "cat pictures",
+	"inReplyTo": "https://mastodon.social/users/Mastodon/statuses/112769333503182077",
+	"published": "2024-08-01T10:00:00Z",
+	"updated": "2024-08-01T10:05:00Z",
+	"attributedTo": "https://fedibird.example/users/alice",
+	"to": "https://www.w3.org/ns/activitystreams#Public",
+	"cc": [
+		"https://fedibird.example/users/alice/followers",
+		"https://mastodon.social/users/Mastodon"
+	],
+	"sensitive": true,
+	"content": "<p>look at him</p><p>RE: <a href=\"https://mastodon.social/@Mastodon/112769333503182077\">mastodon.social/@Mastodon/1127</a></p>",
+	"contentMap": {
+		"en": "<p>look at him</p><p>RE: <a href=\"https://mastodon.social/@Mastodon/112769333503182077\">mastodon.social/@Mastodon/1127</a></p>"
+	},
+	"quoteUrl": "https://mastodon.social/users/Mastodon/statuses/112769333503182077",
+	"quoteUri": "https://mastodon.social/users/Mastodon/statuses/112769333503182077",
+	"_misskey_quote": "https://mastodon.social/users/Mastodon/statuses/112769333503182077",
+	"attachment": [
+		{
+			"type": "Document",
+			"mediaType": "image/jpeg",
+			"url": "https://files.fedibird.example/media_attachments/files/113/000/000/original/cat.jpg",
+			"name": "a grey cat asleep on a keyboard",
+			"blurhash": "UFG[Wk~q-;xu%MRjRjxu_3t7M{WBt7ofRjWB",
+			"focalPoint": [
+				0.0,
+				0.25
+			],
+			"width": 1200,
+			"height": 900
+		}
+	],
+	"tag": [
+		{
+			"type": "Link",
+			"mediaType": "application/ld+json; profile=\"https://www.w3.org/ns/activitystreams\"",
+			"href": "https://mastodon.social/users/Mastodon/statuses/112769333503182077",
+			"name": "RE: https://mastodon.social/users/Mastodon/statuses/112769333503182077"
+		}
+	]
+}
+        "##;
+        let deserialized: ContextWrap<Note> = serde_json::from_str(test_note)
+            .map_err(|x| format!("note deserialize failed with response: {}", x))?;
+        let note = deserialized.item;
+        let attachments = note.attachments();
+        let Some(attachment) = attachments.first() else {
+            return Err("attachment was dropped".to_string());
+        };
+        if attachment
+            .name
+            .as_deref()
+            .ne(&Some("a grey cat asleep on a keyboard"))
+            || attachment.blurhash.is_none()
+            || attachment.media_type.as_deref().ne(&Some("image/jpeg"))
+        {
+            return Err(format!("attachment deserialized wrong: {:?}", attachment));
+        }
+        if note.summary.as_deref().ne(&Some("cat pictures"))
+            || note.sensitive.ne(&Some(true))
+            || note.in_reply_to.is_none()
+            || note.updated.is_none()
+        {
+            return Err(format!("note fields deserialized wrong: {:?}", note));
+        }
+        let quoted = "https://mastodon.social/users/Mastodon/statuses/112769333503182077";
+        let mut link_only = note.clone();
+        link_only.quote_url = None;
+        link_only.quote_uri = None;
+        link_only.misskey_quote = None;
+        match (note.quote(), link_only.quote()) {
+            (Some(a), Some(b)) if a.as_str().eq(quoted) && b.as_str().eq(quoted) => Ok(()),
+            x => Err(format!("quote not found: {:?}", x)),
+        }
     }
 }
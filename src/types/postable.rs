@@ -1,8 +1,20 @@
+use std::collections::HashMap;
+
 use super::inboxable::InboxableVerifyErr;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use super::{context::ContextWrap, note::Note, question::Question, share::Share};
+use super::{
+    context::ContextWrap,
+    extensions::Extensions,
+    hashtag::{merge_hashtags, Hashtag},
+    html::strip_html,
+    language::{detect_language, is_language_code},
+    note::{AttachmentType, Note},
+    publication::{Publication, PublicationType},
+    question::Question,
+    share::Share,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(untagged)]
@@ -11,6 +23,19 @@ pub enum ApPostable {
     Question(Question),
     Note(Note),
     Share(Share),
+    /// pages, videos, events and audio, tried last as
+    /// it is the most lenient about its authors
+    Publication(Publication),
+}
+
+/// media attached to an object we have not modeled attachments for
+fn has_media(extensions: &Extensions) -> bool {
+    extensions.attachment().iter().any(|x| {
+        matches!(
+            x.get("type").and_then(|x| x.as_str()),
+            Some("Document" | "Image" | "Video" | "Audio")
+        )
+    })
 }
 
 impl ApPostable {
@@ -19,15 +44,176 @@ impl ApPostable {
             ApPostable::Question(question) => &question.id,
             ApPostable::Note(note) => &note.id,
             ApPostable::Share(share) => &share.id,
+            ApPostable::Publication(publication) => &publication.id,
         }
     }
     pub fn actor(&self) -> &Url {
         match self {
-            ApPostable::Question(question) => &question.actor,
+            ApPostable::Question(question) => &question.attributed_to,
             ApPostable::Note(note) => &note.attributed_to,
             ApPostable::Share(share) => &share.actor,
+            ApPostable::Publication(publication) => publication.actor(),
+        }
+    }
+    pub fn published(&self) -> i64 {
+        match self {
+            ApPostable::Question(question) => question.published,
+            ApPostable::Note(note) => note.published,
+            ApPostable::Share(share) => share.published,
+            ApPostable::Publication(publication) => publication.published,
+        }
+    }
+    /// the hashtags of the post, shares have none of their own
+    pub fn get_tags(&self) -> Option<Vec<String>> {
+        match self {
+            ApPostable::Question(question) => question.get_tags(),
+            ApPostable::Note(note) => note.get_tags(),
+            ApPostable::Share(_) => None,
+            ApPostable::Publication(publication) => publication.get_tags(),
+        }
+    }
+    /// the hashtags of the tag field along with those only found in
+    /// the content, see [`merge_hashtags`]
+    pub fn hashtags(&self) -> Vec<Hashtag> {
+        let (content, content_map) = match self {
+            ApPostable::Question(question) => (
+                &question.content,
+                &question
+                    .extensions
+                    .get::<HashMap<String, String>>("contentMap"),
+            ),
+            ApPostable::Note(note) => (&note.content, &note.content_map),
+            ApPostable::Share(_) => return Vec::new(),
+            ApPostable::Publication(publication) => {
+                (&publication.content, &publication.content_map)
+            }
+        };
+        let contents: Vec<&str> = content
+            .iter()
+            .chain(content_map.iter().flat_map(HashMap::values))
+            .map(String::as_str)
+            .collect();
+        merge_hashtags(self.get_tags(), &contents)
+    }
+    /// the plain text of the post's content
+    pub fn text(&self) -> Option<String> {
+        let content = match self {
+            ApPostable::Question(question) => question.content.clone(),
+            ApPostable::Note(note) => note.content.clone(),
+            ApPostable::Share(_) => None,
+            ApPostable::Publication(publication) => publication.text(),
+        };
+        content.as_deref().map(strip_html)
+    }
+    /// the post this one replies to
+    pub fn in_reply_to(&self) -> Option<Url> {
+        match self {
+            ApPostable::Note(note) => note.in_reply_to.clone(),
+            ApPostable::Share(_) => None,
+            ApPostable::Question(Question { extensions, .. })
+            | ApPostable::Publication(Publication { extensions, .. }) => {
+                extensions.get_url("inReplyTo")
+            }
         }
     }
+    /// marked sensitive or behind a content warning
+    pub fn is_sensitive(&self) -> bool {
+        let (sensitive, summary) = match self {
+            ApPostable::Question(question) => (
+                question.extensions.get_bool("sensitive"),
+                question.extensions.get_str("summary"),
+            ),
+            ApPostable::Note(note) => (note.sensitive, note.summary.as_deref()),
+            ApPostable::Share(_) => return false,
+            ApPostable::Publication(publication) => (publication.sensitive, None),
+        };
+        sensitive == Some(true) || summary.is_some_and(|x| !x.trim().is_empty())
+    }
+    /// has images, video or audio attached, or is a video or audio itself
+    pub fn has_media(&self) -> bool {
+        match self {
+            ApPostable::Note(note) => note
+                .attachment
+                .iter()
+                .flat_map(|x| x.clone().into_array())
+                .any(|x| !matches!(x.type_field, AttachmentType::Link)),
+            ApPostable::Share(_) => false,
+            ApPostable::Question(Question { extensions, .. }) => has_media(extensions),
+            ApPostable::Publication(publication) => {
+                matches!(
+                    publication.type_field,
+                    PublicationType::Video | PublicationType::Audio
+                ) || has_media(&publication.extensions)
+            }
+        }
+    }
+    /// the languages the post is in, as declared by its content map or
+    /// lemmy's language, or failing that as detected from its text
+    pub fn languages(&self) -> Vec<String> {
+        let (content_map, extensions) = match self {
+            ApPostable::Question(question) => (None, &question.extensions),
+            ApPostable::Note(note) => (note.content_map.as_ref(), &note.extensions),
+            ApPostable::Share(_) => return Vec::new(),
+            ApPostable::Publication(publication) => {
+                (publication.content_map.as_ref(), &publication.extensions)
+            }
+        };
+        let mut languages: Vec<String> = content_map
+            .map(|x| x.keys().cloned().collect())
+            .or_else(|| {
+                let content_map = extensions.0.get("contentMap")?.as_object()?;
+                Some(content_map.keys().cloned().collect())
+            })
+            .unwrap_or_default();
+        // lemmy's undetermined language
+        let lemmy = extensions
+            .0
+            .get("language")
+            .and_then(|x| x.get("identifier")?.as_str())
+            .filter(|x| *x != "und");
+        languages.extend(lemmy.map(str::to_string));
+        languages.retain(|x| is_language_code(x));
+        if languages.is_empty() {
+            let detected = self.text().as_deref().and_then(detect_language);
+            languages.extend(detected.map(str::to_string));
+        }
+        languages.sort();
+        languages.dedup();
+        languages
+    }
+    /// the post this one quotes, only notes quote
+    pub fn quote(&self) -> Option<&Url> {
+        match self {
+            ApPostable::Note(note) => note.quote(),
+            _ => None,
+        }
+    }
+    pub fn is_public(&self) -> bool {
+        match self {
+            ApPostable::Question(question) => question.is_public(),
+            ApPostable::Note(note) => note.is_public(),
+            ApPostable::Share(_) => false,
+            ApPostable::Publication(publication) => publication.is_public(),
+        }
+    }
+    /// everyone the post is addressed to, including the
+    /// `audience` lemmy addresses the group a post is in with
+    pub fn recipients(&self) -> Vec<Url> {
+        let (to, cc, extensions) = match self {
+            ApPostable::Question(question) => (&question.to, &None, &question.extensions),
+            ApPostable::Note(note) => (&note.to, &note.cc, &note.extensions),
+            ApPostable::Share(_) => return Vec::new(),
+            ApPostable::Publication(publication) => {
+                (&publication.to, &publication.cc, &publication.extensions)
+            }
+        };
+        to.iter()
+            .chain(cc.iter())
+            .flat_map(|x| x.clone().into_array())
+            .map(|x| x.get_url().clone())
+            .chain(extensions.get_url("audience"))
+            .collect()
+    }
     pub fn verify(self, origin_domain: &str) -> Result<Self, InboxableVerifyErr> {
         if self.id().domain().ne(&Some(origin_domain))
             || self.actor().domain().ne(&Some(origin_domain))
@@ -50,6 +236,10 @@ impl ApPostable {
                 context: Share::get_context(),
                 item: ApPostable::Share(share),
             },
+            ApPostable::Publication(publication) => ContextWrap {
+                context: Publication::get_context(),
+                item: ApPostable::Publication(publication),
+            },
         }
     }
 }
@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::serde_fns::{
+    deserialize_optional_time, deserialize_time, serialize_optional_time, serialize_time,
+};
+use super::{
+    context::{Context, ContextItem},
+    core_types::OptionalArray,
+    extensions::Extensions,
+    link::{addresses_public, LinkSimpleOrExpanded, TagItem},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum PublicationType {
+    /// Represents a Web Page.
+    ///
+    /// lemmy and its forks send posts as pages
+    ///
+    /// https://www.w3.org/TR/activitystreams-vocabulary/#dfn-page
+    Page,
+    /// Represents a video document of any kind.
+    ///
+    /// https://www.w3.org/TR/activitystreams-vocabulary/#dfn-video
+    Video,
+    /// Represents any kind of event.
+    ///
+    /// https://www.w3.org/TR/activitystreams-vocabulary/#dfn-event
+    Event,
+    /// Represents an audio document of any kind.
+    ///
+    /// https://www.w3.org/TR/activitystreams-vocabulary/#dfn-audio
+    Audio,
+}
+
+/// the author of a publication. peertube embeds both the account
+/// and the channel a video was uploaded to
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum AttributedTo {
+    Id(Url),
+    Object {
+        #[serde(rename = "type")]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        type_field: Option<String>,
+        id: Url,
+    },
+}
+
+impl AttributedTo {
+    pub fn id(&self) -> &Url {
+        match self {
+            AttributedTo::Id(id) | AttributedTo::Object { id, .. } => id,
+        }
+    }
+}
+
+/// the posts of forums, video and audio platforms and event planners,
+/// which carry hashtags like notes but are titled and often long-form
+///
+/// articles from blogs are deserialized as a [`super::note::Note`]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Publication {
+    #[serde(rename = "type")]
+    pub type_field: PublicationType,
+    pub id: Url,
+
+    pub attributed_to: OptionalArray<AttributedTo>,
+
+    #[serde(deserialize_with = "deserialize_time")]
+    #[serde(serialize_with = "serialize_time")]
+    pub published: i64,
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_optional_time")]
+    #[serde(serialize_with = "serialize_optional_time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<OptionalArray<LinkSimpleOrExpanded>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cc: Option<OptionalArray<LinkSimpleOrExpanded>>,
+
+    /// peertube and funkwhale send hashtags without an href
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<OptionalArray<TagItem>>,
+
+    /// the title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensitive: Option<bool>,
+
+    /// html, or markdown when the media type says so
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_map: Option<HashMap<String, String>>,
+
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+impl Publication {
+    pub fn get_context() -> Context {
+        Context::Array(vec![ContextItem::String(
+            "https://www.w3.org/ns/activitystreams".to_string(),
+        )])
+    }
+    /// the account that published it, rather than the channel
+    /// or group it was published to
+    pub fn actor(&self) -> &Url {
+        let authors = match &self.attributed_to {
+            OptionalArray::Single(author) => std::slice::from_ref(author),
+            OptionalArray::Multiple(authors) => authors.as_slice(),
+        };
+        authors
+            .iter()
+            .find(|author| match author {
+                AttributedTo::Object { type_field, .. } => type_field.as_deref() == Some("Person"),
+                AttributedTo::Id(_) => false,
+            })
+            .or(authors.first())
+            .map(AttributedTo::id)
+            .unwrap_or(&self.id)
+    }
+    pub fn get_tags(&self) -> Option<Vec<String>> {
+        let tags = self.tag.clone()?.into_array();
+        Some(
+            tags.iter()
+                .filter_map(|tag| tag.hashtag().map(str::to_string))
+                .collect(),
+        )
+    }
+    pub fn is_public(&self) -> bool {
+        addresses_public(&self.to)
+    }
+    /// the title and the content, for matching keywords
+    pub fn text(&self) -> Option<String> {
+        match (&self.name, &self.content) {
+            (Some(name), Some(content)) => Some(format!("<p>{name}</p>{content}")),
+            (Some(name), None) => Some(name.clone()),
+            (None, content) => content.clone(),
+        }
+    }
+    /// the declared language, which is only known when the
+    /// content map holds a single language
+    pub fn language(&self) -> Option<&str> {
+        let content_map = self.content_map.as_ref()?;
+        match content_map.len() {
+            1 => content_map.keys().next().map(|x| x.as_str()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{inboxable::Inboxable, postable::ApPostable};
+
+    use super::PublicationType;
+
+    #[test]
+    fn test_deserialize_peertube_video() -> Result<(), String> {
+        let video = r##"
+{
+	"@context": [
+		"https://www.w3.org/ns/activitystreams",
+		"https://w3id.org/security/v1",
+		{
+			"pt": "https://joinpeertube.org/ns#",
+			"Hashtag": "as:Hashtag",
+			"uuid": "sc:identifier"
+		}
+	],
+	"type": "Video",
+	"id": "https://framatube.org/videos/watch/9c9de5e8-0a1e-484a-b099-e80766180a6d",
+	"name": "What is PeerTube?",
+	"duration": "PT113S",
+	"uuid": "9c9de5e8-0a1e-484a-b099-e80766180a6d",
+	"tag": [
+		{
+			"type": "Hashtag",
+			"name": "peertube"
+		},
+		{
+			"type": "Hashtag",
+			"name": "fediverse"
+		}
+	],
+	"views": 125488,
+	"sensitive": false,
+	"published": "2018-10-01T10:52:46.396Z",
+	"updated": "2024-03-02T08:12:31.871Z",
+	"mediaType": "text/markdown",
+	"content": "**[Want to help to translate this video?](https://weblate.framasoft.org/projects/what-is-peertube-video/)**",
+	"url": [
+		{
+			"type": "Link",
+			"mediaType": "text/html",
+			"href": "https://framatube.org/w/kkGMgK9ZtnKfYAgnEtQxbv"
+		}
+	],
+	"attributedTo": [
+		{
+			"type": "Person",
+			"id": "https://framatube.org/accounts/framasoft"
+		},
+		{
+			"type": "Group",
+			"id": "https://framatube.org/video-channels/joinpeertube"
+		}
+	],
+	"to": [
+		"https://www.w3.org/ns/activitystreams#Public"
+	],
+	"cc": [
+		"https://framatube.org/accounts/framasoft/followers"
+	]
+}
+        "##;
+        let inboxable: Inboxable = serde_json::from_str(video).map_err(|x| x.to_string())?;
+        let Inboxable::Postable(postable) = inboxable else {
+            return Err(format!("video deserialized to {:?}", inboxable));
+        };
+        if !matches!(
+            &postable,
+            ApPostable::Publication(publication)
+                if matches!(publication.type_field, PublicationType::Video)
+        ) {
+            return Err(format!("video deserialized to {:?}", postable));
+        }
+        if postable.actor().as_str() != "https://framatube.org/accounts/framasoft"
+            || !postable.is_public()
+        {
+            return Err(format!("video fields deserialized wrong: {:?}", postable));
+        }
+        match postable.get_tags() {
+            Some(tags) if tags.eq(&vec!["peertube".to_string(), "fediverse".to_string()]) => Ok(()),
+            x => Err(format!("unexpected tags {:?}", x)),
+        }
+    }
+
+    #[test]
+    fn test_deserialize_lemmy_page() -> Result<(), String> {
+        let page = r##"
+{
+	"id": "https://lemmy.ml/post/1234567",
+	"type": "Page",
+	"attributedTo": "https://lemmy.ml/u/nutomic",
+	"to": [
+		"https://lemmy.ml/c/rust",
+		"https://www.w3.org/ns/activitystreams#Public"
+	],
+	"name": "Announcing Rust 1.80",
+	"cc": [],
+	"content": "<p>New release with <a href=\"https://lemmy.ml/c/rust\">#rust</a> goodies</p>",
+	"mediaType": "text/html",
+	"attachment": [
+		{
+			"href": "https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html",
+			"type": "Link"
+		}
+	],
+	"sensitive": false,
+	"published": "2024-07-25T16:12:42.913537Z",
+	"language": {
+		"identifier": "en",
+		"name": "English"
+	},
+	"audience": "https://lemmy.ml/c/rust",
+	"tag": [
+		{
+			"href": "https://lemmy.ml/post/1234567",
+			"type": "Hashtag",
+			"name": "#rust"
+		}
+	]
+}
+        "##;
+        let postable: ApPostable = serde_json::from_str(page).map_err(|x| x.to_string())?;
+        let ApPostable::Publication(publication) = &postable else {
+            return Err(format!("page deserialized to {:?}", postable));
+        };
+        if publication.actor().as_str() != "https://lemmy.ml/u/nutomic"
+            || !postable.is_public()
+            || !postable
+                .text()
+                .is_some_and(|text| text.trim_start().starts_with("Announcing Rust 1.80\n"))
+        {
+            return Err(format!("page fields deserialized wrong: {:?}", postable));
+        }
+        if !postable
+            .recipients()
+            .iter()
+            .any(|recipient| recipient.as_str() == "https://lemmy.ml/c/rust")
+        {
+            return Err(format!(
+                "community not addressed: {:?}",
+                postable.recipients()
+            ));
+        }
+        match postable.get_tags() {
+            Some(tags) if tags.eq(&vec!["#rust".to_string()]) => Ok(()),
+            x => Err(format!("unexpected tags {:?}", x)),
+        }
+    }
+}
@@ -1,7 +1,14 @@
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use super::{context::Context, note::Note};
+use super::serde_fns::{deserialize_time, serialize_time};
+use super::{
+    context::Context,
+    core_types::OptionalArray,
+    extensions::Extensions,
+    link::{addresses_public, get_hashtags, LinkSimpleOrExpanded},
+    note::Note,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum QuestionType {
@@ -23,11 +30,26 @@ pub enum QuestionType {
 /// https://www.w3.org/TR/activitystreams-vocabulary/#dfn-question
 pub struct Question {
     pub id: Url,
-    pub actor: Url,
+    pub attributed_to: Url,
     #[serde(rename = "type")]
     pub type_field: QuestionType,
     #[serde(flatten)]
     pub options: ChoiceType,
+
+    #[serde(deserialize_with = "deserialize_time")]
+    #[serde(serialize_with = "serialize_time")]
+    pub published: i64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<OptionalArray<LinkSimpleOrExpanded>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<OptionalArray<LinkSimpleOrExpanded>>,
+
+    /// html
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+
     /// indicates that a poll can only be voted on by local users
     #[serde(skip_serializing_if = "Option::is_none")]
     pub local_only: Option<bool>,
@@ -36,12 +58,20 @@ pub struct Question {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub versia_url: Option<Url>,
+    #[serde(flatten)]
+    pub extensions: Extensions,
 }
 
 impl Question {
     pub fn get_context() -> Context {
         Note::get_context()
     }
+    pub fn get_tags(&self) -> Option<Vec<String>> {
+        get_hashtags(&self.tag)
+    }
+    pub fn is_public(&self) -> bool {
+        addresses_public(&self.to)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -63,3 +93,97 @@ pub struct QuestionOption {
     #[serde(rename = "type")]
     pub type_field: QuestionOptionType,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::context::ContextWrap;
+
+    use super::Question;
+
+    #[test]
+    fn test_deserialize_question() -> Result<(), String> {
+        // a poll in the shape mastodon sends them
+        let test_question = r##"
+{
+	"@context": [
+		"https://www.w3.org/ns/activitystreams",
+		{
+			"ostatus": "http://ostatus.org#",
+			"atomUri": "ostatus:atomUri",
+			"inReplyToAtomUri": "ostatus:inReplyToAtomUri",
+			"conversation": "ostatus:conversation",
+			"sensitive": "as:sensitive",
+			"toot": "http://joinmastodon.org/ns#",
+			"votersCount": "toot:votersCount",
+			"Hashtag": "as:Hashtag"
+		}
+	],
+	"id": "https://mastodon.social/users/Mastodon/statuses/113001122334455667",
+	"type": "Question",
+	"summary": null,
+	"inReplyTo": null,
+	"published": "2024-08-20T12:00:00Z",
+	"url": "https://mastodon.social/@Mastodon/113001122334455667",
+	"attributedTo": "https://mastodon.social/users/Mastodon",
+	"to": [
+		"https://www.w3.org/ns/activitystreams#Public"
+	],
+	"cc": [
+		"https://mastodon.social/users/Mastodon/followers"
+	],
+	"sensitive": false,
+	"content": "<p>which do you prefer? <a href=\"https://mastodon.social/tags/rust\" class=\"mention hashtag\" rel=\"tag\">#<span>rust</span></a></p>",
+	"attachment": [],
+	"tag": [
+		{
+			"type": "Hashtag",
+			"href": "https://mastodon.social/tags/rust",
+			"name": "#rust"
+		}
+	],
+	"endTime": "2024-08-21T12:00:00Z",
+	"votersCount": 2,
+	"oneOf": [
+		{
+			"type": "Note",
+			"name": "tabs",
+			"replies": {
+				"type": "Collection",
+				"totalItems": 1
+			}
+		},
+		{
+			"type": "Note",
+			"name": "spaces",
+			"replies": {
+				"type": "Collection",
+				"totalItems": 1
+			}
+		}
+	]
+}
+        "##;
+        let deserialized: Result<ContextWrap<Question>, serde_json::Error> =
+            serde_json::from_str(test_question);
+        let deserialized = match deserialized {
+            Ok(x) => x.item,
+            Err(x) => return Err(format!("question deserialize failed with response: {}", x)),
+        };
+        if !deserialized.is_public() {
+            return Err("question should be public".to_string());
+        }
+        // the choices are modeled, so only the unmodeled properties are extensions
+        if deserialized.extensions.voters_count().ne(&Some(2))
+            || deserialized.extensions.0.contains_key("oneOf")
+        {
+            return Err(format!(
+                "unexpected extensions {:?}",
+                deserialized.extensions
+            ));
+        }
+        match deserialized.get_tags() {
+            Some(tags) if tags.eq(&vec!["#rust".to_string()]) => Ok(()),
+            x => Err(format!("unexpected tags {:?}", x)),
+        }
+    }
+}
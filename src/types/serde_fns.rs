@@ -6,8 +6,8 @@ pub fn deserialize_time<'de, D>(deserializer: D) -> Result<i64, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let input = <&str>::deserialize(deserializer)?;
-    match DateTime::parse_from_rfc3339(input) {
+    let input = String::deserialize(deserializer)?;
+    match DateTime::parse_from_rfc3339(&input) {
         Ok(ok) => Ok(ok.timestamp_millis()),
         Err(err) => Err(D::Error::custom(err)),
     }
@@ -23,6 +23,42 @@ where
     s.serialize_str(&time.to_rfc3339_opts(SecondsFormat::Millis, true))
 }
 
+/// leaves out the fraction of whole seconds like mastodon does, so that
+/// the notes it sends serialize back to what was received
+pub fn serialize_time_auto<S>(x: &i64, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let Some(time) = DateTime::from_timestamp_millis(*x) else {
+        return Err(S::Error::custom("timestamp out of range"));
+    };
+    s.serialize_str(&time.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+}
+
+/// for optional times, the field also needs `#[serde(default)]`
+pub fn deserialize_optional_time<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(input) => match DateTime::parse_from_rfc3339(&input) {
+            Ok(ok) => Ok(Some(ok.timestamp_millis())),
+            Err(err) => Err(D::Error::custom(err)),
+        },
+        None => Ok(None),
+    }
+}
+
+pub fn serialize_optional_time<S>(x: &Option<i64>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match x {
+        Some(x) => serialize_time(x, s),
+        None => s.serialize_none(),
+    }
+}
+
 pub fn default_true() -> bool {
     true
 }
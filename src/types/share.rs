@@ -2,10 +2,11 @@ use super::serde_fns::{deserialize_time, serialize_time};
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use super::context::Context;
+use super::context::{Context, ContextWrap};
 use super::core_types::OptionalArray;
+use super::extensions::Extensions;
 use super::link::LinkSimpleOrExpanded;
-use super::{note::Note, question::Question};
+use super::{create::Create, note::Note, question::Question, update::Update};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ShareType {
@@ -13,6 +14,7 @@ pub enum ShareType {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum Shareable {
     Note(Note),
     Question(Question),
@@ -40,11 +42,44 @@ pub struct Share {
     /// booster's follower's collection
     pub cc: Option<OptionalArray<LinkSimpleOrExpanded>>,
 
-    pub object: Url,
+    /// when a post is only boosted by one of the tags it matched,
+    /// the others are listed here
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<OptionalArray<LinkSimpleOrExpanded>>,
+
+    pub object: SharedObject,
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// what an announce boosts. groups announce the activity that created
+/// or edited the post rather than the post itself (FEP-1b12)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum SharedObject {
+    Id(Url),
+    Activity(Box<Create>),
+    Update(Box<Update>),
+}
+
+impl SharedObject {
+    pub fn id(&self) -> &Url {
+        match self {
+            SharedObject::Id(id) => id,
+            SharedObject::Activity(create) => &create.id,
+            SharedObject::Update(update) => &update.id,
+        }
+    }
 }
 
 impl Share {
     pub fn get_context() -> Context {
         Context::Single("https://www.w3.org/ns/activitystreams".to_owned())
     }
+    pub fn wrap_context(self) -> ContextWrap<Self> {
+        ContextWrap {
+            context: Share::get_context(),
+            item: self,
+        }
+    }
 }
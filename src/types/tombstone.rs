@@ -1 +1,22 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum TombstoneType {
+    Tombstone,
+}
+
+/// A Tombstone represents a content object that has been deleted.
+/// It can be used in Collections to signify that there used to be
+/// an object at this position, but it has been deleted.
+///
+/// mastodon sends these as the object of deletes for posts
+///
+/// https://www.w3.org/TR/activitystreams-vocabulary/#dfn-tombstone
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Tombstone {
+    #[serde(rename = "type")]
+    pub type_field: TombstoneType,
+    pub id: Url,
+}
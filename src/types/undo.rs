@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{
+    context::{Context, ContextWrap},
+    follow_and_response::Follow,
+    share::Share,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum UndoType {
+    Undo,
+}
+
+/// Indicates that the actor is undoing the object. In most cases,
+/// the object will be an Activity describing some previously
+/// performed action (for instance, a person may have previously
+/// "liked" an article but, for whatever reason, might choose to
+/// undo that like at some later point in time).
+///
+/// https://www.w3.org/TR/activitystreams-vocabulary/#dfn-undo
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Undo {
+    #[serde(rename = "type")]
+    pub type_field: UndoType,
+    pub id: Url,
+    pub actor: Url,
+    pub object: Undoable,
+}
+
+impl Undo {
+    pub fn wrap_context(self) -> ContextWrap<Self> {
+        ContextWrap {
+            context: Context::Single("https://www.w3.org/ns/activitystreams".to_owned()),
+            item: self,
+        }
+    }
+}
+
+/// the activity being undone is always embedded
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum Undoable {
+    Announce(Share),
+    Follow(Follow),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::context::ContextWrap;
+
+    use super::{Undo, Undoable};
+
+    #[test]
+    fn test_deserialize_undo_follow() -> Result<(), String> {
+        // an unfollow in the shape mastodon sends them
+        let test_undo = r##"
+{
+	"@context": "https://www.w3.org/ns/activitystreams",
+	"id": "https://mastodon.social/users/Mastodon#follows/1234/undo",
+	"type": "Undo",
+	"actor": "https://mastodon.social/users/Mastodon",
+	"object": {
+		"id": "https://mastodon.social/d1f4ab3c-7e2a-4c36-9a0b-2f0b1d9c8e11",
+		"type": "Follow",
+		"actor": "https://mastodon.social/users/Mastodon",
+		"object": "https://testing1.ivytime.gay/tags/rust"
+	}
+}
+        "##;
+        let deserialized: Result<ContextWrap<Undo>, serde_json::Error> =
+            serde_json::from_str(test_undo);
+        match deserialized {
+            Ok(ContextWrap {
+                item:
+                    Undo {
+                        object: Undoable::Follow(_),
+                        ..
+                    },
+                ..
+            }) => Ok(()),
+            Ok(x) => Err(format!("undo deserialized to the wrong object: {:?}", x)),
+            Err(x) => Err(format!(
+                "undo activity deserialize failed with response: {}",
+                x
+            )),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{actors::Actor, postable::ApPostable};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum UpdateType {
+    Update,
+}
+
+/// Indicates that the actor has updated the object. Note, however,
+/// that this vocabulary does not define a mechanism for describing
+/// the actual set of modifications made to object.
+///
+/// in practice the object is always the full updated object, either
+/// an edited post or an actor updating their own profile
+///
+/// https://www.w3.org/TR/activitystreams-vocabulary/#dfn-update
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Update {
+    #[serde(rename = "type")]
+    pub type_field: UpdateType,
+    pub id: Url,
+    pub actor: Url,
+    pub object: Updatable,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum Updatable {
+    Postable(ApPostable),
+    Actor(Actor),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::context::ContextWrap;
+
+    use super::{Updatable, Update};
+
+    #[test]
+    fn test_deserialize_update_note() -> Result<(), String> {
+        // an edited status in the shape mastodon sends them
+        let test_update = r##"
+{
+	"@context": [
+		"https://www.w3.org/ns/activitystreams",
+		{
+			"atomUri": "ostatus:atomUri",
+			"sensitive": "as:sensitive",
+			"toot": "http://joinmastodon.org/ns#",
+			"Hashtag": "as:Hashtag"
+		}
+	],
+	"id": "https://mastodon.social/users/Mastodon/statuses/112769333503182077#updates/1720724400",
+	"type": "Update",
+	"actor": "https://mastodon.social/users/Mastodon",
+	"published": "2024-07-11T19:00:00Z",
+	"to": [
+		"https://www.w3.org/ns/activitystreams#Public"
+	],
+	"cc": [
+		"https://mastodon.social/users/Mastodon/followers"
+	],
+	"object": {
+		"id": "https://mastodon.social/users/Mastodon/statuses/112769333503182077",
+		"type": "Note",
+		"summary": null,
+		"inReplyTo": null,
+		"published": "2024-07-11T18:44:32Z",
+		"updated": "2024-07-11T19:00:00Z",
+		"url": "https://mastodon.social/@Mastodon/112769333503182077",
+		"attributedTo": "https://mastodon.social/users/Mastodon",
+		"to": [
+			"https://www.w3.org/ns/activitystreams#Public"
+		],
+		"cc": [
+			"https://mastodon.social/users/Mastodon/followers"
+		],
+		"sensitive": false,
+		"content": "<p>We’re hiring again! <a href=\"https://mastodon.social/tags/FediHire\" class=\"mention hashtag\" rel=\"tag\">#<span>FediHire</span></a></p>",
+		"attachment": [],
+		"tag": [
+			{
+				"type": "Hashtag",
+				"href": "https://mastodon.social/tags/FediHire",
+				"name": "#FediHire"
+			}
+		]
+	}
+}
+        "##;
+        let deserialized: Result<ContextWrap<Update>, serde_json::Error> =
+            serde_json::from_str(test_update);
+        match deserialized {
+            Ok(ContextWrap {
+                item:
+                    Update {
+                        object: Updatable::Postable(_),
+                        ..
+                    },
+                ..
+            }) => Ok(()),
+            Ok(x) => Err(format!("update deserialized to the wrong object: {:?}", x)),
+            Err(x) => Err(format!(
+                "update activity deserialize failed with response: {}",
+                x
+            )),
+        }
+    }
+
+    #[test]
+    fn test_deserialize_update_actor() -> Result<(), String> {
+        // a profile edit in the shape mastodon sends them
+        let test_update = r##"
+{
+	"@context": [
+		"https://www.w3.org/ns/activitystreams",
+		"https://w3id.org/security/v1"
+	],
+	"id": "https://mastodon.social/users/Mastodon#updates/1720724400",
+	"type": "Update",
+	"actor": "https://mastodon.social/users/Mastodon",
+	"to": [
+		"https://www.w3.org/ns/activitystreams#Public"
+	],
+	"object": {
+		"id": "https://mastodon.social/users/Mastodon",
+		"type": "Person",
+		"following": "https://mastodon.social/users/Mastodon/following",
+		"followers": "https://mastodon.social/users/Mastodon/followers",
+		"inbox": "https://mastodon.social/users/Mastodon/inbox",
+		"outbox": "https://mastodon.social/users/Mastodon/outbox",
+		"preferredUsername": "Mastodon",
+		"name": "Mastodon",
+		"summary": "<p>Free, open-source decentralized social media platform.</p>",
+		"url": "https://mastodon.social/@Mastodon",
+		"manuallyApprovesFollowers": false,
+		"discoverable": true,
+		"published": "2016-11-23T00:00:00Z",
+		"publicKey": {
+			"id": "https://mastodon.social/users/Mastodon#main-key",
+			"owner": "https://mastodon.social/users/Mastodon",
+			"publicKeyPem": "-----BEGIN PUBLIC KEY-----\nMIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEAtpNfuGPl/WTnSq3dTurF\nMRelAIdvGVkO/VKYZJvIleYA27/YTnpmlY2g+0Xxe1Xv/ZSMd9dl8Xad9kvcJPjH\nvJH7VdHp4cODWEWHm2DR3rEsfqsaaqgTvHn+J8VvQ2aQmMpDvY0/G+kScKnmlLZv\nmzYAWpFPPPZVnfOAjsZ7u/D3ScUqz9+Y3Uu8yNTNhEJqTi0TmDrXnfMOjWfASpmO\nFCeMGzPgfrpbTJmLMLD+tNDXtWD3d3LWUnNuDOHW7Dam3c0yhK1tVo6lUaIYF0YV\nfkFCJOF5qGQbxNi4eMu2XXqVAjBJ9e+k1m2gVcT4NfT8vTBAYXmMCrjA7DwRqVHF\nXwIDAQAB\n-----END PUBLIC KEY-----\n"
+		},
+		"endpoints": {
+			"sharedInbox": "https://mastodon.social/inbox"
+		}
+	}
+}
+        "##;
+        let deserialized: Result<ContextWrap<Update>, serde_json::Error> =
+            serde_json::from_str(test_update);
+        match deserialized {
+            Ok(ContextWrap {
+                item:
+                    Update {
+                        object: Updatable::Actor(_),
+                        ..
+                    },
+                ..
+            }) => Ok(()),
+            Ok(x) => Err(format!("update deserialized to the wrong object: {:?}", x)),
+            Err(x) => Err(format!(
+                "update activity deserialize failed with response: {}",
+                x
+            )),
+        }
+    }
+}